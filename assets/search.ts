@@ -0,0 +1,104 @@
+// Suggests VTubers while typing in the search box. The search page works
+// without this, it just saves a round trip to the results page.
+
+type Suggestion = {
+  id: number;
+  name: string;
+  img: string | null;
+  org_name: string;
+};
+
+const DEBOUNCE_MS = 200;
+
+const input = document.querySelector<HTMLInputElement>("#search");
+const list = document.querySelector<HTMLUListElement>("#search-suggestions");
+
+if (input && list && input.dataset.suggestions) {
+  const endpoint = input.dataset.suggestions;
+  const basePath = input.dataset.basePath ?? "";
+  let timer: number | undefined;
+  let controller: AbortController | undefined;
+
+  const hide = () => {
+    list.replaceChildren();
+    list.classList.add("hidden");
+  };
+
+  const render = (suggestions: Suggestion[]) => {
+    if (suggestions.length === 0) {
+      hide();
+      return;
+    }
+
+    list.replaceChildren(
+      ...suggestions.map((suggestion) => {
+        const item = document.createElement("li");
+        const link = document.createElement("a");
+        const name = document.createElement("span");
+        const org = document.createElement("span");
+
+        link.href = `${basePath}/vtuber/${suggestion.id}/details`;
+        link.className =
+          "flex justify-between px-4 py-2 hover:bg-su-bg-1 dark:hover:bg-su-dark-bg-1";
+
+        name.className = "font-bold";
+        name.textContent = suggestion.name;
+
+        org.className = "text-sm opacity-70";
+        org.textContent = suggestion.org_name;
+
+        link.append(name, org);
+        item.append(link);
+
+        return item;
+      })
+    );
+
+    list.classList.remove("hidden");
+  };
+
+  const suggest = async (query: string) => {
+    controller?.abort();
+    controller = new AbortController();
+
+    try {
+      const response = await fetch(
+        `${endpoint}?q=${encodeURIComponent(query)}`,
+        {
+          headers: { Accept: "application/json" },
+          signal: controller.signal,
+        }
+      );
+
+      if (response.ok) {
+        render(await response.json());
+      } else {
+        hide();
+      }
+    } catch (error) {
+      if (!(error instanceof DOMException && error.name === "AbortError")) {
+        hide();
+      }
+    }
+  };
+
+  input.addEventListener("input", () => {
+    window.clearTimeout(timer);
+
+    const query = input.value.trim();
+
+    if (query === "") {
+      controller?.abort();
+      hide();
+      return;
+    }
+
+    timer = window.setTimeout(() => suggest(query), DEBOUNCE_MS);
+  });
+
+  input.addEventListener("keydown", (event) => {
+    if (event.key === "Escape") {
+      hide();
+    }
+  });
+}
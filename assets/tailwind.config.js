@@ -4,6 +4,7 @@ module.exports = {
   content: [
     './src/oshismash_web/views/*.rs',
     './src/oshismash_web/views.rs',
+    './src/oshismash_web/components/*.rs',
     './public/**/*.html',
     './assets/**/*.ts'
   ],
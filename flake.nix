@@ -47,6 +47,10 @@
         program = "${self.packages.${system}.oshismash}/bin/oshismash";
       };
 
+      checks.${system}.migrations = import ./nix/checks/migrations.nix {
+        inherit pkgs;
+      };
+
       nixosModule = import ./nix/services/oshismash.nix;
       devShells."${system}".default = shell;
     };
@@ -0,0 +1,31 @@
+{ pkgs }:
+
+# Deploys every change in the plan against a throwaway Postgres, verifying each
+# one, then reverts them all and deploys them again. A change that doesn't
+# parse, or doesn't revert cleanly, fails `nix flake check`.
+pkgs.runCommand "oshismash-migrations" {
+  nativeBuildInputs = with pkgs; [ postgresql sqitchPg ];
+
+  SQITCH_FULLNAME = "oshismash";
+  SQITCH_EMAIL = "oshismash@localhost";
+} ''
+  export HOME=$TMPDIR
+  export PGDATA=$TMPDIR/pgdata
+  export PGHOST=$TMPDIR
+  export PGUSER=postgres
+
+  initdb --auth=trust --username=postgres > /dev/null
+  pg_ctl start --wait --log=$TMPDIR/postgres.log \
+    --options="-c listen_addresses= -k $PGHOST"
+  createdb oshismash
+
+  cp ${../../sqitch.conf} sqitch.conf
+  cp -r ${../../sql} sql
+
+  sqitch deploy --verify db:pg:oshismash
+  sqitch revert -y db:pg:oshismash
+  sqitch deploy --verify db:pg:oshismash
+
+  pg_ctl stop --wait
+  touch $out
+''
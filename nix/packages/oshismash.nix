@@ -22,6 +22,11 @@
       --config assets/tailwind.config.js \
       --output public/app.css \
       --minify
+
+    esbuild assets/search.ts \
+      --bundle \
+      --minify \
+      --outfile=public/search.js
   '';
 
   installPhase = old.installPhase + ''
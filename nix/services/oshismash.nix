@@ -13,7 +13,13 @@
         host = mkOption {
           default = "localhost";
           type = with types; str;
-          description = "Host oshismash use for its redirects";
+          description = "Host oshismash use for its links when neither publicUrl nor the request has one";
+        };
+
+        publicUrl = mkOption {
+          default = "";
+          type = with types; str;
+          description = "URL oshismash is reached at, like https://oshismash.com. It's taken from each request (and its X-Forwarded-* headers) without it";
         };
 
         port = mkOption {
@@ -22,6 +28,42 @@
           description = "Port number oshismash will run on";
         };
 
+        bind = mkOption {
+          default = "0.0.0.0";
+          type = with types; str;
+          description = "Address oshismash will listen on";
+        };
+
+        basePath = mkOption {
+          default = "/";
+          type = with types; str;
+          description = "Path oshismash is served under, like /oshismash";
+        };
+
+        shutdownGrace = mkOption {
+          default = 5;
+          type = with types; int;
+          description = "Seconds that new requests are still accepted after readiness fails, when the server is stopped";
+        };
+
+        shutdownTimeout = mkOption {
+          default = 30;
+          type = with types; int;
+          description = "Seconds that in-flight requests get to finish when the server is stopped";
+        };
+
+        mediaPath = mkOption {
+          default = "/var/lib/oshismash/media";
+          type = with types; str;
+          description = "Where the processed VTuber images are stored";
+        };
+
+        adminTokenFile = mkOption {
+          default = "";
+          type = with types; uniq str;
+          description = "Path to the admin token for the moderation pages. They're disabled without it";
+        };
+
         dbHost = mkOption {
           default = "localhost";
           type = with types; str;
@@ -57,6 +99,12 @@
           description = "Path to DB CA certificate";
         };
 
+        dbReplicaHost = mkOption {
+          default = "";
+          type = with types; uniq str;
+          description = "Host of a read replica for read-only queries. Reads go to the primary without it";
+        };
+
         dbPoolSize = mkOption {
           default = "1";
           type = with types; uniq str;
@@ -88,6 +136,11 @@
           {
             APP__PORT = "${cfg.port}";
             APP__HOST = "${cfg.host}";
+            APP__BIND = "${cfg.bind}";
+            APP__BASE_PATH = "${cfg.basePath}";
+            APP__MEDIA_PATH = "${cfg.mediaPath}";
+            APP__SHUTDOWN_GRACE = toString cfg.shutdownGrace;
+            APP__SHUTDOWN_TIMEOUT = toString cfg.shutdownTimeout;
             PG__DBNAME = "${cfg.dbName}";
             PG__HOST = "${cfg.dbHost}";
             PG__USER = "${cfg.dbUser}";
@@ -96,14 +149,29 @@
             PG__POOL_SIZE = "${cfg.dbPoolSize}";
           }
 
+          (mkIf ("${cfg.publicUrl}" != "") {
+            APP__PUBLIC_URL = "${cfg.publicUrl}";
+          })
+
+          (mkIf ("${cfg.adminTokenFile}" != "") {
+            APP__ADMIN_TOKEN_FILE = "${cfg.adminTokenFile}";
+          })
+
           (mkIf ("${cfg.dbCACertFile}" != "") {
             PG__CA_CERT = "${cfg.dbCACertFile}";
           })
+
+          (mkIf ("${cfg.dbReplicaHost}" != "") {
+            PG__REPLICA_HOST = "${cfg.dbReplicaHost}";
+          })
         ];
 
         serviceConfig = {
           Type = "simple";
           ExecStart = "${oshismash}/bin/oshismash";
+          StateDirectory = "oshismash";
+          # Gives the server time to drain before systemd kills it.
+          TimeoutStopSec = cfg.shutdownGrace + cfg.shutdownTimeout + 10;
         };
       };
 
@@ -9,6 +9,7 @@ pkgs.mkShell {
     rustfmt
 
     nodePackages.tailwindcss
+    esbuild
 
     # Database
     sqitchPg
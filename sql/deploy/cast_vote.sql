@@ -0,0 +1,62 @@
+-- Deploy oshismash:cast_vote to pg
+-- requires: retire
+
+BEGIN;
+  -- Everything a vote needs in one call, so it's atomic and only costs one
+  -- round trip: checks that the guest exists, and that they've voted for
+  -- every VTuber before this one in their deck, then records the vote and
+  -- gives back the next stack.
+  --
+  -- The guest is locked for the rest of the transaction, so two of their votes
+  -- can't both pass the ordering check before either is recorded.
+  CREATE FUNCTION app.cast_vote
+    ( guest_id    UUID
+    , vtuber_id   BIGINT
+    , action      app.ACTION
+    , locale      app.LOCALE DEFAULT 'en'
+    , deck_filter app.DECK_FILTER DEFAULT NULL
+    )
+    RETURNS JSONB
+    LANGUAGE PLPGSQL
+    AS $$
+      DECLARE
+        reachable BOOLEAN;
+      BEGIN
+        PERFORM 1
+          FROM app.guests
+          WHERE guests.guest_id = cast_vote.guest_id
+          FOR NO KEY UPDATE;
+
+        IF NOT FOUND THEN
+          RAISE SQLSTATE 'Z0003'
+            USING MESSAGE = 'No such guest';
+        END IF;
+
+        -- There's no row at all for a VTuber that doesn't exist, which leaves
+        -- `reachable` as `NULL`.
+        SELECT access.reachable
+          INTO reachable
+          FROM app.get_deck_access
+            ( cast_vote.vtuber_id
+            , cast_vote.guest_id
+            , cast_vote.deck_filter
+            ) AS access;
+
+        IF reachable IS NOT TRUE THEN
+          RAISE SQLSTATE 'Z0004'
+            USING MESSAGE = 'The VTubers before this one have to be voted for first';
+        END IF;
+
+        RETURN app.vote
+          ( cast_vote.guest_id
+          , cast_vote.vtuber_id
+          , cast_vote.action
+          , cast_vote.locale
+          , cast_vote.deck_filter
+          );
+      END;
+    $$;
+
+  COMMENT ON FUNCTION app.cast_vote IS
+    'Checks that the guest can vote for the VTuber, then votes on them.';
+COMMIT;
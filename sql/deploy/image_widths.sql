@@ -0,0 +1,20 @@
+-- Deploy oshismash:image_widths to pg
+-- requires: cast_vote
+
+BEGIN;
+  -- Keys end with the width of the source now (`{hash}-{width}`), so pages
+  -- know which variants really are narrower than the others. Older keys don't
+  -- have it, so they're cleared for `oshismash images sync` to process again.
+  ALTER TABLE app.vtubers DROP CONSTRAINT vtubers_img_check;
+
+  UPDATE app.vtubers
+    SET img = NULL
+    WHERE img IS NOT NULL;
+
+  ALTER TABLE app.vtubers
+    ADD CONSTRAINT vtubers_img_check
+      CHECK (img ~ '^[0-9a-f]{32}-[1-9][0-9]*$');
+
+  COMMENT ON COLUMN app.vtubers.img IS
+    'Key of the processed image, served from `/media/{img}/{width}.{avif,webp}`. It ends with the width of the source.';
+COMMIT;
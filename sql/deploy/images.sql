@@ -0,0 +1,19 @@
+-- Deploy oshismash:images to pg
+-- requires: slugs
+
+BEGIN;
+  -- `img` used to be a hotlinked URL. It's kept around as where the image is
+  -- fetched from, and `img` is now the key of the processed image in the media
+  -- storage. It's `NULL` until the image has been processed, so everything that
+  -- gives back `img` keeps working, and shows a placeholder until then.
+  ALTER TABLE app.vtubers RENAME COLUMN img TO img_source;
+
+  ALTER TABLE app.vtubers
+    ADD COLUMN img TEXT CHECK (img ~ '^[0-9a-f]{32}$');
+
+  COMMENT ON COLUMN app.vtubers.img_source IS
+    'URL that the image is fetched from by `oshismash images sync`.';
+
+  COMMENT ON COLUMN app.vtubers.img IS
+    'Key of the processed image, served from `/media/{img}/{width}.{avif,webp}`.';
+COMMIT;
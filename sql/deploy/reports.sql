@@ -0,0 +1,387 @@
+-- Deploy oshismash:reports to pg
+-- requires: suggestions
+
+BEGIN;
+  -- Hidden VTubers are taken out of the deck, the leaderboard, and search, but
+  -- they're kept in the chain (and their votes are kept) so they can be shown
+  -- again later.
+  ALTER TABLE app.vtubers ADD COLUMN hidden BOOLEAN NOT NULL DEFAULT FALSE;
+
+  CREATE TYPE app.REPORT_REASON AS ENUM ('takedown', 'wrong_image', 'wrong_info', 'other');
+
+  CREATE TYPE app.REPORT_STATUS AS ENUM ('open', 'resolved', 'dismissed');
+
+  -- Problems that guests found with a VTuber's card. `resolved` means that the
+  -- VTuber was hidden because of it.
+  CREATE TABLE app.reports (
+    report_id   BIGINT PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+    vtuber_id   BIGINT REFERENCES app.vtubers NOT NULL,
+    guest_id    UUID REFERENCES app.guests NOT NULL,
+
+    reason      app.REPORT_REASON NOT NULL,
+    details     TEXT NOT NULL CHECK (length(details) <= 1000),
+
+    status      app.REPORT_STATUS NOT NULL DEFAULT 'open',
+
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    reviewed_at TIMESTAMPTZ
+  );
+
+  -- A guest can only have one open report per VTuber.
+  CREATE UNIQUE INDEX reports_guest_vtuber_index
+    ON app.reports (guest_id, vtuber_id)
+    WHERE status = 'open';
+
+  CREATE INDEX reports_open_index ON app.reports (created_at) WHERE status = 'open';
+
+  -- The recursion still goes through hidden VTubers so that the ones after them
+  -- are still in the deck. Since everything that walks the deck (including
+  -- `get_vote_stack` through `deck_neighbors`) goes through this, hidden ones
+  -- are skipped over.
+  CREATE OR REPLACE VIEW app.deck AS
+    WITH RECURSIVE chain AS (
+      SELECT vtubers.vtuber_id, vtubers.next, 1 AS position
+        FROM app.vtubers
+        WHERE vtubers.prev IS NULL
+      UNION ALL
+      SELECT vtubers.vtuber_id, vtubers.next, chain.position + 1
+        FROM app.vtubers
+        JOIN chain
+          ON vtubers.vtuber_id = chain.next
+        WHERE chain.position < (SELECT count(*) FROM app.vtubers)
+    )
+    SELECT chain.vtuber_id, chain.position, vtubers.org_id, vtubers.group_id, vtubers.region
+      FROM chain
+      JOIN app.vtubers
+        ON vtubers.vtuber_id = chain.vtuber_id
+      WHERE NOT vtubers.hidden;
+
+  -- Gives back `NULL` instead of the report's ID if the guest already has an
+  -- open report for the VTuber.
+  CREATE FUNCTION app.report_vtuber
+    ( guest_id  UUID
+    , vtuber_id BIGINT
+    , reason    app.REPORT_REASON
+    , details   TEXT
+    )
+    RETURNS BIGINT
+    LANGUAGE SQL
+    AS $$
+      INSERT INTO app.reports (guest_id, vtuber_id, reason, details)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (guest_id, vtuber_id) WHERE status = 'open' DO NOTHING
+        RETURNING reports.report_id;
+    $$;
+
+  CREATE FUNCTION app.get_open_reports()
+    RETURNS JSONB
+    LANGUAGE SQL
+    STABLE
+    AS $$
+      SELECT coalesce(
+        jsonb_agg(
+          jsonb_build_object
+            ( 'report_id'
+            , reports.report_id
+            , 'vtuber_id'
+            , vtubers.vtuber_id
+            , 'vtuber_name'
+            , vtubers.name
+            , 'slug'
+            , vtubers.slug
+            , 'hidden'
+            , vtubers.hidden
+            , 'reason'
+            , reports.reason
+            , 'details'
+            , reports.details
+            , 'created_at'
+            , reports.created_at
+            )
+          ORDER BY reports.created_at
+        ),
+        '[]'::JSONB
+      )
+        FROM app.reports
+        JOIN app.vtubers
+          ON vtubers.vtuber_id = reports.vtuber_id
+        WHERE reports.status = 'open';
+    $$;
+
+  CREATE FUNCTION app.get_hidden_vtubers()
+    RETURNS JSONB
+    LANGUAGE SQL
+    STABLE
+    AS $$
+      SELECT coalesce(
+        jsonb_agg(
+          jsonb_build_object
+            ( 'vtuber_id'
+            , vtubers.vtuber_id
+            , 'name'
+            , vtubers.name
+            , 'slug'
+            , vtubers.slug
+            )
+          ORDER BY vtubers.name
+        ),
+        '[]'::JSONB
+      )
+        FROM app.vtubers
+        WHERE vtubers.hidden;
+    $$;
+
+  -- Hides the reported VTuber. The other open reports on them are resolved
+  -- along with it, since there's nothing left to do for those. Gives back
+  -- whether the report was open.
+  CREATE FUNCTION app.resolve_report(report_id BIGINT)
+    RETURNS BOOLEAN
+    LANGUAGE PLPGSQL
+    AS $$
+      DECLARE
+        reported_vtuber_id BIGINT;
+      BEGIN
+        SELECT reports.vtuber_id
+          INTO reported_vtuber_id
+          FROM app.reports
+          WHERE reports.report_id = resolve_report.report_id
+            AND reports.status = 'open'
+          FOR UPDATE;
+
+        IF NOT FOUND THEN
+          RETURN FALSE;
+        END IF;
+
+        UPDATE app.vtubers
+          SET hidden = TRUE
+          WHERE vtubers.vtuber_id = reported_vtuber_id;
+
+        UPDATE app.reports
+          SET status = 'resolved'
+            , reviewed_at = now()
+          WHERE reports.vtuber_id = reported_vtuber_id
+            AND reports.status = 'open';
+
+        RETURN TRUE;
+      END;
+    $$;
+
+  -- Gives back whether the report was open.
+  CREATE FUNCTION app.dismiss_report(report_id BIGINT)
+    RETURNS BOOLEAN
+    LANGUAGE SQL
+    AS $$
+      WITH dismissed AS (
+        UPDATE app.reports
+          SET status = 'dismissed'
+            , reviewed_at = now()
+          WHERE reports.report_id = dismiss_report.report_id
+            AND reports.status = 'open'
+          RETURNING reports.report_id
+      )
+      SELECT exists(SELECT FROM dismissed);
+    $$;
+
+  -- Gives back whether there's such a VTuber.
+  CREATE FUNCTION app.set_vtuber_hidden
+    ( vtuber_id BIGINT
+    , hidden    BOOLEAN
+    )
+    RETURNS BOOLEAN
+    LANGUAGE SQL
+    AS $$
+      WITH updated AS (
+        UPDATE app.vtubers
+          SET hidden = $2
+          WHERE vtubers.vtuber_id = $1
+          RETURNING vtubers.vtuber_id
+      )
+      SELECT exists(SELECT FROM updated);
+    $$;
+
+  -- Hidden VTubers aren't ranked.
+  CREATE OR REPLACE FUNCTION app.get_leaderboard(locale app.LOCALE DEFAULT 'en')
+    RETURNS JSONB
+    LANGUAGE SQL
+    STABLE
+    AS $$
+      WITH entries AS (
+        SELECT
+          vtubers.vtuber_id,
+          coalesce(translations.name, vtubers.name) AS name,
+          vtubers.img,
+          orgs.name AS org_name,
+          metrics.smashes,
+          metrics.passes,
+          metrics.skips
+          FROM app.vtubers AS vtubers
+          JOIN app.orgs AS orgs
+            ON vtubers.org_id = orgs.org_id
+          LEFT JOIN app.vtuber_translations AS translations
+            ON translations.vtuber_id = vtubers.vtuber_id
+           AND translations.locale = $1
+          CROSS JOIN app.get_metrics(vtubers.vtuber_id) AS metrics
+          WHERE NOT vtubers.hidden
+      )
+      SELECT coalesce(
+        jsonb_agg(
+          to_jsonb(entries)
+          ORDER BY
+            entries.smashes::NUMERIC / nullif(entries.smashes + entries.passes, 0) DESC NULLS LAST,
+            entries.smashes DESC,
+            entries.name
+        ),
+        '[]'::JSONB
+      )
+        FROM entries;
+    $$;
+
+  -- Hidden VTubers don't have a profile, and don't count towards their org's
+  -- size.
+  CREATE OR REPLACE FUNCTION app.get_vtuber_details
+    ( vtuber_id BIGINT
+    , locale    app.LOCALE DEFAULT 'en'
+    )
+    RETURNS JSONB
+    LANGUAGE SQL
+    STABLE
+    AS $$
+      WITH org_ranks AS (
+        SELECT
+          vtubers.vtuber_id,
+          rank() OVER (
+            ORDER BY
+              metrics.smashes::NUMERIC / nullif(metrics.smashes + metrics.passes, 0) DESC NULLS LAST,
+              metrics.smashes DESC
+          ) AS org_rank,
+          count(*) OVER () AS org_size
+          FROM app.vtubers
+          CROSS JOIN app.get_metrics(vtubers.vtuber_id) AS metrics
+          WHERE NOT vtubers.hidden
+            AND vtubers.org_id = (
+              SELECT target.org_id
+                FROM app.vtubers AS target
+                WHERE target.vtuber_id = $1
+            )
+      )
+      SELECT
+        json_build_object
+          ( 'id'
+          , vtubers.vtuber_id
+          , 'name'
+          , coalesce(translations.name, vtubers.name)
+          , 'description'
+          , coalesce(translations.description, vtubers.description)
+          , 'img'
+          , vtubers.img
+          , 'org_name'
+          , orgs.name
+          , 'group_name'
+          , groups.name
+          , 'region'
+          , vtubers.region
+          , 'youtube'
+          , vtubers.youtube
+          , 'twitch'
+          , vtubers.twitch
+          , 'twitter'
+          , vtubers.twitter
+          , 'stats'
+          , json_build_object
+              ( 'all_time'
+              , (SELECT to_jsonb(m) FROM app.get_metrics($1) AS m)
+              , 'last_week'
+              , (SELECT to_jsonb(m) FROM app.get_metrics_since($1, now() - INTERVAL '7 days') AS m)
+              , 'last_month'
+              , (SELECT to_jsonb(m) FROM app.get_metrics_since($1, now() - INTERVAL '30 days') AS m)
+              )
+          , 'org_rank'
+          , org_ranks.org_rank
+          , 'org_size'
+          , org_ranks.org_size
+          )
+        FROM app.vtubers AS vtubers
+        JOIN app.orgs AS orgs
+          ON vtubers.org_id = orgs.org_id
+        JOIN org_ranks
+          ON org_ranks.vtuber_id = vtubers.vtuber_id
+        LEFT JOIN app.groups AS groups
+          ON vtubers.group_id = groups.group_id
+        LEFT JOIN app.vtuber_translations AS translations
+          ON translations.vtuber_id = vtubers.vtuber_id
+         AND translations.locale = $2
+        WHERE vtubers.vtuber_id = $1;
+    $$;
+
+  -- Hidden VTubers can't be found.
+  CREATE OR REPLACE FUNCTION app.search_vtubers
+    ( query       TEXT
+    , locale      app.LOCALE DEFAULT 'en'
+    , max_results INTEGER DEFAULT 20
+    )
+    RETURNS JSONB
+    LANGUAGE SQL
+    STABLE
+    AS $$
+      WITH names AS (
+        SELECT vtubers.vtuber_id, vtubers.name
+          FROM app.vtubers
+        UNION ALL
+        SELECT vtuber_aliases.vtuber_id, vtuber_aliases.alias
+          FROM app.vtuber_aliases
+        UNION ALL
+        SELECT vtuber_translations.vtuber_id, vtuber_translations.name
+          FROM app.vtuber_translations
+          WHERE vtuber_translations.name IS NOT NULL
+      ), name_matches AS (
+        SELECT names.vtuber_id, max(word_similarity($1, names.name)) AS score
+          FROM names
+          WHERE $1 <% names.name
+             OR names.name ILIKE '%' || $1 || '%'
+          GROUP BY names.vtuber_id
+      ), description_matches AS (
+        SELECT
+          vtubers.vtuber_id,
+          ts_rank(to_tsvector('simple', vtubers.description), plainto_tsquery('simple', $1)) / 2 AS score
+          FROM app.vtubers
+          WHERE to_tsvector('simple', vtubers.description) @@ plainto_tsquery('simple', $1)
+      ), matches AS (
+        SELECT all_matches.vtuber_id, max(all_matches.score) AS score
+          FROM (
+            SELECT * FROM name_matches
+            UNION ALL
+            SELECT * FROM description_matches
+          ) AS all_matches
+          JOIN app.vtubers
+            ON vtubers.vtuber_id = all_matches.vtuber_id
+          WHERE NOT vtubers.hidden
+          GROUP BY all_matches.vtuber_id
+          ORDER BY score DESC, all_matches.vtuber_id
+          LIMIT $3
+      )
+      SELECT coalesce(
+        jsonb_agg(
+          json_build_object
+            ( 'id'
+            , vtubers.vtuber_id
+            , 'name'
+            , coalesce(translations.name, vtubers.name)
+            , 'img'
+            , vtubers.img
+            , 'org_name'
+            , orgs.name
+            )
+          ORDER BY matches.score DESC, vtubers.vtuber_id
+        ),
+        '[]'::JSONB
+      )
+        FROM matches
+        JOIN app.vtubers AS vtubers
+          ON vtubers.vtuber_id = matches.vtuber_id
+        JOIN app.orgs AS orgs
+          ON vtubers.org_id = orgs.org_id
+        LEFT JOIN app.vtuber_translations AS translations
+          ON translations.vtuber_id = vtubers.vtuber_id
+         AND translations.locale = $2;
+    $$;
+COMMIT;
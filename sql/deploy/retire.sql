@@ -0,0 +1,250 @@
+-- Deploy oshismash:retire to pg
+-- requires: reports
+
+BEGIN;
+  -- Retired VTubers are taken out of the chain entirely, so they have no
+  -- `prev`/`next`. Their votes stay, so they're still on the leaderboard and
+  -- still have a profile. `retired_after` is who they came after, to put them
+  -- back in the same spot if they're restored.
+  ALTER TABLE app.vtubers
+    ADD COLUMN retired_at    TIMESTAMPTZ,
+    ADD COLUMN retired_after BIGINT REFERENCES app.vtubers (vtuber_id),
+    ADD CONSTRAINT vtubers_retired_unlinked
+      CHECK (retired_at IS NULL OR (prev IS NULL AND next IS NULL));
+
+  -- A retired VTuber has no `prev` either, so they're not the start of the
+  -- chain.
+  CREATE OR REPLACE VIEW app.deck AS
+    WITH RECURSIVE chain AS (
+      SELECT vtubers.vtuber_id, vtubers.next, 1 AS position
+        FROM app.vtubers
+        WHERE vtubers.prev IS NULL
+          AND vtubers.retired_at IS NULL
+      UNION ALL
+      SELECT vtubers.vtuber_id, vtubers.next, chain.position + 1
+        FROM app.vtubers
+        JOIN chain
+          ON vtubers.vtuber_id = chain.next
+        WHERE chain.position < (SELECT count(*) FROM app.vtubers)
+    )
+    SELECT chain.vtuber_id, chain.position, vtubers.org_id, vtubers.group_id, vtubers.region
+      FROM chain
+      JOIN app.vtubers
+        ON vtubers.vtuber_id = chain.vtuber_id
+      WHERE NOT vtubers.hidden;
+
+  -- Splices an unlinked VTuber into the chain right after `after_vtuber_id`, or
+  -- at the very start if it's `NULL`. Changes to the chain are rare enough that
+  -- they're just done one at a time, so two of them can't link a VTuber to a
+  -- stale neighbor. Callers that change `app.vtubers` before this have to take
+  -- the same lock first, or two of them could deadlock.
+  CREATE FUNCTION app.link_vtuber
+    ( vtuber_id       BIGINT
+    , after_vtuber_id BIGINT
+    )
+    RETURNS VOID
+    LANGUAGE PLPGSQL
+    AS $$
+      DECLARE
+        next_vtuber_id BIGINT;
+      BEGIN
+        LOCK TABLE app.vtubers IN SHARE ROW EXCLUSIVE MODE;
+
+        IF after_vtuber_id IS NULL THEN
+          SELECT vtubers.vtuber_id
+            INTO next_vtuber_id
+            FROM app.vtubers
+            WHERE vtubers.prev IS NULL
+              AND vtubers.retired_at IS NULL
+              AND vtubers.vtuber_id <> link_vtuber.vtuber_id;
+        ELSE
+          SELECT vtubers.next
+            INTO next_vtuber_id
+            FROM app.vtubers
+            WHERE vtubers.vtuber_id = after_vtuber_id
+              AND vtubers.retired_at IS NULL;
+
+          IF NOT FOUND THEN
+            RAISE SQLSTATE 'Z0002'
+              USING MESSAGE = 'No VTuber to put this one after';
+          END IF;
+        END IF;
+
+        UPDATE app.vtubers
+          SET prev = after_vtuber_id
+            , next = next_vtuber_id
+          WHERE vtubers.vtuber_id = link_vtuber.vtuber_id;
+
+        UPDATE app.vtubers
+          SET next = link_vtuber.vtuber_id
+          WHERE vtubers.vtuber_id = after_vtuber_id;
+
+        UPDATE app.vtubers
+          SET prev = link_vtuber.vtuber_id
+          WHERE vtubers.vtuber_id = next_vtuber_id;
+      END;
+    $$;
+
+  -- Takes the VTuber out of the chain, and links their neighbors to each other.
+  -- Gives back whether they were in the chain.
+  CREATE FUNCTION app.retire_vtuber(vtuber_id BIGINT)
+    RETURNS BOOLEAN
+    LANGUAGE PLPGSQL
+    AS $$
+      DECLARE
+        prev_vtuber_id BIGINT;
+        next_vtuber_id BIGINT;
+      BEGIN
+        LOCK TABLE app.vtubers IN SHARE ROW EXCLUSIVE MODE;
+
+        SELECT vtubers.prev, vtubers.next
+          INTO prev_vtuber_id, next_vtuber_id
+          FROM app.vtubers
+          WHERE vtubers.vtuber_id = retire_vtuber.vtuber_id
+            AND vtubers.retired_at IS NULL;
+
+        IF NOT FOUND THEN
+          RETURN FALSE;
+        END IF;
+
+        UPDATE app.vtubers
+          SET prev = NULL
+            , next = NULL
+            , retired_at = now()
+            , retired_after = prev_vtuber_id
+          WHERE vtubers.vtuber_id = retire_vtuber.vtuber_id;
+
+        UPDATE app.vtubers
+          SET next = next_vtuber_id
+          WHERE vtubers.vtuber_id = prev_vtuber_id;
+
+        UPDATE app.vtubers
+          SET prev = prev_vtuber_id
+          WHERE vtubers.vtuber_id = next_vtuber_id;
+
+        RETURN TRUE;
+      END;
+    $$;
+
+  -- Puts a retired VTuber back into the chain, right after `after_vtuber_id` or
+  -- at the very start if it's `NULL`. Gives back whether they were retired.
+  CREATE FUNCTION app.restore_vtuber
+    ( vtuber_id       BIGINT
+    , after_vtuber_id BIGINT
+    )
+    RETURNS BOOLEAN
+    LANGUAGE PLPGSQL
+    AS $$
+      BEGIN
+        LOCK TABLE app.vtubers IN SHARE ROW EXCLUSIVE MODE;
+
+        UPDATE app.vtubers
+          SET retired_at = NULL
+            , retired_after = NULL
+          WHERE vtubers.vtuber_id = restore_vtuber.vtuber_id
+            AND vtubers.retired_at IS NOT NULL;
+
+        IF NOT FOUND THEN
+          RETURN FALSE;
+        END IF;
+
+        PERFORM app.link_vtuber(restore_vtuber.vtuber_id, after_vtuber_id);
+
+        RETURN TRUE;
+      END;
+    $$;
+
+  -- `retired_after` is left out if that VTuber isn't in the chain anymore
+  -- either, since they can't be put back after them.
+  CREATE FUNCTION app.get_retired_vtubers()
+    RETURNS JSONB
+    LANGUAGE SQL
+    STABLE
+    AS $$
+      SELECT coalesce(
+        jsonb_agg(
+          jsonb_build_object
+            ( 'vtuber_id'
+            , vtubers.vtuber_id
+            , 'name'
+            , vtubers.name
+            , 'slug'
+            , vtubers.slug
+            , 'retired_at'
+            , vtubers.retired_at
+            , 'retired_after'
+            , after.vtuber_id
+            )
+          ORDER BY vtubers.retired_at DESC
+        ),
+        '[]'::JSONB
+      )
+        FROM app.vtubers
+        LEFT JOIN app.vtubers AS after
+          ON after.vtuber_id = vtubers.retired_after
+         AND after.retired_at IS NULL
+        WHERE vtubers.retired_at IS NOT NULL;
+    $$;
+
+  -- Same as before, but the VTuber is linked with `link_vtuber`, so retired
+  -- VTubers aren't mistaken for the start of the chain.
+  CREATE OR REPLACE FUNCTION app.approve_suggestion
+    ( suggestion_id BIGINT
+    , name TEXT
+    , description TEXT
+    , org_id INTEGER
+    , group_id INTEGER
+    , region app.REGION
+    , img_source TEXT
+    , youtube TEXT
+    , twitch TEXT
+    , twitter TEXT
+    , after_vtuber_id BIGINT
+    )
+    RETURNS BIGINT
+    LANGUAGE PLPGSQL
+    AS $$
+      DECLARE
+        new_vtuber_id BIGINT;
+      BEGIN
+        PERFORM
+          FROM app.suggestions
+          WHERE suggestions.suggestion_id = approve_suggestion.suggestion_id
+            AND suggestions.status = 'pending'
+          FOR UPDATE;
+
+        IF NOT FOUND THEN
+          RETURN NULL;
+        END IF;
+
+        LOCK TABLE app.vtubers IN SHARE ROW EXCLUSIVE MODE;
+
+        INSERT INTO app.vtubers
+          ( name, description, org_id, group_id, region
+          , img_source, youtube, twitch, twitter
+          )
+          VALUES
+            ( approve_suggestion.name
+            , approve_suggestion.description
+            , approve_suggestion.org_id
+            , approve_suggestion.group_id
+            , approve_suggestion.region
+            , approve_suggestion.img_source
+            , approve_suggestion.youtube
+            , approve_suggestion.twitch
+            , approve_suggestion.twitter
+            )
+          RETURNING vtubers.vtuber_id INTO new_vtuber_id;
+
+        PERFORM app.link_vtuber(new_vtuber_id, after_vtuber_id);
+
+        UPDATE app.suggestions
+          SET status = 'approved'
+            , vtuber_id = new_vtuber_id
+            , reviewed_at = now()
+          WHERE suggestions.suggestion_id = approve_suggestion.suggestion_id;
+
+        RETURN new_vtuber_id;
+      END;
+    $$;
+COMMIT;
@@ -0,0 +1,97 @@
+-- Deploy oshismash:search to pg
+-- requires: vtuber_details
+
+BEGIN;
+  CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+  -- Other names that a VTuber goes by, like romanized names or nicknames.
+  CREATE TABLE app.vtuber_aliases (
+    vtuber_id BIGINT REFERENCES app.vtubers NOT NULL,
+    alias     TEXT NOT NULL,
+
+    PRIMARY KEY (vtuber_id, alias)
+  );
+
+  CREATE INDEX vtubers_name_trgm_index
+    ON app.vtubers USING GIN (name gin_trgm_ops);
+
+  CREATE INDEX vtubers_description_fts_index
+    ON app.vtubers USING GIN (to_tsvector('simple', description));
+
+  CREATE INDEX vtuber_aliases_alias_trgm_index
+    ON app.vtuber_aliases USING GIN (alias gin_trgm_ops);
+
+  CREATE INDEX vtuber_translations_name_trgm_index
+    ON app.vtuber_translations USING GIN (name gin_trgm_ops);
+
+  -- Finds VTubers whose name (in any locale) or alias is similar to the query,
+  -- or whose description has the words in it. Names are ranked higher than
+  -- descriptions.
+  CREATE FUNCTION app.search_vtubers
+    ( query       TEXT
+    , locale      app.LOCALE DEFAULT 'en'
+    , max_results INTEGER DEFAULT 20
+    )
+    RETURNS JSONB
+    LANGUAGE SQL
+    STABLE
+    AS $$
+      WITH names AS (
+        SELECT vtubers.vtuber_id, vtubers.name
+          FROM app.vtubers
+        UNION ALL
+        SELECT vtuber_aliases.vtuber_id, vtuber_aliases.alias
+          FROM app.vtuber_aliases
+        UNION ALL
+        SELECT vtuber_translations.vtuber_id, vtuber_translations.name
+          FROM app.vtuber_translations
+          WHERE vtuber_translations.name IS NOT NULL
+      ), name_matches AS (
+        SELECT names.vtuber_id, max(word_similarity($1, names.name)) AS score
+          FROM names
+          WHERE $1 <% names.name
+             OR names.name ILIKE '%' || $1 || '%'
+          GROUP BY names.vtuber_id
+      ), description_matches AS (
+        SELECT
+          vtubers.vtuber_id,
+          ts_rank(to_tsvector('simple', vtubers.description), plainto_tsquery('simple', $1)) / 2 AS score
+          FROM app.vtubers
+          WHERE to_tsvector('simple', vtubers.description) @@ plainto_tsquery('simple', $1)
+      ), matches AS (
+        SELECT vtuber_id, max(score) AS score
+          FROM (
+            SELECT * FROM name_matches
+            UNION ALL
+            SELECT * FROM description_matches
+          ) AS all_matches
+          GROUP BY vtuber_id
+          ORDER BY score DESC, vtuber_id
+          LIMIT $3
+      )
+      SELECT coalesce(
+        jsonb_agg(
+          json_build_object
+            ( 'id'
+            , vtubers.vtuber_id
+            , 'name'
+            , coalesce(translations.name, vtubers.name)
+            , 'img'
+            , vtubers.img
+            , 'org_name'
+            , orgs.name
+            )
+          ORDER BY matches.score DESC, vtubers.vtuber_id
+        ),
+        '[]'::JSONB
+      )
+        FROM matches
+        JOIN app.vtubers AS vtubers
+          ON vtubers.vtuber_id = matches.vtuber_id
+        JOIN app.orgs AS orgs
+          ON vtubers.org_id = orgs.org_id
+        LEFT JOIN app.vtuber_translations AS translations
+          ON translations.vtuber_id = vtubers.vtuber_id
+         AND translations.locale = $2;
+    $$;
+COMMIT;
@@ -0,0 +1,334 @@
+-- Deploy oshismash:shuffle to pg
+-- requires: deck_filters
+
+BEGIN;
+  -- Guests with a seed see the deck in their own shuffled order instead of the
+  -- `prev`/`next` chain. The same seed always gives the same order, so going
+  -- back and forth still works.
+  ALTER TABLE app.guests
+    ADD COLUMN deck_seed INTEGER;
+
+  -- Turns the shuffled order on or off for a guest. A guest that turns it on
+  -- again keeps their old order if they never turned it off.
+  CREATE FUNCTION app.set_deck_shuffle
+    ( guest_id UUID
+    , shuffled BOOLEAN
+    )
+    RETURNS VOID
+    LANGUAGE SQL
+    AS $$
+      UPDATE app.guests
+        SET deck_seed = CASE
+          WHEN $2 THEN coalesce(guests.deck_seed, floor(random() * 2147483647)::INTEGER)
+          ELSE NULL
+        END
+        WHERE guests.guest_id = $1;
+    $$;
+
+  -- The deck in the order that the guest sees it.
+  CREATE FUNCTION app.guest_deck(guest_id UUID)
+    RETURNS TABLE
+      ( vtuber_id     BIGINT
+      , deck_position INTEGER
+      , org_id        INTEGER
+      , group_id      INTEGER
+      , region        app.REGION
+      )
+    LANGUAGE SQL
+    STABLE
+    AS $$
+      SELECT
+        deck.vtuber_id,
+        CASE
+          WHEN guests.deck_seed IS NULL THEN deck.position
+          ELSE (
+            row_number() OVER (
+              ORDER BY md5(guests.deck_seed::TEXT || ':' || deck.vtuber_id::TEXT)
+            )
+          )::INTEGER
+        END,
+        deck.org_id,
+        deck.group_id,
+        deck.region
+        FROM app.deck
+        LEFT JOIN app.guests
+          ON guests.guest_id = $1;
+    $$;
+
+  -- These follow the guest's order now, so they need the guest.
+  DROP FUNCTION app.get_deck_access;
+  DROP FUNCTION app.deck_neighbors;
+  DROP FUNCTION app.filtered_deck;
+
+  CREATE FUNCTION app.filtered_deck
+    ( guest_id    UUID
+    , deck_filter app.DECK_FILTER
+    )
+    RETURNS TABLE (vtuber_id BIGINT, deck_position INTEGER)
+    LANGUAGE SQL
+    STABLE
+    AS $$
+      SELECT deck.vtuber_id, deck.deck_position
+        FROM app.guest_deck($1) AS deck
+        WHERE ($2.org_id IS NULL OR deck.org_id = $2.org_id)
+          AND ($2.group_id IS NULL OR deck.group_id = $2.group_id)
+          AND ($2.region IS NULL OR deck.region = $2.region);
+    $$;
+
+  COMMENT ON FUNCTION app.filtered_deck IS
+    'Gets the VTubers that match the filter, along with their position in the guest''s deck.';
+
+  -- The closest VTubers before and after the given one that match the filter,
+  -- in the guest's order. The given VTuber itself doesn't have to match.
+  CREATE FUNCTION app.deck_neighbors
+    ( vtuber_id   BIGINT
+    , guest_id    UUID
+    , deck_filter app.DECK_FILTER
+    )
+    RETURNS TABLE (prev BIGINT, next BIGINT)
+    LANGUAGE SQL
+    STABLE
+    AS $$
+      WITH filtered AS (
+        SELECT * FROM app.filtered_deck($2, $3)
+      ), target AS (
+        SELECT deck.deck_position
+          FROM app.guest_deck($2) AS deck
+          WHERE deck.vtuber_id = $1
+      )
+      SELECT
+        ( SELECT filtered.vtuber_id
+            FROM filtered, target
+            WHERE filtered.deck_position < target.deck_position
+            ORDER BY filtered.deck_position DESC
+            LIMIT 1
+        ),
+        ( SELECT filtered.vtuber_id
+            FROM filtered, target
+            WHERE filtered.deck_position > target.deck_position
+            ORDER BY filtered.deck_position ASC
+            LIMIT 1
+        );
+    $$;
+
+  -- Whether a guest is allowed to see a VTuber in the filtered deck. They can't
+  -- skip ahead of the first VTuber they haven't voted for yet, which is where
+  -- they should resume from. If they've voted for everyone in the deck, then
+  -- they resume from the start.
+  CREATE FUNCTION app.get_deck_access
+    ( vtuber_id   BIGINT
+    , guest_id    UUID
+    , deck_filter app.DECK_FILTER
+    )
+    RETURNS TABLE (in_deck BOOLEAN, reachable BOOLEAN, resume_at BIGINT)
+    LANGUAGE SQL
+    STABLE
+    AS $$
+      WITH filtered AS (
+        SELECT * FROM app.filtered_deck($2, $3)
+      ), frontier AS (
+        SELECT filtered.vtuber_id, filtered.deck_position
+          FROM filtered
+          WHERE NOT EXISTS (
+            SELECT 1
+              FROM app.guest_votes
+              WHERE guest_votes.guest_id = $2
+                AND guest_votes.vtuber_id = filtered.vtuber_id
+          )
+          ORDER BY filtered.deck_position
+          LIMIT 1
+      ), target AS (
+        SELECT filtered.deck_position
+          FROM filtered
+          WHERE filtered.vtuber_id = $1
+      )
+      SELECT
+        EXISTS (SELECT 1 FROM target),
+        EXISTS (
+          SELECT 1
+            FROM target
+            WHERE NOT EXISTS (SELECT 1 FROM frontier)
+               OR target.deck_position <= (SELECT frontier.deck_position FROM frontier)
+        ),
+        coalesce
+          ( (SELECT frontier.vtuber_id FROM frontier)
+          , (SELECT filtered.vtuber_id FROM filtered ORDER BY filtered.deck_position LIMIT 1)
+          );
+    $$;
+
+  CREATE OR REPLACE FUNCTION app.get_vote_stack
+    ( prev_vtuber_id BIGINT
+    , current_vtuber_id BIGINT
+    , guest_id UUID
+    , locale app.LOCALE DEFAULT 'en'
+    , deck_filter app.DECK_FILTER DEFAULT NULL
+    )
+    RETURNS JSONB
+    LANGUAGE PLPGSQL
+    AS $$
+      DECLARE
+        data JSONB;
+      BEGIN
+        IF prev_vtuber_id IS NULL AND current_vtuber_id IS NULL THEN
+          RAISE SQLSTATE 'Z0001'
+            USING MESSAGE = 'Arguments should not be both NULL';
+        END IF;
+
+        WITH current_vtuber_cte AS (
+          -- I'm using jsonb_agg to bypass the annoyance of when `current_vtuber`
+          -- is `NULL`. If it is, using `current_vtuber` in the `FROM` clause
+          -- at the next query is going to cause the entire result to be `NULL`.
+          -- This is not what I want since I have to return the VTuber that was
+          -- previously voted.
+          SELECT jsonb_agg(
+              json_build_object
+                ( 'id'
+                , vtubers.vtuber_id
+                , 'description'
+                , coalesce(translations.description, vtubers.description)
+                , 'name'
+                , coalesce(translations.name, vtubers.name)
+                , 'prev'
+                , neighbors.prev
+                , 'next'
+                , neighbors.next
+                , 'img'
+                , vtubers.img
+                , 'org_name'
+                , orgs.name
+                , 'group_name'
+                , groups.name
+                , 'region'
+                , vtubers.region
+                )
+            )
+            FROM app.vtubers AS vtubers
+            JOIN app.orgs AS orgs
+              ON vtubers.org_id = orgs.org_id
+            LEFT JOIN app.groups AS groups
+              ON vtubers.group_id = groups.group_id
+            LEFT JOIN app.vtuber_translations AS translations
+              ON translations.vtuber_id = vtubers.vtuber_id
+             AND translations.locale = get_vote_stack.locale
+            CROSS JOIN app.deck_neighbors(vtubers.vtuber_id, get_vote_stack.guest_id, get_vote_stack.deck_filter) AS neighbors
+            WHERE vtubers.vtuber_id = get_vote_stack.current_vtuber_id
+        ), prev_results_cte AS (
+          -- Grabs the results of the previous VTuber relative to the current.
+          -- This includes the smash/pass metrics.
+          SELECT
+            jsonb_agg(
+              json_build_object
+                ( 'vtuber_id'
+                , vtubers.vtuber_id
+                , 'name'
+                , coalesce(translations.name, vtubers.name)
+                , 'img'
+                , vtubers.img
+                , 'smashes'
+                , metrics.smashes
+                , 'passes'
+                , metrics.passes
+                )
+            )
+            FROM app.vtubers AS vtubers
+            CROSS JOIN app.get_metrics(prev_vtuber_id) AS metrics
+            LEFT JOIN app.vtuber_translations AS translations
+              ON translations.vtuber_id = vtubers.vtuber_id
+             AND translations.locale = get_vote_stack.locale
+            WHERE vtubers.vtuber_id = get_vote_stack.prev_vtuber_id
+        ), voted_vtubers_cte AS (
+          SELECT jsonb_agg(vtuber_id)
+            FROM app.guest_votes
+            WHERE guest_votes.guest_id = $3
+        ), vote_for_current_cte AS (
+          SELECT jsonb_agg(action)
+            FROM app.guest_votes
+            WHERE guest_votes.guest_id = $3
+              AND guest_votes.vtuber_id = $2
+        )
+        SELECT
+          json_build_object
+            ( 'current'
+            , current_vtuber_cte.jsonb_agg -> 0
+            , 'results'
+            , prev_results_cte.jsonb_agg -> 0
+            , 'voted'
+            , coalesce(voted_vtubers_cte.jsonb_agg, '[]'::JSONB)
+            , 'vote_for_current'
+            , vote_for_current_cte.jsonb_agg -> 0
+            )
+          INTO data
+          FROM current_vtuber_cte
+             , prev_results_cte
+             , voted_vtubers_cte
+             , vote_for_current_cte;
+        RETURN data;
+      END;
+    $$;
+
+  COMMENT ON FUNCTION app.get_vote_stack IS
+    'Gets the current details of the VTuber, and the information + vote results of the previous VTuber.';
+
+  CREATE OR REPLACE FUNCTION app.get_vote_stack_from_previous
+    ( prev_vtuber_id BIGINT
+    , guest_id       UUID
+    , locale         app.LOCALE DEFAULT 'en'
+    , deck_filter    app.DECK_FILTER DEFAULT NULL
+    )
+    RETURNS JSONB
+    LANGUAGE SQL
+    AS $$
+      SELECT app.get_vote_stack($1, neighbors.next, $2, $3, $4)
+        FROM app.deck_neighbors($1, $2, $4) AS neighbors;
+    $$;
+
+  CREATE OR REPLACE FUNCTION app.get_vote_stack_from_current
+    ( current_vtuber_id BIGINT
+    , guest_id          UUID
+    , locale            app.LOCALE DEFAULT 'en'
+    , deck_filter       app.DECK_FILTER DEFAULT NULL
+    )
+    RETURNS JSONB
+    LANGUAGE SQL
+    AS $$
+      WITH current_cte AS (
+        SELECT vtubers.vtuber_id
+          FROM app.vtubers
+          WHERE vtubers.vtuber_id = $1
+      )
+      SELECT app.get_vote_stack(neighbors.prev, current_cte.vtuber_id, $2, $3, $4)
+        FROM current_cte
+        CROSS JOIN app.deck_neighbors(current_cte.vtuber_id, $2, $4) AS neighbors;
+    $$;
+
+  CREATE OR REPLACE FUNCTION app.vote
+    ( guest_id    UUID
+    , vtuber_id   BIGINT
+    , action      app.ACTION
+    , locale      app.LOCALE DEFAULT 'en'
+    , deck_filter app.DECK_FILTER DEFAULT NULL
+    )
+    RETURNS JSONB
+    LANGUAGE SQL
+    AS $$
+      -- Perform an upsert. Users are allowed to change their votes in the event
+      -- of, er, a phenomenon that clears their mind after doing a specific
+      -- action. Maybe they will be filled with regret, or something, who knows.
+      --
+      -- If there's a conflict, then this updates the vote to the new one.
+      WITH prev_vtuber_cte AS (
+        INSERT
+          INTO app.guest_votes (guest_id, vtuber_id, action)
+          VALUES ($1, $2, $3)
+          ON CONFLICT (vtuber_id, guest_id)
+              DO UPDATE SET action = $3
+          RETURNING vtuber_id AS prev_vtuber_id
+      )
+      SELECT app.get_vote_stack(prev_vtuber_cte.prev_vtuber_id, neighbors.next, $1, $4, $5)
+        FROM prev_vtuber_cte
+        CROSS JOIN app.deck_neighbors(prev_vtuber_cte.prev_vtuber_id, $1, $5) AS neighbors;
+    $$;
+
+  COMMENT ON FUNCTION app.vote IS
+    'Votes on a VTuber depending on what the action is.';
+COMMIT;
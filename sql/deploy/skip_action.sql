@@ -0,0 +1,8 @@
+-- Deploy oshismash:skip_action to pg
+-- requires: shuffle
+
+-- The new value can't be used in the same transaction that adds it, so this is
+-- separate from the functions that use it.
+BEGIN;
+  ALTER TYPE app.ACTION ADD VALUE 'skipped';
+COMMIT;
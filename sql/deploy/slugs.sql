@@ -0,0 +1,197 @@
+-- Deploy oshismash:slugs to pg
+-- requires: search
+
+BEGIN;
+  -- Turns a name into something URL friendly, e.g `Nyatasha Nyanners` becomes
+  -- `nyatasha-nyanners`. It's `NULL` if nothing is left, like for names that
+  -- are only in kana. All-digit slugs are prefixed so they can't be mistaken
+  -- for IDs.
+  CREATE FUNCTION app.slugify(name TEXT)
+    RETURNS TEXT
+    LANGUAGE SQL
+    IMMUTABLE
+    AS $$
+      SELECT
+        CASE
+          WHEN slug ~ '^[0-9]+$' THEN 'vtuber-' || slug
+          ELSE slug
+        END
+        FROM nullif(
+          trim(BOTH '-' FROM regexp_replace(lower(name), '[^a-z0-9]+', '-', 'g')),
+          ''
+        ) AS slug;
+    $$;
+
+  ALTER TABLE app.vtubers ADD COLUMN slug TEXT;
+
+  UPDATE app.vtubers
+    SET slug = coalesce(app.slugify(name), 'vtuber-' || vtuber_id);
+
+  -- The oldest VTuber keeps the plain slug if the names are the same.
+  UPDATE app.vtubers AS vtubers
+    SET slug = vtubers.slug || '-' || vtubers.vtuber_id
+    WHERE EXISTS (
+      SELECT
+        FROM app.vtubers AS others
+        WHERE others.slug = vtubers.slug
+          AND others.vtuber_id < vtubers.vtuber_id
+    );
+
+  ALTER TABLE app.vtubers
+    ALTER COLUMN slug SET NOT NULL,
+    ADD CONSTRAINT vtubers_slug_key UNIQUE (slug),
+    ADD CONSTRAINT vtubers_slug_format
+      CHECK (slug ~ '^[a-z0-9]+(-[a-z0-9]+)*$' AND slug !~ '^[0-9]+$');
+
+  -- Slugs don't have to be given when adding VTubers. They're not regenerated
+  -- when the name changes though, since that would break links.
+  CREATE FUNCTION app.set_vtuber_slug()
+    RETURNS TRIGGER
+    LANGUAGE PLPGSQL
+    AS $$
+      BEGIN
+        IF NEW.slug IS NULL THEN
+          NEW.slug := coalesce(app.slugify(NEW.name), 'vtuber-' || NEW.vtuber_id);
+
+          IF EXISTS (SELECT FROM app.vtubers WHERE vtubers.slug = NEW.slug) THEN
+            NEW.slug := NEW.slug || '-' || NEW.vtuber_id;
+          END IF;
+        END IF;
+
+        RETURN NEW;
+      END;
+    $$;
+
+  CREATE TRIGGER vtubers_slug
+    BEFORE INSERT ON app.vtubers
+    FOR EACH ROW
+    EXECUTE FUNCTION app.set_vtuber_slug();
+
+  -- Same as before, but with the slugs to link to.
+  CREATE OR REPLACE FUNCTION app.get_vote_stack
+    ( prev_vtuber_id BIGINT
+    , current_vtuber_id BIGINT
+    , guest_id UUID
+    , locale app.LOCALE DEFAULT 'en'
+    , deck_filter app.DECK_FILTER DEFAULT NULL
+    )
+    RETURNS JSONB
+    LANGUAGE PLPGSQL
+    AS $$
+      DECLARE
+        data JSONB;
+      BEGIN
+        IF prev_vtuber_id IS NULL AND current_vtuber_id IS NULL THEN
+          RAISE SQLSTATE 'Z0001'
+            USING MESSAGE = 'Arguments should not be both NULL';
+        END IF;
+
+        WITH current_vtuber_cte AS (
+          -- I'm using jsonb_agg to bypass the annoyance of when `current_vtuber`
+          -- is `NULL`. If it is, using `current_vtuber` in the `FROM` clause
+          -- at the next query is going to cause the entire result to be `NULL`.
+          -- This is not what I want since I have to return the VTuber that was
+          -- previously voted.
+          SELECT jsonb_agg(
+              json_build_object
+                ( 'id'
+                , vtubers.vtuber_id
+                , 'description'
+                , coalesce(translations.description, vtubers.description)
+                , 'name'
+                , coalesce(translations.name, vtubers.name)
+                , 'slug'
+                , vtubers.slug
+                , 'prev'
+                , neighbors.prev
+                , 'prev_slug'
+                , prev_vtubers.slug
+                , 'next'
+                , neighbors.next
+                , 'next_slug'
+                , next_vtubers.slug
+                , 'img'
+                , vtubers.img
+                , 'org_name'
+                , orgs.name
+                , 'group_name'
+                , groups.name
+                , 'region'
+                , vtubers.region
+                )
+            )
+            FROM app.vtubers AS vtubers
+            JOIN app.orgs AS orgs
+              ON vtubers.org_id = orgs.org_id
+            LEFT JOIN app.groups AS groups
+              ON vtubers.group_id = groups.group_id
+            LEFT JOIN app.vtuber_translations AS translations
+              ON translations.vtuber_id = vtubers.vtuber_id
+             AND translations.locale = get_vote_stack.locale
+            CROSS JOIN app.deck_neighbors(vtubers.vtuber_id, get_vote_stack.guest_id, get_vote_stack.deck_filter) AS neighbors
+            LEFT JOIN app.vtubers AS prev_vtubers
+              ON prev_vtubers.vtuber_id = neighbors.prev
+            LEFT JOIN app.vtubers AS next_vtubers
+              ON next_vtubers.vtuber_id = neighbors.next
+            WHERE vtubers.vtuber_id = get_vote_stack.current_vtuber_id
+        ), prev_results_cte AS (
+          -- Grabs the results of the previous VTuber relative to the current.
+          -- This includes the smash/pass metrics.
+          SELECT
+            jsonb_agg(
+              json_build_object
+                ( 'vtuber_id'
+                , vtubers.vtuber_id
+                , 'slug'
+                , vtubers.slug
+                , 'name'
+                , coalesce(translations.name, vtubers.name)
+                , 'img'
+                , vtubers.img
+                , 'smashes'
+                , metrics.smashes
+                , 'passes'
+                , metrics.passes
+                , 'skips'
+                , metrics.skips
+                )
+            )
+            FROM app.vtubers AS vtubers
+            CROSS JOIN app.get_metrics(prev_vtuber_id) AS metrics
+            LEFT JOIN app.vtuber_translations AS translations
+              ON translations.vtuber_id = vtubers.vtuber_id
+             AND translations.locale = get_vote_stack.locale
+            WHERE vtubers.vtuber_id = get_vote_stack.prev_vtuber_id
+        ), voted_vtubers_cte AS (
+          SELECT jsonb_agg(vtuber_id)
+            FROM app.guest_votes
+            WHERE guest_votes.guest_id = $3
+        ), vote_for_current_cte AS (
+          SELECT jsonb_agg(action)
+            FROM app.guest_votes
+            WHERE guest_votes.guest_id = $3
+              AND guest_votes.vtuber_id = $2
+        )
+        SELECT
+          json_build_object
+            ( 'current'
+            , current_vtuber_cte.jsonb_agg -> 0
+            , 'results'
+            , prev_results_cte.jsonb_agg -> 0
+            , 'voted'
+            , coalesce(voted_vtubers_cte.jsonb_agg, '[]'::JSONB)
+            , 'vote_for_current'
+            , vote_for_current_cte.jsonb_agg -> 0
+            )
+          INTO data
+          FROM current_vtuber_cte
+             , prev_results_cte
+             , voted_vtubers_cte
+             , vote_for_current_cte;
+        RETURN data;
+      END;
+    $$;
+
+  COMMENT ON FUNCTION app.get_vote_stack IS
+    'Gets the current details of the VTuber, and the information + vote results of the previous VTuber.';
+COMMIT;
@@ -0,0 +1,207 @@
+-- Deploy oshismash:suggestions to pg
+-- requires: images
+
+BEGIN;
+  CREATE TYPE app.SUGGESTION_STATUS AS ENUM ('pending', 'approved', 'rejected');
+
+  -- VTubers that guests want added. The org is free text since it might not be
+  -- one that's in here yet.
+  CREATE TABLE app.suggestions (
+    suggestion_id BIGINT PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+    guest_id      UUID REFERENCES app.guests NOT NULL,
+
+    name          TEXT NOT NULL CHECK (length(name) BETWEEN 1 AND 100),
+    org_name      TEXT NOT NULL CHECK (length(org_name) <= 100),
+    links         TEXT[] NOT NULL DEFAULT '{}',
+    reason        TEXT NOT NULL CHECK (length(reason) <= 1000),
+
+    status        app.SUGGESTION_STATUS NOT NULL DEFAULT 'pending',
+    -- The VTuber that was added, once it's approved.
+    vtuber_id     BIGINT REFERENCES app.vtubers,
+
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+    reviewed_at   TIMESTAMPTZ
+  );
+
+  CREATE INDEX suggestions_guest_index ON app.suggestions (guest_id, created_at);
+  CREATE INDEX suggestions_pending_index ON app.suggestions (created_at) WHERE status = 'pending';
+
+  -- Gives back `NULL` instead of the suggestion's ID if the guest has already
+  -- made too many suggestions today.
+  CREATE FUNCTION app.suggest_vtuber
+    ( guest_id UUID
+    , name TEXT
+    , org_name TEXT
+    , links TEXT[]
+    , reason TEXT
+    )
+    RETURNS BIGINT
+    LANGUAGE PLPGSQL
+    AS $$
+      DECLARE
+        new_suggestion_id BIGINT;
+      BEGIN
+        -- So that suggestions made at the same time can't get past the limit.
+        PERFORM
+          FROM app.guests
+          WHERE guests.guest_id = suggest_vtuber.guest_id
+          FOR UPDATE;
+
+        IF (
+          SELECT count(*)
+            FROM app.suggestions
+            WHERE suggestions.guest_id = suggest_vtuber.guest_id
+              AND suggestions.created_at > now() - INTERVAL '1 day'
+        ) >= 3 THEN
+          RETURN NULL;
+        END IF;
+
+        INSERT INTO app.suggestions (guest_id, name, org_name, links, reason)
+          VALUES
+            ( suggest_vtuber.guest_id
+            , suggest_vtuber.name
+            , suggest_vtuber.org_name
+            , suggest_vtuber.links
+            , suggest_vtuber.reason
+            )
+          RETURNING suggestions.suggestion_id INTO new_suggestion_id;
+
+        RETURN new_suggestion_id;
+      END;
+    $$;
+
+  CREATE FUNCTION app.get_pending_suggestions()
+    RETURNS JSONB
+    LANGUAGE SQL
+    STABLE
+    AS $$
+      SELECT coalesce(
+        jsonb_agg(
+          jsonb_build_object
+            ( 'suggestion_id'
+            , suggestions.suggestion_id
+            , 'name'
+            , suggestions.name
+            , 'org_name'
+            , suggestions.org_name
+            , 'links'
+            , to_jsonb(suggestions.links)
+            , 'reason'
+            , suggestions.reason
+            , 'created_at'
+            , suggestions.created_at
+            )
+          ORDER BY suggestions.created_at
+        ),
+        '[]'::JSONB
+      )
+        FROM app.suggestions
+        WHERE suggestions.status = 'pending';
+    $$;
+
+  -- Adds the VTuber, and splices them into the chain right after
+  -- `after_vtuber_id`, or at the very start if it's `NULL`. Gives back the new
+  -- VTuber's ID, or `NULL` if the suggestion isn't pending.
+  CREATE FUNCTION app.approve_suggestion
+    ( suggestion_id BIGINT
+    , name TEXT
+    , description TEXT
+    , org_id INTEGER
+    , group_id INTEGER
+    , region app.REGION
+    , img_source TEXT
+    , youtube TEXT
+    , twitch TEXT
+    , twitter TEXT
+    , after_vtuber_id BIGINT
+    )
+    RETURNS BIGINT
+    LANGUAGE PLPGSQL
+    AS $$
+      DECLARE
+        new_vtuber_id BIGINT;
+        next_vtuber_id BIGINT;
+      BEGIN
+        PERFORM
+          FROM app.suggestions
+          WHERE suggestions.suggestion_id = approve_suggestion.suggestion_id
+            AND suggestions.status = 'pending'
+          FOR UPDATE;
+
+        IF NOT FOUND THEN
+          RETURN NULL;
+        END IF;
+
+        IF after_vtuber_id IS NULL THEN
+          SELECT vtubers.vtuber_id
+            INTO next_vtuber_id
+            FROM app.vtubers
+            WHERE vtubers.prev IS NULL
+            FOR UPDATE;
+        ELSE
+          SELECT vtubers.next
+            INTO next_vtuber_id
+            FROM app.vtubers
+            WHERE vtubers.vtuber_id = after_vtuber_id
+            FOR UPDATE;
+
+          IF NOT FOUND THEN
+            RAISE SQLSTATE 'Z0002'
+              USING MESSAGE = 'No VTuber to put the suggestion after';
+          END IF;
+        END IF;
+
+        INSERT INTO app.vtubers
+          ( name, description, org_id, group_id, region
+          , img_source, youtube, twitch, twitter
+          , prev, next
+          )
+          VALUES
+            ( approve_suggestion.name
+            , approve_suggestion.description
+            , approve_suggestion.org_id
+            , approve_suggestion.group_id
+            , approve_suggestion.region
+            , approve_suggestion.img_source
+            , approve_suggestion.youtube
+            , approve_suggestion.twitch
+            , approve_suggestion.twitter
+            , after_vtuber_id
+            , next_vtuber_id
+            )
+          RETURNING vtubers.vtuber_id INTO new_vtuber_id;
+
+        UPDATE app.vtubers
+          SET next = new_vtuber_id
+          WHERE vtubers.vtuber_id = after_vtuber_id;
+
+        UPDATE app.vtubers
+          SET prev = new_vtuber_id
+          WHERE vtubers.vtuber_id = next_vtuber_id;
+
+        UPDATE app.suggestions
+          SET status = 'approved'
+            , vtuber_id = new_vtuber_id
+            , reviewed_at = now()
+          WHERE suggestions.suggestion_id = approve_suggestion.suggestion_id;
+
+        RETURN new_vtuber_id;
+      END;
+    $$;
+
+  -- Gives back whether there was a pending suggestion to reject.
+  CREATE FUNCTION app.reject_suggestion(suggestion_id BIGINT)
+    RETURNS BOOLEAN
+    LANGUAGE SQL
+    AS $$
+      WITH rejected AS (
+        UPDATE app.suggestions
+          SET status = 'rejected'
+            , reviewed_at = now()
+          WHERE suggestions.suggestion_id = reject_suggestion.suggestion_id
+            AND suggestions.status = 'pending'
+          RETURNING suggestions.suggestion_id
+      )
+      SELECT exists(SELECT FROM rejected);
+    $$;
+COMMIT;
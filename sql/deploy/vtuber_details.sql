@@ -0,0 +1,122 @@
+-- Deploy oshismash:vtuber_details to pg
+-- requires: skips
+
+BEGIN;
+  ALTER TABLE app.vtubers
+    ADD COLUMN youtube TEXT CHECK (youtube ~ '^https://'),
+    ADD COLUMN twitch  TEXT CHECK (twitch ~ '^https://'),
+    ADD COLUMN twitter TEXT CHECK (twitter ~ '^https://');
+
+  -- Votes can be changed, so this is what the windowed stats go by.
+  CREATE FUNCTION app.touch_updated_at()
+    RETURNS TRIGGER
+    LANGUAGE PLPGSQL
+    AS $$
+      BEGIN
+        NEW.updated_at = now();
+        RETURN NEW;
+      END;
+    $$;
+
+  CREATE TRIGGER guest_votes_updated_at
+    BEFORE UPDATE ON app.guest_votes
+    FOR EACH ROW
+    EXECUTE FUNCTION app.touch_updated_at();
+
+  CREATE INDEX guest_votes_updated_at_index ON app.guest_votes (vtuber_id, updated_at);
+
+  -- Same as `app.get_metrics`, but only for votes cast (or changed) since then.
+  CREATE FUNCTION app.get_metrics_since
+    ( vtuber_id BIGINT
+    , since     TIMESTAMPTZ
+    )
+    RETURNS TABLE (smashes BIGINT, passes BIGINT, skips BIGINT)
+    LANGUAGE SQL
+    STABLE
+    AS $$
+      SELECT
+        count(*) FILTER (WHERE guest_votes.action = 'smashed') AS smashes,
+        count(*) FILTER (WHERE guest_votes.action = 'passed') AS passes,
+        count(*) FILTER (WHERE guest_votes.action = 'skipped') AS skips
+        FROM app.guest_votes
+        WHERE guest_votes.vtuber_id = $1
+          AND guest_votes.updated_at >= $2;
+    $$;
+
+  -- Everything about a VTuber for their profile page. It's `NULL` if there's
+  -- no such VTuber. The rank within their org goes by the same order as the
+  -- leaderboard.
+  CREATE FUNCTION app.get_vtuber_details
+    ( vtuber_id BIGINT
+    , locale    app.LOCALE DEFAULT 'en'
+    )
+    RETURNS JSONB
+    LANGUAGE SQL
+    STABLE
+    AS $$
+      WITH org_ranks AS (
+        SELECT
+          vtubers.vtuber_id,
+          rank() OVER (
+            ORDER BY
+              metrics.smashes::NUMERIC / nullif(metrics.smashes + metrics.passes, 0) DESC NULLS LAST,
+              metrics.smashes DESC
+          ) AS org_rank,
+          count(*) OVER () AS org_size
+          FROM app.vtubers
+          CROSS JOIN app.get_metrics(vtubers.vtuber_id) AS metrics
+          WHERE vtubers.org_id = (
+            SELECT target.org_id
+              FROM app.vtubers AS target
+              WHERE target.vtuber_id = $1
+          )
+      )
+      SELECT
+        json_build_object
+          ( 'id'
+          , vtubers.vtuber_id
+          , 'name'
+          , coalesce(translations.name, vtubers.name)
+          , 'description'
+          , coalesce(translations.description, vtubers.description)
+          , 'img'
+          , vtubers.img
+          , 'org_name'
+          , orgs.name
+          , 'group_name'
+          , groups.name
+          , 'region'
+          , vtubers.region
+          , 'youtube'
+          , vtubers.youtube
+          , 'twitch'
+          , vtubers.twitch
+          , 'twitter'
+          , vtubers.twitter
+          , 'stats'
+          , json_build_object
+              ( 'all_time'
+              , (SELECT to_jsonb(m) FROM app.get_metrics($1) AS m)
+              , 'last_week'
+              , (SELECT to_jsonb(m) FROM app.get_metrics_since($1, now() - INTERVAL '7 days') AS m)
+              , 'last_month'
+              , (SELECT to_jsonb(m) FROM app.get_metrics_since($1, now() - INTERVAL '30 days') AS m)
+              )
+          , 'org_rank'
+          , org_ranks.org_rank
+          , 'org_size'
+          , org_ranks.org_size
+          )
+        FROM app.vtubers AS vtubers
+        JOIN app.orgs AS orgs
+          ON vtubers.org_id = orgs.org_id
+        JOIN org_ranks
+          ON org_ranks.vtuber_id = vtubers.vtuber_id
+        LEFT JOIN app.groups AS groups
+          ON vtubers.group_id = groups.group_id
+        LEFT JOIN app.vtuber_translations AS translations
+          ON translations.vtuber_id = vtubers.vtuber_id
+         AND translations.locale = $2
+        WHERE vtubers.vtuber_id = $1;
+    $$;
+COMMIT;
@@ -0,0 +1,5 @@
+-- Revert oshismash:cast_vote from pg
+
+BEGIN;
+  DROP FUNCTION app.cast_vote;
+COMMIT;
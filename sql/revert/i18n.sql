@@ -0,0 +1,169 @@
+-- Revert oshismash:i18n from pg
+
+BEGIN;
+  DROP FUNCTION app.vote;
+  DROP FUNCTION app.get_vote_stack_from_current;
+  DROP FUNCTION app.get_vote_stack_from_previous;
+  DROP FUNCTION app.get_vote_stack;
+
+  DROP TABLE app.vtuber_translations;
+  DROP TYPE app.LOCALE;
+
+  CREATE OR REPLACE FUNCTION app.get_vote_stack
+    ( prev_vtuber_id BIGINT
+    , current_vtuber_id BIGINT
+    , guest_id UUID
+    )
+    RETURNS JSONB
+    LANGUAGE PLPGSQL
+    AS $$
+      DECLARE
+        data JSONB;
+      BEGIN
+        IF prev_vtuber_id IS NULL AND current_vtuber_id IS NULL THEN
+          RAISE SQLSTATE 'Z0001'
+            USING MESSAGE = 'Arguments should not be both NULL';
+        END IF;
+
+        WITH current_vtuber_cte AS (
+          -- I'm using jsonb_agg to bypass the annoyance of when `current_vtuber`
+          -- is `NULL`. If it is, using `current_vtuber` in the `FROM` clause
+          -- at the next query is going to cause the entire result to be `NULL`.
+          -- This is not what I want since I have to return the VTuber that was
+          -- previously voted.
+          SELECT jsonb_agg(
+              json_build_object
+                ( 'id'
+                , vtubers.vtuber_id
+                , 'description'
+                , vtubers.description
+                , 'name'
+                , vtubers.name
+                , 'prev'
+                , vtubers.prev
+                , 'next'
+                , vtubers.next
+                , 'img'
+                , vtubers.img
+                , 'org_name'
+                , orgs.name
+                )
+            )
+            FROM app.vtubers AS vtubers JOIN app.orgs AS orgs
+            ON vtubers.org_id = orgs.org_id
+            WHERE vtubers.vtuber_id = get_vote_stack.current_vtuber_id
+        ), prev_results_cte AS (
+          -- Grabs the results of the previous VTuber relative to the current.
+          -- This includes the smash/pass metrics.
+          SELECT
+            jsonb_agg(
+              json_build_object
+                ( 'vtuber_id'
+                , vtubers.vtuber_id
+                , 'name'
+                , vtubers.name
+                , 'img'
+                , vtubers.img
+                , 'smashes'
+                , metrics.smashes
+                , 'passes'
+                , metrics.passes
+                )
+            )
+            FROM app.vtubers AS vtubers
+               , app.get_metrics(prev_vtuber_id) AS metrics
+            WHERE vtubers.vtuber_id = get_vote_stack.prev_vtuber_id
+        ), voted_vtubers_cte AS (
+          SELECT jsonb_agg(vtuber_id)
+            FROM app.guest_votes
+            WHERE guest_votes.guest_id = $3
+        ), vote_for_current_cte AS (
+          SELECT jsonb_agg(action)
+            FROM app.guest_votes
+            WHERE guest_votes.guest_id = $3
+              AND guest_votes.vtuber_id = $2
+        )
+        SELECT
+          json_build_object
+            ( 'current'
+            , current_vtuber_cte.jsonb_agg -> 0
+            , 'results'
+            , prev_results_cte.jsonb_agg -> 0
+            , 'voted'
+            , coalesce(voted_vtubers_cte.jsonb_agg, '[]'::JSONB)
+            , 'vote_for_current'
+            , vote_for_current_cte.jsonb_agg -> 0
+            )
+          INTO data
+          FROM current_vtuber_cte
+             , prev_results_cte
+             , voted_vtubers_cte
+             , vote_for_current_cte;
+        RETURN data;
+      END;
+    $$;
+
+  CREATE OR REPLACE FUNCTION app.get_vote_stack_from_previous
+    ( prev_vtuber_id BIGINT
+    , guest_id       UUID
+    )
+    RETURNS JSONB
+    LANGUAGE SQL
+    AS $$
+      WITH current_cte AS (
+        SELECT vtubers.next
+          FROM app.vtubers
+          WHERE vtubers.vtuber_id = $1
+      )
+      SELECT app.get_vote_stack($1, current_cte.next, $2)
+        FROM current_cte;
+    $$;
+
+  CREATE OR REPLACE FUNCTION app.get_vote_stack_from_current
+    ( current_vtuber_id BIGINT
+    , guest_id          UUID
+    )
+    RETURNS JSONB
+    LANGUAGE SQL
+    AS $$
+      WITH current_cte AS (
+        SELECT vtubers.prev, vtubers.vtuber_id
+          FROM app.vtubers
+          WHERE vtubers.vtuber_id = $1
+      )
+      SELECT app.get_vote_stack(current_cte.prev, current_cte.vtuber_id, $2)
+        FROM current_cte;
+    $$;
+
+    COMMENT ON FUNCTION app.get_vote_stack IS
+      'Gets the current details of the VTuber, and the information + vote results of the previous VTuber.';
+
+  -- Functions
+  CREATE OR REPLACE FUNCTION app.vote(guest_id UUID, vtuber_id BIGINT, action app.ACTION)
+    RETURNS JSONB
+    LANGUAGE SQL
+    AS $$
+      -- Perform an upsert. Users are allowed to change their votes in the event
+      -- of, er, a phenomenon that clears their mind after doing a specific
+      -- action. Maybe they will be filled with regret, or something, who knows.
+      --
+      -- If there's a conflict, then this updates the vote to the new one.
+      WITH prev_vtuber_cte AS (
+        INSERT
+          INTO app.guest_votes (guest_id, vtuber_id, action)
+          VALUES ($1, $2, $3)
+          ON CONFLICT (vtuber_id, guest_id)
+              DO UPDATE SET action = $3
+          RETURNING vtuber_id AS prev_vtuber_id
+      ), current_vtuber_cte AS (
+        SELECT next AS current_vtuber_id
+          FROM app.vtubers, prev_vtuber_cte
+          WHERE vtubers.vtuber_id = prev_vtuber_cte.prev_vtuber_id
+      )
+      SELECT app.get_vote_stack(prev_vtuber_cte.prev_vtuber_id, current_vtuber_cte.current_vtuber_id, $1)
+        FROM prev_vtuber_cte, current_vtuber_cte;
+    $$;
+
+    COMMENT ON FUNCTION app.vote IS
+      'Votes on a VTuber depending on what the action is.';
+COMMIT;
@@ -0,0 +1,16 @@
+-- Revert oshismash:image_widths from pg
+
+BEGIN;
+  ALTER TABLE app.vtubers DROP CONSTRAINT vtubers_img_check;
+
+  UPDATE app.vtubers
+    SET img = NULL
+    WHERE img IS NOT NULL;
+
+  ALTER TABLE app.vtubers
+    ADD CONSTRAINT vtubers_img_check
+      CHECK (img ~ '^[0-9a-f]{32}$');
+
+  COMMENT ON COLUMN app.vtubers.img IS
+    'Key of the processed image, served from `/media/{img}/{width}.{avif,webp}`.';
+COMMIT;
@@ -0,0 +1,7 @@
+-- Revert oshismash:images from pg
+
+BEGIN;
+  ALTER TABLE app.vtubers DROP COLUMN img;
+
+  ALTER TABLE app.vtubers RENAME COLUMN img_source TO img;
+COMMIT;
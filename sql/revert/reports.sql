@@ -0,0 +1,212 @@
+-- Revert oshismash:reports from pg
+
+BEGIN;
+  CREATE OR REPLACE FUNCTION app.search_vtubers
+    ( query       TEXT
+    , locale      app.LOCALE DEFAULT 'en'
+    , max_results INTEGER DEFAULT 20
+    )
+    RETURNS JSONB
+    LANGUAGE SQL
+    STABLE
+    AS $$
+      WITH names AS (
+        SELECT vtubers.vtuber_id, vtubers.name
+          FROM app.vtubers
+        UNION ALL
+        SELECT vtuber_aliases.vtuber_id, vtuber_aliases.alias
+          FROM app.vtuber_aliases
+        UNION ALL
+        SELECT vtuber_translations.vtuber_id, vtuber_translations.name
+          FROM app.vtuber_translations
+          WHERE vtuber_translations.name IS NOT NULL
+      ), name_matches AS (
+        SELECT names.vtuber_id, max(word_similarity($1, names.name)) AS score
+          FROM names
+          WHERE $1 <% names.name
+             OR names.name ILIKE '%' || $1 || '%'
+          GROUP BY names.vtuber_id
+      ), description_matches AS (
+        SELECT
+          vtubers.vtuber_id,
+          ts_rank(to_tsvector('simple', vtubers.description), plainto_tsquery('simple', $1)) / 2 AS score
+          FROM app.vtubers
+          WHERE to_tsvector('simple', vtubers.description) @@ plainto_tsquery('simple', $1)
+      ), matches AS (
+        SELECT vtuber_id, max(score) AS score
+          FROM (
+            SELECT * FROM name_matches
+            UNION ALL
+            SELECT * FROM description_matches
+          ) AS all_matches
+          GROUP BY vtuber_id
+          ORDER BY score DESC, vtuber_id
+          LIMIT $3
+      )
+      SELECT coalesce(
+        jsonb_agg(
+          json_build_object
+            ( 'id'
+            , vtubers.vtuber_id
+            , 'name'
+            , coalesce(translations.name, vtubers.name)
+            , 'img'
+            , vtubers.img
+            , 'org_name'
+            , orgs.name
+            )
+          ORDER BY matches.score DESC, vtubers.vtuber_id
+        ),
+        '[]'::JSONB
+      )
+        FROM matches
+        JOIN app.vtubers AS vtubers
+          ON vtubers.vtuber_id = matches.vtuber_id
+        JOIN app.orgs AS orgs
+          ON vtubers.org_id = orgs.org_id
+        LEFT JOIN app.vtuber_translations AS translations
+          ON translations.vtuber_id = vtubers.vtuber_id
+         AND translations.locale = $2;
+    $$;
+
+  CREATE OR REPLACE FUNCTION app.get_vtuber_details
+    ( vtuber_id BIGINT
+    , locale    app.LOCALE DEFAULT 'en'
+    )
+    RETURNS JSONB
+    LANGUAGE SQL
+    STABLE
+    AS $$
+      WITH org_ranks AS (
+        SELECT
+          vtubers.vtuber_id,
+          rank() OVER (
+            ORDER BY
+              metrics.smashes::NUMERIC / nullif(metrics.smashes + metrics.passes, 0) DESC NULLS LAST,
+              metrics.smashes DESC
+          ) AS org_rank,
+          count(*) OVER () AS org_size
+          FROM app.vtubers
+          CROSS JOIN app.get_metrics(vtubers.vtuber_id) AS metrics
+          WHERE vtubers.org_id = (
+            SELECT target.org_id
+              FROM app.vtubers AS target
+              WHERE target.vtuber_id = $1
+          )
+      )
+      SELECT
+        json_build_object
+          ( 'id'
+          , vtubers.vtuber_id
+          , 'name'
+          , coalesce(translations.name, vtubers.name)
+          , 'description'
+          , coalesce(translations.description, vtubers.description)
+          , 'img'
+          , vtubers.img
+          , 'org_name'
+          , orgs.name
+          , 'group_name'
+          , groups.name
+          , 'region'
+          , vtubers.region
+          , 'youtube'
+          , vtubers.youtube
+          , 'twitch'
+          , vtubers.twitch
+          , 'twitter'
+          , vtubers.twitter
+          , 'stats'
+          , json_build_object
+              ( 'all_time'
+              , (SELECT to_jsonb(m) FROM app.get_metrics($1) AS m)
+              , 'last_week'
+              , (SELECT to_jsonb(m) FROM app.get_metrics_since($1, now() - INTERVAL '7 days') AS m)
+              , 'last_month'
+              , (SELECT to_jsonb(m) FROM app.get_metrics_since($1, now() - INTERVAL '30 days') AS m)
+              )
+          , 'org_rank'
+          , org_ranks.org_rank
+          , 'org_size'
+          , org_ranks.org_size
+          )
+        FROM app.vtubers AS vtubers
+        JOIN app.orgs AS orgs
+          ON vtubers.org_id = orgs.org_id
+        JOIN org_ranks
+          ON org_ranks.vtuber_id = vtubers.vtuber_id
+        LEFT JOIN app.groups AS groups
+          ON vtubers.group_id = groups.group_id
+        LEFT JOIN app.vtuber_translations AS translations
+          ON translations.vtuber_id = vtubers.vtuber_id
+         AND translations.locale = $2
+        WHERE vtubers.vtuber_id = $1;
+    $$;
+
+  CREATE OR REPLACE FUNCTION app.get_leaderboard(locale app.LOCALE DEFAULT 'en')
+    RETURNS JSONB
+    LANGUAGE SQL
+    STABLE
+    AS $$
+      WITH entries AS (
+        SELECT
+          vtubers.vtuber_id,
+          coalesce(translations.name, vtubers.name) AS name,
+          vtubers.img,
+          orgs.name AS org_name,
+          metrics.smashes,
+          metrics.passes,
+          metrics.skips
+          FROM app.vtubers AS vtubers
+          JOIN app.orgs AS orgs
+            ON vtubers.org_id = orgs.org_id
+          LEFT JOIN app.vtuber_translations AS translations
+            ON translations.vtuber_id = vtubers.vtuber_id
+           AND translations.locale = $1
+          CROSS JOIN app.get_metrics(vtubers.vtuber_id) AS metrics
+      )
+      SELECT coalesce(
+        jsonb_agg(
+          to_jsonb(entries)
+          ORDER BY
+            entries.smashes::NUMERIC / nullif(entries.smashes + entries.passes, 0) DESC NULLS LAST,
+            entries.smashes DESC,
+            entries.name
+        ),
+        '[]'::JSONB
+      )
+        FROM entries;
+    $$;
+
+  DROP FUNCTION app.set_vtuber_hidden;
+  DROP FUNCTION app.dismiss_report;
+  DROP FUNCTION app.resolve_report;
+  DROP FUNCTION app.get_hidden_vtubers;
+  DROP FUNCTION app.get_open_reports;
+  DROP FUNCTION app.report_vtuber;
+
+  -- A view can't lose a column that it depends on, so it goes back first.
+  CREATE OR REPLACE VIEW app.deck AS
+    WITH RECURSIVE chain AS (
+      SELECT vtubers.vtuber_id, vtubers.next, 1 AS position
+        FROM app.vtubers
+        WHERE vtubers.prev IS NULL
+      UNION ALL
+      SELECT vtubers.vtuber_id, vtubers.next, chain.position + 1
+        FROM app.vtubers
+        JOIN chain
+          ON vtubers.vtuber_id = chain.next
+        WHERE chain.position < (SELECT count(*) FROM app.vtubers)
+    )
+    SELECT chain.vtuber_id, chain.position, vtubers.org_id, vtubers.group_id, vtubers.region
+      FROM chain
+      JOIN app.vtubers
+        ON vtubers.vtuber_id = chain.vtuber_id;
+
+  DROP TABLE app.reports;
+
+  DROP TYPE app.REPORT_STATUS;
+  DROP TYPE app.REPORT_REASON;
+
+  ALTER TABLE app.vtubers DROP COLUMN hidden;
+COMMIT;
@@ -0,0 +1,124 @@
+-- Revert oshismash:retire from pg
+
+BEGIN;
+  CREATE OR REPLACE FUNCTION app.approve_suggestion
+    ( suggestion_id BIGINT
+    , name TEXT
+    , description TEXT
+    , org_id INTEGER
+    , group_id INTEGER
+    , region app.REGION
+    , img_source TEXT
+    , youtube TEXT
+    , twitch TEXT
+    , twitter TEXT
+    , after_vtuber_id BIGINT
+    )
+    RETURNS BIGINT
+    LANGUAGE PLPGSQL
+    AS $$
+      DECLARE
+        new_vtuber_id BIGINT;
+        next_vtuber_id BIGINT;
+      BEGIN
+        PERFORM
+          FROM app.suggestions
+          WHERE suggestions.suggestion_id = approve_suggestion.suggestion_id
+            AND suggestions.status = 'pending'
+          FOR UPDATE;
+
+        IF NOT FOUND THEN
+          RETURN NULL;
+        END IF;
+
+        IF after_vtuber_id IS NULL THEN
+          SELECT vtubers.vtuber_id
+            INTO next_vtuber_id
+            FROM app.vtubers
+            WHERE vtubers.prev IS NULL
+            FOR UPDATE;
+        ELSE
+          SELECT vtubers.next
+            INTO next_vtuber_id
+            FROM app.vtubers
+            WHERE vtubers.vtuber_id = after_vtuber_id
+            FOR UPDATE;
+
+          IF NOT FOUND THEN
+            RAISE SQLSTATE 'Z0002'
+              USING MESSAGE = 'No VTuber to put the suggestion after';
+          END IF;
+        END IF;
+
+        INSERT INTO app.vtubers
+          ( name, description, org_id, group_id, region
+          , img_source, youtube, twitch, twitter
+          , prev, next
+          )
+          VALUES
+            ( approve_suggestion.name
+            , approve_suggestion.description
+            , approve_suggestion.org_id
+            , approve_suggestion.group_id
+            , approve_suggestion.region
+            , approve_suggestion.img_source
+            , approve_suggestion.youtube
+            , approve_suggestion.twitch
+            , approve_suggestion.twitter
+            , after_vtuber_id
+            , next_vtuber_id
+            )
+          RETURNING vtubers.vtuber_id INTO new_vtuber_id;
+
+        UPDATE app.vtubers
+          SET next = new_vtuber_id
+          WHERE vtubers.vtuber_id = after_vtuber_id;
+
+        UPDATE app.vtubers
+          SET prev = new_vtuber_id
+          WHERE vtubers.vtuber_id = next_vtuber_id;
+
+        UPDATE app.suggestions
+          SET status = 'approved'
+            , vtuber_id = new_vtuber_id
+            , reviewed_at = now()
+          WHERE suggestions.suggestion_id = approve_suggestion.suggestion_id;
+
+        RETURN new_vtuber_id;
+      END;
+    $$;
+
+  DROP FUNCTION app.get_retired_vtubers;
+  DROP FUNCTION app.restore_vtuber;
+  DROP FUNCTION app.retire_vtuber;
+  DROP FUNCTION app.link_vtuber;
+
+  CREATE OR REPLACE VIEW app.deck AS
+    WITH RECURSIVE chain AS (
+      SELECT vtubers.vtuber_id, vtubers.next, 1 AS position
+        FROM app.vtubers
+        WHERE vtubers.prev IS NULL
+      UNION ALL
+      SELECT vtubers.vtuber_id, vtubers.next, chain.position + 1
+        FROM app.vtubers
+        JOIN chain
+          ON vtubers.vtuber_id = chain.next
+        WHERE chain.position < (SELECT count(*) FROM app.vtubers)
+    )
+    SELECT chain.vtuber_id, chain.position, vtubers.org_id, vtubers.group_id, vtubers.region
+      FROM chain
+      JOIN app.vtubers
+        ON vtubers.vtuber_id = chain.vtuber_id
+      WHERE NOT vtubers.hidden;
+
+  -- Without `retired_at`, retired VTubers would look like the start of the
+  -- chain. They're hidden instead, which keeps them out of the deck.
+  UPDATE app.vtubers
+    SET hidden = TRUE
+    WHERE vtubers.retired_at IS NOT NULL;
+
+  ALTER TABLE app.vtubers
+    DROP CONSTRAINT vtubers_retired_unlinked,
+    DROP COLUMN retired_after,
+    DROP COLUMN retired_at;
+COMMIT;
@@ -0,0 +1,13 @@
+-- Revert oshismash:search from pg
+
+BEGIN;
+  DROP FUNCTION app.search_vtubers;
+
+  DROP INDEX app.vtuber_translations_name_trgm_index;
+  DROP INDEX app.vtubers_description_fts_index;
+  DROP INDEX app.vtubers_name_trgm_index;
+
+  DROP TABLE app.vtuber_aliases;
+
+  DROP EXTENSION IF EXISTS pg_trgm;
+COMMIT;
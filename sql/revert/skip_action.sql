@@ -0,0 +1,48 @@
+-- Revert oshismash:skip_action from pg
+
+-- Enum values can't be removed, so the type is replaced with one that doesn't
+-- have `skipped`. `app.vote` depends on the old type, so it's recreated too.
+BEGIN;
+  DELETE FROM app.guest_votes
+    WHERE action = 'skipped';
+
+  ALTER TYPE app.ACTION RENAME TO ACTION_OLD;
+  CREATE TYPE app.ACTION AS ENUM ('smashed', 'passed');
+
+  ALTER TABLE app.guest_votes
+    ALTER COLUMN action TYPE app.ACTION USING action::TEXT::app.ACTION;
+
+  DROP FUNCTION app.vote;
+  DROP TYPE app.ACTION_OLD;
+
+  CREATE FUNCTION app.vote
+    ( guest_id    UUID
+    , vtuber_id   BIGINT
+    , action      app.ACTION
+    , locale      app.LOCALE DEFAULT 'en'
+    , deck_filter app.DECK_FILTER DEFAULT NULL
+    )
+    RETURNS JSONB
+    LANGUAGE SQL
+    AS $$
+      -- Perform an upsert. Users are allowed to change their votes in the event
+      -- of, er, a phenomenon that clears their mind after doing a specific
+      -- action. Maybe they will be filled with regret, or something, who knows.
+      --
+      -- If there's a conflict, then this updates the vote to the new one.
+      WITH prev_vtuber_cte AS (
+        INSERT
+          INTO app.guest_votes (guest_id, vtuber_id, action)
+          VALUES ($1, $2, $3)
+          ON CONFLICT (vtuber_id, guest_id)
+              DO UPDATE SET action = $3
+          RETURNING vtuber_id AS prev_vtuber_id
+      )
+      SELECT app.get_vote_stack(prev_vtuber_cte.prev_vtuber_id, neighbors.next, $1, $4, $5)
+        FROM prev_vtuber_cte
+        CROSS JOIN app.deck_neighbors(prev_vtuber_cte.prev_vtuber_id, $1, $5) AS neighbors;
+    $$;
+
+  COMMENT ON FUNCTION app.vote IS
+    'Votes on a VTuber depending on what the action is.';
+COMMIT;
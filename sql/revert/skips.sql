@@ -0,0 +1,163 @@
+-- Revert oshismash:skips from pg
+
+BEGIN;
+  DROP FUNCTION app.get_leaderboard;
+  DROP FUNCTION app.get_metrics;
+
+  CREATE FUNCTION app.get_metrics(vtuber_id BIGINT)
+    RETURNS TABLE (smashes BIGINT, passes BIGINT)
+    LANGUAGE SQL
+    AS $$
+      WITH smashes_cte AS (
+        SELECT count(*)
+          FROM app.guest_votes
+          WHERE guest_votes.vtuber_id = $1
+            AND guest_votes.action = 'smashed'
+      ), passes_cte AS (
+        SELECT count(*)
+          FROM app.guest_votes
+          WHERE guest_votes.vtuber_id = $1
+            AND guest_votes.action = 'passed'
+      )
+      SELECT
+        smashes_cte.count AS smashes,
+        passes_cte.count AS passes
+      FROM smashes_cte, passes_cte, app.vtubers
+      GROUP BY smashes_cte.count, passes_cte.count;
+    $$;
+
+  CREATE OR REPLACE FUNCTION app.get_vote_stack
+    ( prev_vtuber_id BIGINT
+    , current_vtuber_id BIGINT
+    , guest_id UUID
+    , locale app.LOCALE DEFAULT 'en'
+    , deck_filter app.DECK_FILTER DEFAULT NULL
+    )
+    RETURNS JSONB
+    LANGUAGE PLPGSQL
+    AS $$
+      DECLARE
+        data JSONB;
+      BEGIN
+        IF prev_vtuber_id IS NULL AND current_vtuber_id IS NULL THEN
+          RAISE SQLSTATE 'Z0001'
+            USING MESSAGE = 'Arguments should not be both NULL';
+        END IF;
+
+        WITH current_vtuber_cte AS (
+          -- I'm using jsonb_agg to bypass the annoyance of when `current_vtuber`
+          -- is `NULL`. If it is, using `current_vtuber` in the `FROM` clause
+          -- at the next query is going to cause the entire result to be `NULL`.
+          -- This is not what I want since I have to return the VTuber that was
+          -- previously voted.
+          SELECT jsonb_agg(
+              json_build_object
+                ( 'id'
+                , vtubers.vtuber_id
+                , 'description'
+                , coalesce(translations.description, vtubers.description)
+                , 'name'
+                , coalesce(translations.name, vtubers.name)
+                , 'prev'
+                , neighbors.prev
+                , 'next'
+                , neighbors.next
+                , 'img'
+                , vtubers.img
+                , 'org_name'
+                , orgs.name
+                , 'group_name'
+                , groups.name
+                , 'region'
+                , vtubers.region
+                )
+            )
+            FROM app.vtubers AS vtubers
+            JOIN app.orgs AS orgs
+              ON vtubers.org_id = orgs.org_id
+            LEFT JOIN app.groups AS groups
+              ON vtubers.group_id = groups.group_id
+            LEFT JOIN app.vtuber_translations AS translations
+              ON translations.vtuber_id = vtubers.vtuber_id
+             AND translations.locale = get_vote_stack.locale
+            CROSS JOIN app.deck_neighbors(vtubers.vtuber_id, get_vote_stack.guest_id, get_vote_stack.deck_filter) AS neighbors
+            WHERE vtubers.vtuber_id = get_vote_stack.current_vtuber_id
+        ), prev_results_cte AS (
+          -- Grabs the results of the previous VTuber relative to the current.
+          -- This includes the smash/pass metrics.
+          SELECT
+            jsonb_agg(
+              json_build_object
+                ( 'vtuber_id'
+                , vtubers.vtuber_id
+                , 'name'
+                , coalesce(translations.name, vtubers.name)
+                , 'img'
+                , vtubers.img
+                , 'smashes'
+                , metrics.smashes
+                , 'passes'
+                , metrics.passes
+                )
+            )
+            FROM app.vtubers AS vtubers
+            CROSS JOIN app.get_metrics(prev_vtuber_id) AS metrics
+            LEFT JOIN app.vtuber_translations AS translations
+              ON translations.vtuber_id = vtubers.vtuber_id
+             AND translations.locale = get_vote_stack.locale
+            WHERE vtubers.vtuber_id = get_vote_stack.prev_vtuber_id
+        ), voted_vtubers_cte AS (
+          SELECT jsonb_agg(vtuber_id)
+            FROM app.guest_votes
+            WHERE guest_votes.guest_id = $3
+        ), vote_for_current_cte AS (
+          SELECT jsonb_agg(action)
+            FROM app.guest_votes
+            WHERE guest_votes.guest_id = $3
+              AND guest_votes.vtuber_id = $2
+        )
+        SELECT
+          json_build_object
+            ( 'current'
+            , current_vtuber_cte.jsonb_agg -> 0
+            , 'results'
+            , prev_results_cte.jsonb_agg -> 0
+            , 'voted'
+            , coalesce(voted_vtubers_cte.jsonb_agg, '[]'::JSONB)
+            , 'vote_for_current'
+            , vote_for_current_cte.jsonb_agg -> 0
+            )
+          INTO data
+          FROM current_vtuber_cte
+             , prev_results_cte
+             , voted_vtubers_cte
+             , vote_for_current_cte;
+        RETURN data;
+      END;
+    $$;
+
+  COMMENT ON FUNCTION app.get_vote_stack IS
+    'Gets the current details of the VTuber, and the information + vote results of the previous VTuber.';
+
+  CREATE OR REPLACE FUNCTION app.get_vtuber_results(vtuber_id BIGINT)
+    RETURNS JSONB
+    LANGUAGE SQL
+    AS $$
+      SELECT
+        json_build_object
+          ( 'vtuber_id'
+          , vtubers.vtuber_id
+          , 'name'
+          , vtubers.name
+          , 'img'
+          , vtubers.img
+          , 'smashes'
+          , metrics.smashes
+          , 'passes'
+          , metrics.passes
+          )
+        FROM app.vtubers AS vtubers
+           , app.get_metrics(vtuber_id) AS metrics
+        WHERE vtubers.vtuber_id = get_vtuber_results.vtuber_id;
+    $$;
+COMMIT;
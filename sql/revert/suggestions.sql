@@ -0,0 +1,12 @@
+-- Revert oshismash:suggestions from pg
+
+BEGIN;
+  DROP FUNCTION app.reject_suggestion;
+  DROP FUNCTION app.approve_suggestion;
+  DROP FUNCTION app.get_pending_suggestions;
+  DROP FUNCTION app.suggest_vtuber;
+
+  DROP TABLE app.suggestions;
+
+  DROP TYPE app.SUGGESTION_STATUS;
+COMMIT;
@@ -0,0 +1,15 @@
+-- Revert oshismash:vtuber_details from pg
+
+BEGIN;
+  DROP FUNCTION app.get_vtuber_details;
+  DROP FUNCTION app.get_metrics_since;
+
+  DROP INDEX app.guest_votes_updated_at_index;
+  DROP TRIGGER guest_votes_updated_at ON app.guest_votes;
+  DROP FUNCTION app.touch_updated_at;
+
+  ALTER TABLE app.vtubers
+    DROP COLUMN youtube,
+    DROP COLUMN twitch,
+    DROP COLUMN twitter;
+COMMIT;
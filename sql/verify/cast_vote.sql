@@ -0,0 +1,10 @@
+-- Verify oshismash:cast_vote on pg
+
+BEGIN;
+
+  SELECT has_function_privilege(
+    'app.cast_vote(UUID, BIGINT, app.ACTION, app.LOCALE, app.DECK_FILTER)',
+    'execute'
+  );
+
+ROLLBACK;
@@ -0,0 +1,12 @@
+-- Verify oshismash:deck_filters on pg
+
+BEGIN;
+
+  SELECT vtuber_id, position, org_id, group_id, region
+    FROM app.deck
+    WHERE FALSE;
+
+  SELECT has_function_privilege('app.get_deck_access(BIGINT, UUID, app.DECK_FILTER)', 'execute');
+  SELECT has_function_privilege('app.get_vote_stack(BIGINT, BIGINT, UUID, app.LOCALE, app.DECK_FILTER)', 'execute');
+
+ROLLBACK;
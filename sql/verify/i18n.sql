@@ -0,0 +1,11 @@
+-- Verify oshismash:i18n on pg
+
+BEGIN;
+
+  SELECT vtuber_id, locale, name, description
+    FROM app.vtuber_translations
+    WHERE FALSE;
+
+  SELECT has_function_privilege('app.get_vote_stack(BIGINT, BIGINT, UUID, app.LOCALE)', 'execute');
+
+ROLLBACK;
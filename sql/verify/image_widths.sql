@@ -0,0 +1,10 @@
+-- Verify oshismash:image_widths on pg
+
+BEGIN;
+
+  SELECT 1 / count(*)
+    FROM pg_constraint
+    WHERE conname = 'vtubers_img_check'
+      AND pg_get_constraintdef(oid) LIKE '%-[1-9]%';
+
+ROLLBACK;
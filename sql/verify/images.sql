@@ -0,0 +1,9 @@
+-- Verify oshismash:images on pg
+
+BEGIN;
+
+  SELECT img, img_source
+    FROM app.vtubers
+    WHERE FALSE;
+
+ROLLBACK;
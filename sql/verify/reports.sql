@@ -0,0 +1,20 @@
+-- Verify oshismash:reports on pg
+
+BEGIN;
+
+  SELECT hidden
+    FROM app.vtubers
+    WHERE FALSE;
+
+  SELECT report_id, vtuber_id, guest_id, reason, details, status, created_at, reviewed_at
+    FROM app.reports
+    WHERE FALSE;
+
+  SELECT has_function_privilege('app.report_vtuber(UUID, BIGINT, app.REPORT_REASON, TEXT)', 'execute');
+  SELECT has_function_privilege('app.get_open_reports()', 'execute');
+  SELECT has_function_privilege('app.get_hidden_vtubers()', 'execute');
+  SELECT has_function_privilege('app.resolve_report(BIGINT)', 'execute');
+  SELECT has_function_privilege('app.dismiss_report(BIGINT)', 'execute');
+  SELECT has_function_privilege('app.set_vtuber_hidden(BIGINT, BOOLEAN)', 'execute');
+
+ROLLBACK;
@@ -0,0 +1,14 @@
+-- Verify oshismash:retire on pg
+
+BEGIN;
+
+  SELECT retired_at, retired_after
+    FROM app.vtubers
+    WHERE FALSE;
+
+  SELECT has_function_privilege('app.link_vtuber(BIGINT, BIGINT)', 'execute');
+  SELECT has_function_privilege('app.retire_vtuber(BIGINT)', 'execute');
+  SELECT has_function_privilege('app.restore_vtuber(BIGINT, BIGINT)', 'execute');
+  SELECT has_function_privilege('app.get_retired_vtubers()', 'execute');
+
+ROLLBACK;
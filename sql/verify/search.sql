@@ -0,0 +1,11 @@
+-- Verify oshismash:search on pg
+
+BEGIN;
+
+  SELECT vtuber_id, alias
+    FROM app.vtuber_aliases
+    WHERE FALSE;
+
+  SELECT has_function_privilege('app.search_vtubers(TEXT, app.LOCALE, INTEGER)', 'execute');
+
+ROLLBACK;
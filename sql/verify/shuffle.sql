@@ -0,0 +1,12 @@
+-- Verify oshismash:shuffle on pg
+
+BEGIN;
+
+  SELECT guest_id, deck_seed
+    FROM app.guests
+    WHERE FALSE;
+
+  SELECT has_function_privilege('app.set_deck_shuffle(UUID, BOOLEAN)', 'execute');
+  SELECT has_function_privilege('app.deck_neighbors(BIGINT, UUID, app.DECK_FILTER)', 'execute');
+
+ROLLBACK;
@@ -0,0 +1,7 @@
+-- Verify oshismash:skip_action on pg
+
+BEGIN;
+
+  SELECT 'skipped'::app.ACTION;
+
+ROLLBACK;
@@ -0,0 +1,10 @@
+-- Verify oshismash:skips on pg
+
+BEGIN;
+
+  SELECT smashes, passes, skips
+    FROM app.get_metrics(NULL);
+
+  SELECT has_function_privilege('app.get_leaderboard(app.LOCALE)', 'execute');
+
+ROLLBACK;
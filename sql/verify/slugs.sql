@@ -0,0 +1,13 @@
+-- Verify oshismash:slugs on pg
+
+BEGIN;
+
+  SELECT slug
+    FROM app.vtubers
+    WHERE FALSE;
+
+  SELECT has_function_privilege('app.slugify(TEXT)', 'execute');
+
+  SELECT 1/(app.slugify('Nyatasha Nyanners') = 'nyatasha-nyanners')::INTEGER;
+
+ROLLBACK;
@@ -0,0 +1,14 @@
+-- Verify oshismash:suggestions on pg
+
+BEGIN;
+
+  SELECT suggestion_id, guest_id, name, org_name, links, reason, status, vtuber_id, created_at, reviewed_at
+    FROM app.suggestions
+    WHERE FALSE;
+
+  SELECT has_function_privilege('app.suggest_vtuber(UUID, TEXT, TEXT, TEXT[], TEXT)', 'execute');
+  SELECT has_function_privilege('app.get_pending_suggestions()', 'execute');
+  SELECT has_function_privilege('app.approve_suggestion(BIGINT, TEXT, TEXT, INTEGER, INTEGER, app.REGION, TEXT, TEXT, TEXT, TEXT, BIGINT)', 'execute');
+  SELECT has_function_privilege('app.reject_suggestion(BIGINT)', 'execute');
+
+ROLLBACK;
@@ -0,0 +1,11 @@
+-- Verify oshismash:vtuber_details on pg
+
+BEGIN;
+
+  SELECT youtube, twitch, twitter
+    FROM app.vtubers
+    WHERE FALSE;
+
+  SELECT has_function_privilege('app.get_vtuber_details(BIGINT, app.LOCALE)', 'execute');
+
+ROLLBACK;
@@ -1,12 +1,28 @@
-use crate::config::AppConfig;
-use deadpool_postgres::{Manager, Object, Pool, PoolError};
-use native_tls::{Certificate, TlsConnector};
+use crate::config::{AppConfig, SslMode, Tls};
+use deadpool_postgres::{Hook, Manager, Object, Pool, PoolError, Runtime, Timeouts};
+use native_tls::{Certificate, Identity, TlsConnector};
 use postgres_native_tls::MakeTlsConnector;
-use std::{fmt, io};
+use std::{
+    fmt, io,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
 use tokio_postgres::NoTls;
 
+/// How long reads stay on the primary after the replica fails
+const REPLICA_RETRY_AFTER: Duration = Duration::from_secs(30);
+
 pub struct Handle {
     pub pool: Pool,
+    /// Read replica for queries that don't need to see the latest writes
+    pub replica: Option<Pool>,
+    replica_down_until: Mutex<Option<Instant>>,
+    /// Size of the statement cache of the last client each pool handed out
+    cached_statements: Arc<AtomicUsize>,
+    replica_cached_statements: Arc<AtomicUsize>,
 }
 
 // TODO: Break this error into multiple ones. DB & business logic stuff?
@@ -15,6 +31,8 @@ pub enum Error {
     PoolError(PoolError),
     CACertFileError(io::Error),
     InvalidCACert,
+    ClientCertFileError(io::Error),
+    InvalidClientCert,
     FailedToBuildTlsConnector,
     FailedToBuildPool,
 }
@@ -25,6 +43,12 @@ impl fmt::Display for Error {
             Error::PoolError(pe) => write!(f, "Couldn't get a client from the pool: {}", pe),
             Error::CACertFileError(ie) => write!(f, "Failed to read CA cert: {}", ie),
             Error::InvalidCACert => write!(f, "Invalid PEM file"),
+            Error::ClientCertFileError(ie) => {
+                write!(f, "Failed to read client cert or key: {}", ie)
+            }
+            Error::InvalidClientCert => {
+                write!(f, "Invalid client cert or PKCS #8 key")
+            }
             Error::FailedToBuildTlsConnector => {
                 write!(f, "Couldn't build a TLS connector with this certificate")
             }
@@ -41,30 +65,51 @@ impl From<PoolError> for Error {
 
 impl Handle {
     pub async fn new(app_config: AppConfig) -> Result<Handle, Error> {
-        let manager = match app_config.ca_cert_path {
-            Some(ca_cert_path) => {
-                let cert = std::fs::read(ca_cert_path).map_err(|e| Error::CACertFileError(e))?;
+        let cached_statements = Arc::new(AtomicUsize::new(0));
+        let replica_cached_statements = Arc::new(AtomicUsize::new(0));
+
+        let pool = build_pool(
+            app_config.pg,
+            &app_config.tls,
+            app_config.manager.clone(),
+            app_config.pool_size,
+            app_config.pool_timeouts,
+            cached_statements.clone(),
+        )?;
 
-                let ntls_cert = Certificate::from_pem(&cert).map_err(|_| Error::InvalidCACert)?;
+        prewarm(&pool, app_config.pool_prewarm).await?;
 
-                let tls = TlsConnector::builder()
-                    .add_root_certificate(ntls_cert)
-                    .build()
-                    .map_err(|_| Error::FailedToBuildTlsConnector)?;
+        let replica = match app_config.replica {
+            Some(replica) => {
+                let replica = build_pool(
+                    replica,
+                    &app_config.tls,
+                    app_config.manager,
+                    app_config.replica_pool_size,
+                    app_config.pool_timeouts,
+                    replica_cached_statements.clone(),
+                )?;
 
-                let conn = MakeTlsConnector::new(tls);
+                // Reads can go to the primary, so a replica that's down
+                // shouldn't keep the server from starting.
+                let prewarm_size = app_config.pool_prewarm.min(app_config.replica_pool_size);
 
-                Manager::from_config(app_config.pg, conn, app_config.manager)
+                if let Err(e) = prewarm(&replica, prewarm_size).await {
+                    eprintln!("Couldn't connect to the replica: {}", e);
+                }
+
+                Some(replica)
             }
-            None => Manager::from_config(app_config.pg, NoTls, app_config.manager),
+            None => None,
         };
 
-        let pool = Pool::builder(manager)
-            .max_size(app_config.pool_size)
-            .build()
-            .map_err(|_| Error::FailedToBuildPool)?;
-
-        Ok(Handle { pool })
+        Ok(Handle {
+            pool,
+            replica,
+            replica_down_until: Mutex::new(None),
+            cached_statements,
+            replica_cached_statements,
+        })
     }
 
     /// Creates a new client in a pool.
@@ -74,4 +119,177 @@ impl Handle {
         println!("Getting client from pool...");
         self.pool.get().await
     }
+
+    /// Gets a client for queries that only read, and can be a little behind.
+    /// These go to the replica if there's one, unless it's been failing, in
+    /// which case they go to the primary. Reads that have to see what the
+    /// guest just did (like their own votes) should use `client` instead.
+    pub async fn reader(&self) -> Result<Object, PoolError> {
+        if let Some(replica) = self.available_replica() {
+            match replica.get().await {
+                Ok(client) => return Ok(client),
+                Err(e) => {
+                    eprintln!("Replica is unavailable, reading from the primary: {}", e);
+
+                    *self.replica_down_until.lock().unwrap() =
+                        Some(Instant::now() + REPLICA_RETRY_AFTER);
+                }
+            }
+        }
+
+        self.pool.get().await
+    }
+
+    fn available_replica(&self) -> Option<&Pool> {
+        let replica = self.replica.as_ref()?;
+        let mut down_until = self.replica_down_until.lock().unwrap();
+
+        match *down_until {
+            Some(until) if Instant::now() < until => None,
+            _ => {
+                *down_until = None;
+                Some(replica)
+            }
+        }
+    }
+
+    /// Metrics of the primary pool, and the replica's if there's one.
+    pub fn metrics(&self) -> Vec<(&'static str, PoolMetrics)> {
+        let primary = (
+            "primary",
+            PoolMetrics::of(&self.pool, &self.cached_statements),
+        );
+
+        let replica = self.replica.as_ref().map(|replica| {
+            (
+                "replica",
+                PoolMetrics::of(replica, &self.replica_cached_statements),
+            )
+        });
+
+        std::iter::once(primary).chain(replica).collect()
+    }
+
+    /// Stops handing out clients, and closes the idle ones.
+    pub fn close(&self) {
+        self.pool.close();
+
+        if let Some(replica) = &self.replica {
+            replica.close();
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolMetrics {
+    pub max_size: usize,
+    /// Connections that are open, whether they're in use or not
+    pub size: usize,
+    pub available: usize,
+    /// Requests waiting for a connection
+    pub waiting: usize,
+    /// Prepared statements cached by the last connection that was handed out.
+    /// Statements are cached per connection, so this levels off at about the
+    /// number of queries once the connections have been used for a while.
+    pub cached_statements: usize,
+}
+
+impl PoolMetrics {
+    fn of(pool: &Pool, cached_statements: &AtomicUsize) -> PoolMetrics {
+        let status = pool.status();
+
+        PoolMetrics {
+            max_size: status.max_size,
+            size: status.size,
+            available: status.available.max(0) as usize,
+            waiting: (-status.available).max(0) as usize,
+            cached_statements: cached_statements.load(Ordering::Relaxed),
+        }
+    }
+}
+
+fn build_pool(
+    pg: tokio_postgres::Config,
+    tls: &Tls,
+    manager: deadpool_postgres::ManagerConfig,
+    size: usize,
+    timeouts: Timeouts,
+    cached_statements: Arc<AtomicUsize>,
+) -> Result<Pool, Error> {
+    let manager = match tls_connector(tls)? {
+        Some(tls) => {
+            let conn = MakeTlsConnector::new(tls);
+
+            Manager::from_config(pg, conn, manager)
+        }
+        None => Manager::from_config(pg, NoTls, manager),
+    };
+
+    // Recycling is when a client is handed out again, which is the only time
+    // its cache can be looked at without holding up the pool.
+    let track_cache = Hook::sync_fn(move |client, _| {
+        cached_statements.store(client.statement_cache.size(), Ordering::Relaxed);
+        Ok(())
+    });
+
+    // The timeouts need a runtime to run on.
+    Pool::builder(manager)
+        .max_size(size)
+        .timeouts(timeouts)
+        .runtime(Runtime::Tokio1)
+        .post_recycle(track_cache)
+        .build()
+        .map_err(|_| Error::FailedToBuildPool)
+}
+
+/// Opens `size` connections at once, and puts them back in the pool. This also
+/// checks that the DB can be connected to at all.
+async fn prewarm(pool: &Pool, size: usize) -> Result<(), Error> {
+    let clients = futures::future::try_join_all((0..size).map(|_| pool.get())).await?;
+
+    drop(clients);
+
+    Ok(())
+}
+
+/// Checks the server's certificate as much as libpq would for the same
+/// `sslmode`.
+fn tls_connector(tls: &Tls) -> Result<Option<TlsConnector>, Error> {
+    if tls.mode == SslMode::Disable {
+        return Ok(None);
+    }
+
+    let mut builder = TlsConnector::builder();
+
+    if let Some(ca_cert_path) = &tls.ca_cert_path {
+        let cert = std::fs::read(ca_cert_path).map_err(Error::CACertFileError)?;
+        let ntls_cert = Certificate::from_pem(&cert).map_err(|_| Error::InvalidCACert)?;
+
+        builder.add_root_certificate(ntls_cert);
+    }
+
+    if let Some(client_cert) = &tls.client_cert {
+        let cert = std::fs::read(&client_cert.cert_path).map_err(Error::ClientCertFileError)?;
+        let key = std::fs::read(&client_cert.key_path).map_err(Error::ClientCertFileError)?;
+        let identity = Identity::from_pkcs8(&cert, &key).map_err(|_| Error::InvalidClientCert)?;
+
+        builder.identity(identity);
+    }
+
+    match tls.mode {
+        // Like libpq, these only check the certificate if there's a CA cert
+        // to check it with.
+        SslMode::Prefer | SslMode::Require if tls.ca_cert_path.is_none() => {
+            builder.danger_accept_invalid_certs(true);
+        }
+        SslMode::Prefer | SslMode::Require | SslMode::VerifyCa => {
+            builder.danger_accept_invalid_hostnames(true);
+        }
+        SslMode::Disable | SslMode::VerifyFull => (),
+    }
+
+    builder
+        .build()
+        .map(Some)
+        .map_err(|_| Error::FailedToBuildTlsConnector)
 }
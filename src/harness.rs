@@ -0,0 +1,175 @@
+use std::{collections::BTreeMap, env, sync::Arc};
+
+use axum::{body::Body, Router};
+use cookie::{time::Duration, Cookie};
+use hyper::{
+    header::{CONTENT_TYPE, COOKIE, LOCATION, SET_COOKIE},
+    Request, StatusCode, Uri,
+};
+use tower::ServiceExt;
+
+use crate::config::{AppConfig, Layers};
+use crate::db;
+use crate::oshismash::store::{
+    self,
+    memory::{Listing, MemoryStore},
+};
+use crate::oshismash_web::shutdown::Readiness;
+
+/// Database that `TestClient::postgres` runs against, with every change in
+/// `sql/` deployed. Tests that need it are ignored unless asked for.
+const DATABASE_URL_ENV: &str = "OSHISMASH_TEST_DATABASE_URL";
+
+/// Sends requests straight to the router, without binding a port. Cookies are
+/// kept between requests like a browser would, except that their paths (and
+/// the other attributes) are ignored.
+pub struct TestClient {
+    app: Router,
+    cookies: BTreeMap<String, Cookie<'static>>,
+}
+
+#[derive(Debug)]
+pub struct TestResponse {
+    pub status: StatusCode,
+    /// Path and query of the `Location` header, if it's a redirect.
+    pub location: Option<String>,
+    pub body: String,
+}
+
+impl TestClient {
+    /// The app with the deck in memory. Only the voting flow works, since the
+    /// rest of the pages need Postgres.
+    pub fn memory(deck: Vec<Listing>) -> TestClient {
+        TestClient::memory_at("/", deck)
+    }
+
+    /// Like `memory`, but with the app mounted under `base_path`.
+    pub fn memory_at(base_path: &str, deck: Vec<Listing>) -> TestClient {
+        let config = config("postgres://oshismash@localhost/oshismash", base_path);
+        let store = store::Handle::new(Arc::new(MemoryStore::new(deck)));
+
+        TestClient::new(crate::router(&config, store, None, Readiness::default()))
+    }
+
+    /// The app running against the database in `OSHISMASH_TEST_DATABASE_URL`.
+    /// Tests that use it are `#[ignore]`d, and run with `cargo test --
+    /// --ignored` once the database is set up.
+    pub async fn postgres() -> TestClient {
+        let database_url = env::var(DATABASE_URL_ENV)
+            .unwrap_or_else(|_| panic!("{} has to be set", DATABASE_URL_ENV));
+        let config = config(&database_url, "/");
+        let db_handle = Arc::new(db::Handle::new(config.clone()).await.unwrap());
+        let store = store::Handle::new(db_handle.clone());
+
+        TestClient::new(crate::router(
+            &config,
+            store,
+            Some(db_handle),
+            Readiness::default(),
+        ))
+    }
+
+    fn new(app: Router) -> TestClient {
+        TestClient {
+            app,
+            cookies: BTreeMap::new(),
+        }
+    }
+
+    /// Decoded value of a cookie.
+    pub fn cookie(&self, name: &str) -> Option<&str> {
+        self.cookies.get(name).map(Cookie::value)
+    }
+
+    pub async fn get(&mut self, uri: &str) -> TestResponse {
+        let req = self.request("GET", uri).body(Body::empty()).unwrap();
+
+        self.send(req).await
+    }
+
+    /// Submits a form. Values aren't encoded, so they should be plain.
+    pub async fn post(&mut self, uri: &str, form: &[(&str, &str)]) -> TestResponse {
+        let body = form
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let req = self
+            .request("POST", uri)
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(body))
+            .unwrap();
+
+        self.send(req).await
+    }
+
+    /// Goes to where the response redirects to.
+    pub async fn follow(&mut self, res: TestResponse) -> TestResponse {
+        match res.location {
+            Some(location) => self.get(&location).await,
+            None => panic!("expected a redirect, got {:?}", res),
+        }
+    }
+
+    fn request(&self, method: &str, uri: &str) -> hyper::http::request::Builder {
+        let cookies = self
+            .cookies
+            .values()
+            .map(|cookie| cookie.encoded().stripped().to_string())
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(COOKIE, cookies)
+    }
+
+    async fn send(&mut self, req: Request<Body>) -> TestResponse {
+        let res = self.app.clone().oneshot(req).await.unwrap();
+
+        for value in res.headers().get_all(SET_COOKIE) {
+            let cookie = Cookie::parse_encoded(value.to_str().unwrap().to_string()).unwrap();
+
+            // Removed cookies are sent back already expired.
+            match cookie.max_age() {
+                Some(max_age) if max_age <= Duration::ZERO => {
+                    self.cookies.remove(cookie.name());
+                }
+                _ => {
+                    self.cookies.insert(cookie.name().to_string(), cookie);
+                }
+            }
+        }
+
+        let status = res.status();
+        let location = res.headers().get(LOCATION).map(|location| {
+            let uri = location.to_str().unwrap().parse::<Uri>().unwrap();
+
+            uri.path_and_query().unwrap().to_string()
+        });
+
+        let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+
+        TestResponse {
+            status,
+            location,
+            body: String::from_utf8(body.to_vec()).unwrap(),
+        }
+    }
+}
+
+fn config(database_url: &str, base_path: &str) -> AppConfig {
+    let mut layers = Layers::default();
+
+    layers.add_env(|var| match var {
+        "APP__HOST" => Some(String::from("localhost")),
+        "APP__BASE_PATH" => Some(base_path.to_string()),
+        "APP__STATIC_ASSETS" => Some(format!("{}/public", env!("CARGO_MANIFEST_DIR"))),
+        "DATABASE_URL" => Some(database_url.to_string()),
+        _ => None,
+    });
+
+    layers.build().unwrap()
+}
@@ -9,23 +9,117 @@ pub mod db;
 
 pub mod config;
 
+// Self-hosted VTuber images
+pub mod media;
+
+// In-process HTTP client for testing the whole app
+#[cfg(test)]
+mod harness;
+
 use axum::{routing, Router};
 use axum_extra::routing::SpaRouter;
 use std::{net::SocketAddr, sync::Arc};
 use tower::ServiceBuilder;
 use tower_http::add_extension::AddExtensionLayer;
 
-use oshismash_web::handlers;
+use oshismash::store;
+use oshismash_web::{error, handlers, i18n, shutdown};
 
 pub async fn run(config: config::AppConfig, db_handle: db::Handle) -> Result<(), hyper::Error> {
     let db_handle = Arc::new(db_handle);
-    let arc_config = Arc::new(config.clone());
+    let store = store::Handle::new(db_handle.clone());
+    let readiness = shutdown::Readiness::default();
+
+    let app = router(&config, store, Some(db_handle.clone()), readiness.clone());
+
+    let addr = SocketAddr::from((config.bind, config.port));
+
+    println!("Running on {}{}", addr, config.base_path);
+
+    let (stop_accepting, stopped_accepting) = tokio::sync::oneshot::channel::<()>();
+
+    let server = axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .with_graceful_shutdown(async {
+            stopped_accepting.await.ok();
+        });
+
+    tokio::pin!(server);
+
+    let result = tokio::select! {
+        result = &mut server => result,
+        _ = shutdown::signal() => {
+            // Readiness fails first so that no new requests are sent here.
+            // Load balancers only notice on their next check though, so new
+            // requests are still accepted for a bit. Only then is the listener
+            // closed, and the ones in flight (like votes) get to finish.
+            readiness.start_draining();
+
+            println!(
+                "Not ready anymore, still accepting for {}s",
+                config.shutdown_grace.as_secs()
+            );
 
+            match tokio::time::timeout(config.shutdown_grace, &mut server).await {
+                // The server only stops by itself if it failed.
+                Ok(result) => result,
+                Err(_) => {
+                    stop_accepting.send(()).ok();
+
+                    println!(
+                        "Draining connections (for up to {}s)",
+                        config.shutdown_timeout.as_secs()
+                    );
+
+                    match tokio::time::timeout(config.shutdown_timeout, &mut server).await {
+                        Ok(result) => result,
+                        Err(_) => {
+                            eprintln!("Connections took too long to drain, dropping the rest");
+                            Ok(())
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    db_handle.close();
+    println!("Database pool closed");
+
+    result
+}
+
+/// Builds the app without serving it, so that it can be merged into another
+/// service. Everything is under `config.base_path`. The pool isn't closed when
+/// the router is dropped, so that's up to the caller.
+pub fn app(config: &config::AppConfig, db_handle: Arc<db::Handle>) -> Router {
+    let store = store::Handle::new(db_handle.clone());
+
+    router(
+        config,
+        store,
+        Some(db_handle),
+        shutdown::Readiness::default(),
+    )
+}
+
+/// Like `app`, but with the store and readiness given. The pages of the voting
+/// flow only need `store`, while the rest (like the admin pages) also need
+/// `db_handle`, and fail without it.
+fn router(
+    config: &config::AppConfig,
+    store: store::Handle,
+    db_handle: Option<Arc<db::Handle>>,
+    readiness: shutdown::Readiness,
+) -> Router {
     // TODO: Add cookie stuff to middleware
     // TODO: Add rate limiter
     let middleware = ServiceBuilder::new()
-        .layer(AddExtensionLayer::new(db_handle))
-        .layer(AddExtensionLayer::new(arc_config));
+        .layer(AddExtensionLayer::new(store))
+        .layer(AddExtensionLayer::new(Arc::new(config.clone())))
+        .layer(AddExtensionLayer::new(readiness))
+        .layer(axum::middleware::from_fn(i18n::negotiate))
+        .layer(axum::middleware::from_fn(error::render));
 
     // TODO: Implement dynamic version of UI (JS)
     let app = Router::new()
@@ -33,24 +127,362 @@ pub async fn run(config: config::AppConfig, db_handle: db::Handle) -> Result<(),
         .route("/", routing::post(handlers::vote::vote))
         // .route("/api/vtuber/:vtuber_id", handlers::vtuber::results)
         // .route("/rpc/vote", routing::post(handlers::vote::rpc_vote))
-        .merge(SpaRouter::new("/assets", config.static_assets_path))
-        .route("/:vtuber_id", routing::get(handlers::vtuber::show_given_id))
-        .layer(middleware.into_inner());
+        .merge(SpaRouter::new("/assets", &config.static_assets_path))
+        .route("/healthz", routing::get(handlers::health::live))
+        .route("/readyz", routing::get(handlers::health::ready))
+        .route("/metrics", routing::get(handlers::metrics::show))
+        .route(
+            "/locale/:locale",
+            routing::get(handlers::locale::set_locale),
+        )
+        .route("/filters", routing::get(handlers::deck::show_filters))
+        .route("/shuffle", routing::post(handlers::deck::set_shuffle))
+        .route("/leaderboard", routing::get(handlers::leaderboard::show))
+        .route("/search", routing::get(handlers::search::show))
+        .route(
+            "/search/suggestions",
+            routing::get(handlers::search::suggestions),
+        )
+        .route("/media/:key/:file", routing::get(handlers::media::show))
+        .route(
+            "/suggest",
+            routing::get(handlers::suggestions::show_form).post(handlers::suggestions::submit),
+        )
+        .route(
+            "/admin/suggestions",
+            routing::get(handlers::admin::suggestions),
+        )
+        .route(
+            "/admin/suggestions/:suggestion_id/approve",
+            routing::post(handlers::admin::approve),
+        )
+        .route(
+            "/admin/suggestions/:suggestion_id/reject",
+            routing::post(handlers::admin::reject),
+        )
+        .route("/admin/reports", routing::get(handlers::admin::reports))
+        .route(
+            "/admin/reports/:report_id/resolve",
+            routing::post(handlers::admin::resolve_report),
+        )
+        .route(
+            "/admin/reports/:report_id/dismiss",
+            routing::post(handlers::admin::dismiss_report),
+        )
+        .route("/admin/vtubers", routing::get(handlers::admin::vtubers))
+        .route(
+            "/admin/vtubers/:vtuber_id/unhide",
+            routing::post(handlers::admin::unhide),
+        )
+        .route(
+            "/admin/vtubers/:vtuber_id/retire",
+            routing::post(handlers::admin::retire),
+        )
+        .route(
+            "/admin/vtubers/:vtuber_id/restore",
+            routing::post(handlers::admin::restore),
+        )
+        .route(
+            "/vtuber/:vtuber_id/details",
+            routing::get(handlers::vtuber::details),
+        )
+        .route(
+            "/vtuber/:vtuber_id/report",
+            routing::get(handlers::reports::show_form).post(handlers::reports::submit),
+        )
+        .route("/:vtuber_id", routing::get(handlers::vtuber::show_given_id));
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
+    let app = match db_handle {
+        Some(db_handle) => app.layer(AddExtensionLayer::new(db_handle)),
+        None => app,
+    };
 
-    println!("Running on port {}", config.port);
+    let app = app.layer(middleware.into_inner());
 
-    axum::Server::bind(&addr)
-        .serve(app.into_make_service())
-        .with_graceful_shutdown(signal_shutdown())
-        .await
+    if config.base_path.is_empty() {
+        app
+    } else {
+        // With the trailing `/`, the root is `/oshismash/` rather than
+        // `/oshismash`, which is where redirects to the root go.
+        Router::new().nest(&format!("{}/", config.base_path), app)
+    }
 }
 
-async fn signal_shutdown() {
-    tokio::signal::ctrl_c()
-        .await
-        .expect("expect tokio signal ctrl-c");
+#[cfg(test)]
+mod tests {
+    use hyper::StatusCode;
+
+    use crate::harness::TestClient;
+    use crate::oshismash::{store::memory::Listing, vtubers::Region};
+
+    fn deck() -> Vec<Listing> {
+        vec![
+            Listing::new(1, "nyatasha-nyanners"),
+            Listing {
+                region: Region::Jp,
+                ..Listing::new(2, "veibae")
+            },
+            Listing::new(3, "silvervale"),
+        ]
+    }
+
+    /// Votes for whoever's current until there's nobody left, like a guest
+    /// clicking through the deck. Gives back the VTubers that were voted for.
+    async fn vote_through_the_deck(client: &mut TestClient, query: &str) -> Vec<String> {
+        let mut voted = Vec::new();
+        let res = client.get(&format!("/{}", query)).await;
+        let mut res = client.follow(res).await;
+
+        while client.cookie("current") != Some("none") {
+            assert_eq!(res.status, StatusCode::OK);
+
+            let vtuber_id = client.cookie("current").unwrap().to_string();
+            let voted_res = client
+                .post(
+                    &format!("/{}", query),
+                    &[("vtuber_id", &vtuber_id), ("action", "smashed")],
+                )
+                .await;
+
+            assert_eq!(voted_res.status, StatusCode::FOUND);
+
+            voted.push(vtuber_id);
+            res = client.follow(voted_res).await;
+        }
+
+        assert_eq!(res.status, StatusCode::OK);
+        assert!(res.body.contains("You can touch grass now."));
+
+        voted
+    }
+
+    #[tokio::test]
+    async fn new_guests_start_at_the_first_vtuber() {
+        let mut client = TestClient::memory(deck());
+
+        let res = client.get("/").await;
+
+        assert_eq!(res.status, StatusCode::FOUND);
+        assert_eq!(res.location.as_deref(), Some("/nyatasha-nyanners"));
+        assert!(client.cookie("id").is_some());
+        assert_eq!(client.cookie("current"), Some("1"));
+        assert_eq!(client.cookie("last_visited"), Some("none"));
+
+        let res = client.follow(res).await;
+
+        assert_eq!(res.status, StatusCode::OK);
+        assert_eq!(client.cookie("voted"), Some(""));
+    }
+
+    #[tokio::test]
+    async fn old_numeric_links_redirect_to_slugs() {
+        let mut client = TestClient::memory(deck());
+
+        let res = client.get("/2").await;
+
+        assert_eq!(res.status, StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(res.location.as_deref(), Some("/veibae"));
+        assert_eq!(client.get("/nobody").await.status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn votes_move_guests_along_the_deck() {
+        let mut client = TestClient::memory(deck());
+        client.get("/").await;
+
+        let res = client
+            .post("/", &[("vtuber_id", "1"), ("action", "smashed")])
+            .await;
+
+        assert_eq!(res.status, StatusCode::FOUND);
+        assert_eq!(res.location.as_deref(), Some("/veibae"));
+        assert_eq!(client.cookie("voted"), Some("1"));
+        assert_eq!(client.cookie("current"), Some("2"));
+
+        // VTubers that the filter leaves out can't be voted for through it.
+        let res = client
+            .post("/?region=en", &[("vtuber_id", "2"), ("action", "passed")])
+            .await;
+
+        assert_eq!(res.status, StatusCode::FORBIDDEN);
+
+        // Without it, the vote moves along as usual.
+        let res = client
+            .post("/", &[("vtuber_id", "2"), ("action", "passed")])
+            .await;
+
+        assert_eq!(res.location.as_deref(), Some("/silvervale"));
+        assert_eq!(client.cookie("voted"), Some("1,2"));
+    }
+
+    #[tokio::test]
+    async fn guests_cant_skip_ahead() {
+        let mut client = TestClient::memory(deck());
+        client.get("/").await;
+
+        let res = client.get("/silvervale").await;
+
+        assert_eq!(res.status, StatusCode::FOUND);
+        assert_eq!(res.location.as_deref(), Some("/nyatasha-nyanners"));
+        assert_eq!(client.cookie("flash"), Some("vote_previous_first"));
+
+        let res = client.follow(res).await;
+
+        assert!(res
+            .body
+            .contains("You have to vote for the previous entries first."));
+        assert_eq!(client.cookie("flash"), None);
+
+        let res = client
+            .post("/", &[("vtuber_id", "3"), ("action", "smashed")])
+            .await;
+
+        assert_eq!(res.status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn changed_votes_are_confirmed() {
+        let mut client = TestClient::memory(deck());
+        client.get("/").await;
+
+        client
+            .post("/", &[("vtuber_id", "1"), ("action", "smashed")])
+            .await;
+        assert_eq!(client.cookie("flash"), None);
+
+        client
+            .post("/", &[("vtuber_id", "1"), ("action", "passed")])
+            .await;
+        assert_eq!(client.cookie("flash"), Some("vote_changed"));
+        assert_eq!(client.cookie("voted"), Some("1"));
+    }
+
+    #[tokio::test]
+    async fn results_of_vtubers_that_were_only_skipped_are_empty() {
+        let mut client = TestClient::memory(deck());
+        client.get("/").await;
+
+        let res = client
+            .post("/", &[("vtuber_id", "1"), ("action", "skipped")])
+            .await;
+        let res = client.follow(res).await;
+
+        assert_eq!(res.status, StatusCode::OK);
+        assert!(res.body.contains("1 didn't know them"));
+        assert!(res.body.contains("width: 0%;"));
+        assert!(!res.body.contains("NaN"));
+    }
+
+    #[tokio::test]
+    async fn guests_reach_the_end_of_the_deck() {
+        let mut client = TestClient::memory(deck());
+
+        assert_eq!(
+            vote_through_the_deck(&mut client, "").await,
+            ["1", "2", "3"]
+        );
+        assert_eq!(client.cookie("last_visited"), Some("3"));
+
+        // Coming back later shows the end of the deck again.
+        let res = client.get("/").await;
+
+        assert_eq!(res.status, StatusCode::OK);
+        assert!(res.body.contains("You can touch grass now."));
+    }
+
+    #[tokio::test]
+    async fn filtered_decks_end_early() {
+        let mut client = TestClient::memory(deck());
+
+        assert_eq!(
+            vote_through_the_deck(&mut client, "?region=en").await,
+            ["1", "3"]
+        );
+    }
+
+    #[tokio::test]
+    async fn routes_and_redirects_are_under_the_base_path() {
+        let mut client = TestClient::memory_at("/oshismash/", deck());
+
+        assert_eq!(client.get("/").await.status, StatusCode::NOT_FOUND);
+        assert_eq!(client.get("/healthz").await.status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            client.get("/oshismash/healthz").await.status,
+            StatusCode::OK
+        );
+
+        let res = client.get("/oshismash/").await;
+
+        assert_eq!(res.status, StatusCode::FOUND);
+        assert_eq!(
+            res.location.as_deref(),
+            Some("/oshismash/nyatasha-nyanners")
+        );
+        assert_eq!(client.follow(res).await.status, StatusCode::OK);
+
+        let res = client
+            .post("/oshismash/", &[("vtuber_id", "1"), ("action", "smashed")])
+            .await;
+
+        assert_eq!(res.location.as_deref(), Some("/oshismash/veibae"));
+        assert_eq!(
+            client.get("/oshismash/2").await.location.as_deref(),
+            Some("/oshismash/veibae")
+        );
+    }
+
+    #[tokio::test]
+    async fn links_are_under_the_base_path() {
+        let mut client = TestClient::memory_at("/oshismash", deck());
+
+        let res = client.get("/oshismash/").await;
+        let card = client.follow(res).await;
+        let not_found = client.get("/oshismash/nobody").await;
+
+        assert_eq!(card.status, StatusCode::OK);
+        assert_eq!(not_found.status, StatusCode::NOT_FOUND);
+
+        for res in [card, not_found] {
+            let links = [" href=\"", " src=\"", " action=\""]
+                .iter()
+                .flat_map(|attr| res.body.split(attr).skip(1))
+                .filter_map(|rest| rest.split('"').next())
+                .filter(|link| link.starts_with('/'))
+                .collect::<Vec<&str>>();
+
+            assert!(links.iter().any(|link| link.contains("/search")));
+
+            for link in links {
+                assert!(link.starts_with("/oshismash/"), "{}", link);
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a database in OSHISMASH_TEST_DATABASE_URL"]
+    async fn votes_for_nobody_are_refused_in_postgres() {
+        let mut client = TestClient::postgres().await;
+
+        client.get("/").await;
+
+        let res = client
+            .post("/", &[("vtuber_id", "999999999"), ("action", "smashed")])
+            .await;
+
+        assert_eq!(res.status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a database in OSHISMASH_TEST_DATABASE_URL"]
+    async fn guests_reach_the_end_of_the_deck_in_postgres() {
+        let mut client = TestClient::postgres().await;
+
+        let voted = vote_through_the_deck(&mut client, "").await;
 
-    println!("signal shutdown")
+        assert!(!voted.is_empty());
+        assert_eq!(
+            client.cookie("voted").map(|voted| voted.split(',').count()),
+            Some(voted.len())
+        );
+    }
 }
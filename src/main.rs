@@ -1,22 +1,67 @@
 #![forbid(unsafe_code)]
 
-use std::process;
+use std::{env, process};
 
-use oshismash::db;
-use oshismash::config::AppConfig;
+use oshismash::config::{self, AppConfig, Layers};
+use oshismash::{db, media};
+
+const USAGE: &str = "Usage:
+  oshismash [FLAGS]                                  Run the server
+  oshismash [FLAGS] config check                     Check the config, and show where each setting came from
+  oshismash [FLAGS] images sync                      Fetch and process missing VTuber images
+  oshismash [FLAGS] images import <VTUBER_ID> <FILE> Process an image for a VTuber from a file
+
+Flags (each one overrides its env var, which overrides the config file):";
 
 #[tokio::main]
 async fn main() {
-    println!("Loading configuration from environment");
-    let config = AppConfig::from_env().unwrap_or_else(|err| {
-        eprintln!("Application config error: {}", err);
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let (layers, args) = Layers::collect(&args).unwrap_or_else(|errors| {
+        eprintln!(
+            "Invalid arguments:\n{}\n\n{}\n{}",
+            errors,
+            USAGE,
+            config::flags_help()
+        );
+        process::exit(2);
+    });
+
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let checking_config = args == ["config", "check"];
+
+    // Where every setting came from is only for someone looking, rather than
+    // for every log of every start.
+    if checking_config {
+        println!("Configuration:\n{}", layers.describe());
+    }
+
+    let config = layers.build().unwrap_or_else(|errors| {
+        eprintln!("Application config errors:\n{}", errors);
         process::exit(1);
     });
 
+    if checking_config {
+        println!("Configuration is valid");
+        return;
+    }
+
     println!("Attempting to establish a database connection");
-    match db::Handle::new(config.clone()).await {
+    let db_handle = match db::Handle::new(config.clone()).await {
         Ok(db_handle) => {
             eprintln!("Database connection established");
+            db_handle
+        }
+
+        Err(e) => {
+            eprintln!("Database error: {:#?}", e);
+            process::exit(1);
+        }
+    };
+
+    match args.as_slice() {
+        [] => {
             // https://docs.rs/axum/0.4.8/axum/extract/struct.Extension.html
             if let Err(e) = oshismash::run(config, db_handle).await {
                 eprintln!("Application error: {}", e);
@@ -24,9 +69,72 @@ async fn main() {
             }
         }
 
+        ["images", "sync"] => sync_images(&config, &db_handle).await,
+
+        ["images", "import", vtuber_id, path] => {
+            let vtuber_id = vtuber_id.parse::<i64>().unwrap_or_else(|_| {
+                eprintln!("Not a VTuber ID: {}", vtuber_id);
+                process::exit(2);
+            });
+
+            import_image(&config, &db_handle, vtuber_id, path).await
+        }
+
+        _ => {
+            eprintln!("{}\n{}", USAGE, config::flags_help());
+            process::exit(2);
+        }
+    }
+}
+
+async fn sync_images(config: &AppConfig, db_handle: &db::Handle) {
+    let client = db_handle.client().await.unwrap_or_else(|e| {
+        eprintln!("Database error: {}", e);
+        process::exit(1);
+    });
+
+    let store = media::Store::new(&config.media_path);
+
+    let results = media::sync(&client, &store).await.unwrap_or_else(|e| {
+        eprintln!("Image sync error: {}", e);
+        process::exit(1);
+    });
+
+    let mut failed = 0;
+
+    for (vtuber_id, result) in results {
+        match result {
+            Ok(key) => println!("VTuber {}: {}", vtuber_id, key),
+            Err(e) => {
+                failed += 1;
+                eprintln!("VTuber {}: {}", vtuber_id, e);
+            }
+        }
+    }
+
+    if failed > 0 {
+        process::exit(1);
+    }
+}
+
+async fn import_image(config: &AppConfig, db_handle: &db::Handle, vtuber_id: i64, path: &str) {
+    let source = std::fs::read(path).unwrap_or_else(|e| {
+        eprintln!("Couldn't read {}: {}", path, e);
+        process::exit(1);
+    });
+
+    let client = db_handle.client().await.unwrap_or_else(|e| {
+        eprintln!("Database error: {}", e);
+        process::exit(1);
+    });
+
+    let store = media::Store::new(&config.media_path);
+
+    match media::ingest(&client, &store, vtuber_id, source).await {
+        Ok(key) => println!("VTuber {}: {}", vtuber_id, key),
         Err(e) => {
-            eprintln!("Database error: {:#?}", e);
+            eprintln!("VTuber {}: {}", vtuber_id, e);
             process::exit(1);
         }
-    };
+    }
 }
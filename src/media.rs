@@ -0,0 +1,444 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use deadpool_postgres::Object;
+use image::{imageops::FilterType, RgbaImage};
+use rgb::FromSlice;
+use sha2::{Digest, Sha256};
+use tokio_postgres::types::Type;
+
+/// Widths that every image is resized to. Images are never scaled up, so the
+/// variants at or past a small source's width are all just the source.
+pub const WIDTHS: [u32; 3] = [160, 480, 960];
+
+/// Same scale as the AVIF quality. The variants are photos, so lossless WebP is
+/// several times bigger for nothing that can be seen.
+const WEBP_QUALITY: f32 = 75.0;
+
+/// Sources bigger than this are rejected before they're decoded.
+const MAX_SOURCE_BYTES: usize = 10 * 1024 * 1024;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("failed to fetch the source image ({0})")]
+    FetchFailed(reqwest::Error),
+    #[error("the source image is bigger than {} bytes", MAX_SOURCE_BYTES)]
+    TooLarge,
+    #[error("couldn't decode the source image ({0})")]
+    DecodeFailed(image::ImageError),
+    #[error("couldn't encode a variant ({0})")]
+    EncodeFailed(String),
+    #[error("couldn't write to the media storage ({0})")]
+    StorageFailed(io::Error),
+    #[error("failed to perform the DB query ({0})")]
+    QueryFailed(tokio_postgres::Error),
+    #[error("no such VTuber")]
+    VTuberNotFound,
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        Error::FetchFailed(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::StorageFailed(e)
+    }
+}
+
+impl From<tokio_postgres::Error> for Error {
+    fn from(e: tokio_postgres::Error) -> Self {
+        Error::QueryFailed(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Avif,
+    Webp,
+}
+
+impl Format {
+    pub const ALL: [Format; 2] = [Format::Avif, Format::Webp];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Avif => "avif",
+            Format::Webp => "webp",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Format::Avif => "image/avif",
+            Format::Webp => "image/webp",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<Format> {
+        Format::ALL
+            .into_iter()
+            .find(|format| format.extension() == extension)
+    }
+}
+
+/// A resized copy of an image. It's stored as `{key}/{width}.{extension}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Variant {
+    pub width: u32,
+    pub format: Format,
+}
+
+impl Variant {
+    pub fn file_name(&self) -> String {
+        format!("{}.{}", self.width, self.format.extension())
+    }
+
+    /// Parses a file name like `480.webp`. Only the widths in `WIDTHS` are
+    /// valid, so this doubles as a check for what can be served.
+    pub fn from_file_name(file_name: &str) -> Option<Variant> {
+        let (width, extension) = file_name.split_once('.')?;
+        let width = width.parse::<u32>().ok()?;
+
+        if WIDTHS.contains(&width) {
+            Some(Variant {
+                width,
+                format: Format::from_extension(extension)?,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Images are stored by the hash of their source, so a key's files never
+/// change. The first 128 bits of the SHA-256 are plenty for this. The width of
+/// the source comes after it (e.g `{hash}-300`), so that pages know which
+/// variants really are narrower than the others.
+pub fn key_for(source: &[u8], width: u32) -> String {
+    let hash = Sha256::digest(source)[..16]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+
+    format!("{}-{}", hash, width)
+}
+
+pub fn is_valid_key(key: &str) -> bool {
+    match key.split_once('-') {
+        Some((hash, width)) => {
+            hash.len() == 32
+                && hash
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+                && width.chars().all(|c| c.is_ascii_digit())
+                && source_width(key).is_some()
+        }
+        None => false,
+    }
+}
+
+/// Width of the source image, from its key.
+pub fn source_width(key: &str) -> Option<u32> {
+    let (_, width) = key.split_once('-')?;
+
+    width.parse::<u32>().ok().filter(|width| *width > 0)
+}
+
+/// Real widths of an image's variants, next to the width in `WIDTHS` that
+/// they're stored as. Variants at or past the source's width are all the same
+/// unscaled copy, so only the first of them is given, with the source's width.
+pub fn real_widths(key: &str) -> Vec<(u32, u32)> {
+    let source_width = source_width(key).unwrap_or(u32::MAX);
+    let mut widths = Vec::new();
+
+    for width in WIDTHS {
+        widths.push((width, width.min(source_width)));
+
+        if width >= source_width {
+            break;
+        }
+    }
+
+    widths
+}
+
+/// Encoded variants of an image, and the width of its source for the key.
+#[derive(Debug)]
+pub struct Processed {
+    pub width: u32,
+    pub variants: Vec<(Variant, Vec<u8>)>,
+}
+
+/// Decodes the source, and encodes every variant of it. This is CPU heavy
+/// (especially AVIF), so it should be run with `spawn_blocking`.
+pub fn process(source: &[u8]) -> Result<Processed, Error> {
+    let image = image::load_from_memory(source).map_err(Error::DecodeFailed)?;
+    let mut variants = Vec::new();
+    let mut unscaled = None;
+
+    for width in WIDTHS {
+        let encoded = if width < image.width() {
+            encode(
+                &image
+                    .resize(width, u32::MAX, FilterType::Lanczos3)
+                    .to_rgba8(),
+            )?
+        } else {
+            // Every variant from here on is the same unscaled copy, so it's only
+            // encoded once. They're all still stored, so that any width in
+            // `WIDTHS` can be linked to.
+            match &unscaled {
+                Some(encoded) => Clone::clone(encoded),
+                None => unscaled.insert(encode(&image.to_rgba8())?).clone(),
+            }
+        };
+
+        for (format, bytes) in encoded {
+            variants.push((Variant { width, format }, bytes));
+        }
+    }
+
+    Ok(Processed {
+        width: image.width(),
+        variants,
+    })
+}
+
+fn encode(pixels: &RgbaImage) -> Result<[(Format, Vec<u8>); 2], Error> {
+    Ok([
+        (Format::Avif, encode_avif(pixels)?),
+        (Format::Webp, encode_webp(pixels)),
+    ])
+}
+
+fn encode_avif(pixels: &RgbaImage) -> Result<Vec<u8>, Error> {
+    let image = ravif::Img::new(
+        pixels.as_raw().as_rgba(),
+        pixels.width() as usize,
+        pixels.height() as usize,
+    );
+
+    ravif::Encoder::new()
+        .with_quality(70.0)
+        .with_speed(6)
+        .encode_rgba(image)
+        .map(|encoded| encoded.avif_file)
+        .map_err(|e| Error::EncodeFailed(e.to_string()))
+}
+
+fn encode_webp(pixels: &RgbaImage) -> Vec<u8> {
+    webp::Encoder::from_rgba(pixels.as_raw(), pixels.width(), pixels.height())
+        .encode(WEBP_QUALITY)
+        .to_vec()
+}
+
+/// Local storage for the processed images.
+#[derive(Debug, Clone)]
+pub struct Store {
+    root: PathBuf,
+}
+
+impl Store {
+    pub fn new(root: impl AsRef<Path>) -> Store {
+        Store {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn path(&self, key: &str, variant: Variant) -> PathBuf {
+        self.root.join(key).join(variant.file_name())
+    }
+
+    /// Writes the variants of an image. Each one is written to a temporary file
+    /// first, so a half-written variant is never served.
+    pub async fn save(&self, key: &str, variants: &[(Variant, Vec<u8>)]) -> Result<(), Error> {
+        tokio::fs::create_dir_all(self.root.join(key)).await?;
+
+        for (variant, bytes) in variants {
+            let path = self.path(key, *variant);
+            let tmp_path = path.with_extension("tmp");
+
+            tokio::fs::write(&tmp_path, bytes).await?;
+            tokio::fs::rename(&tmp_path, &path).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn read(&self, key: &str, variant: Variant) -> io::Result<Vec<u8>> {
+        tokio::fs::read(self.path(key, variant)).await
+    }
+}
+
+/// Downloads a source image. Hosts that are slow or send something huge are
+/// given up on.
+pub async fn fetch(url: &str) -> Result<Vec<u8>, Error> {
+    let client = reqwest::Client::builder().timeout(FETCH_TIMEOUT).build()?;
+    let mut response = client.get(url).send().await?.error_for_status()?;
+
+    if response
+        .content_length()
+        .map_or(false, |length| length > MAX_SOURCE_BYTES as u64)
+    {
+        return Err(Error::TooLarge);
+    }
+
+    let mut bytes = Vec::new();
+
+    while let Some(chunk) = response.chunk().await? {
+        bytes.extend_from_slice(&chunk);
+
+        if bytes.len() > MAX_SOURCE_BYTES {
+            return Err(Error::TooLarge);
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Processes a source image, stores its variants, and points the VTuber to
+/// them. Gives back the image's key.
+pub async fn ingest(
+    client: &Object,
+    store: &Store,
+    vtuber_id: i64,
+    source: Vec<u8>,
+) -> Result<String, Error> {
+    if source.len() > MAX_SOURCE_BYTES {
+        return Err(Error::TooLarge);
+    }
+
+    let (key, processed) = tokio::task::spawn_blocking(move || {
+        process(&source).map(|processed| (key_for(&source, processed.width), processed))
+    })
+    .await
+    .map_err(|e| Error::EncodeFailed(e.to_string()))??;
+
+    store.save(&key, &processed.variants).await?;
+
+    let statement = client
+        .prepare_typed_cached(
+            "UPDATE app.vtubers SET img = $2 WHERE vtuber_id = $1",
+            &[Type::INT8, Type::TEXT],
+        )
+        .await?;
+
+    match client.execute(&statement, &[&vtuber_id, &key]).await? {
+        0 => Err(Error::VTuberNotFound),
+        _ => Ok(key),
+    }
+}
+
+/// Fetches and processes the source images of every VTuber that doesn't have
+/// one stored yet. Each VTuber gets their own result so that one broken host
+/// doesn't stop the rest.
+pub async fn sync(
+    client: &Object,
+    store: &Store,
+) -> Result<Vec<(i64, Result<String, Error>)>, Error> {
+    let statement = client
+        .prepare_typed_cached(
+            "SELECT vtuber_id, img_source
+               FROM app.vtubers
+               WHERE img IS NULL
+                 AND img_source IS NOT NULL
+               ORDER BY vtuber_id",
+            &[],
+        )
+        .await?;
+
+    let mut results = Vec::new();
+
+    for row in client.query(&statement, &[]).await? {
+        let vtuber_id: i64 = row.get("vtuber_id");
+        let source_url: String = row.get("img_source");
+
+        let result = match fetch(&source_url).await {
+            Ok(source) => ingest(client, store, vtuber_id, source).await,
+            Err(e) => Err(e),
+        };
+
+        results.push((vtuber_id, result));
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_valid_key, key_for, process, real_widths, Format, Processed, Variant, WIDTHS};
+
+    #[test]
+    fn variants_are_only_the_known_widths_and_formats() {
+        assert_eq!(
+            Variant::from_file_name("480.webp"),
+            Some(Variant {
+                width: 480,
+                format: Format::Webp,
+            })
+        );
+
+        assert_eq!(Variant::from_file_name("481.webp"), None);
+        assert_eq!(Variant::from_file_name("480.png"), None);
+        assert_eq!(Variant::from_file_name("../480.avif"), None);
+    }
+
+    #[test]
+    fn keys_are_hex_hashes_with_a_width() {
+        let key = key_for(b"nyanners.png", 300);
+
+        assert!(is_valid_key(&key));
+        assert!(key.ends_with("-300"));
+        assert_eq!(key, key_for(b"nyanners.png", 300));
+        assert!(!is_valid_key("../../etc/passwd"));
+        assert!(!is_valid_key(&key.to_uppercase()));
+        assert!(!is_valid_key(&key.replace("-300", "-+300")));
+        assert!(!is_valid_key(&key.replace("-300", "-0")));
+        assert!(!is_valid_key(&key.replace("-300", "")));
+    }
+
+    #[test]
+    fn only_real_widths_are_given() {
+        let key = |width| key_for(b"nyanners.png", width);
+
+        assert_eq!(
+            real_widths(&key(2000)),
+            [(160, 160), (480, 480), (960, 960)]
+        );
+        assert_eq!(real_widths(&key(960)), [(160, 160), (480, 480), (960, 960)]);
+        assert_eq!(real_widths(&key(300)), [(160, 160), (480, 300)]);
+        assert_eq!(real_widths(&key(100)), [(160, 100)]);
+    }
+
+    #[test]
+    fn small_sources_are_not_scaled_up() {
+        let mut source = Vec::new();
+
+        image::DynamicImage::new_rgba8(200, 100)
+            .write_to(
+                &mut std::io::Cursor::new(&mut source),
+                image::ImageOutputFormat::Png,
+            )
+            .unwrap();
+
+        let Processed { width, variants } = process(&source).unwrap();
+
+        assert_eq!(width, 200);
+        assert_eq!(variants.len(), WIDTHS.len() * Format::ALL.len());
+
+        // Only WebP can be decoded back, but that's enough to see the sizes.
+        for (variant, bytes) in variants.iter().filter(|(v, _)| v.format == Format::Webp) {
+            let decoded = image::load_from_memory(bytes).unwrap();
+
+            assert_eq!(decoded.width(), variant.width.min(200));
+        }
+    }
+}
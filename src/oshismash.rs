@@ -1,13 +1,16 @@
+pub(crate) mod deck;
 pub(crate) mod guests;
+pub(crate) mod leaderboard;
+pub(crate) mod locale;
+pub(crate) mod reports;
+pub(crate) mod search;
+pub(crate) mod store;
+pub(crate) mod suggestions;
 pub(crate) mod vote;
 pub(crate) mod vtubers;
 
-use axum::{
-    extract::rejection::{ExtensionRejection, FormRejection},
-    response::{IntoResponse, Response},
-};
+use axum::extract::rejection::{ExtensionRejection, FormRejection};
 use deadpool_postgres::PoolError;
-use hyper::StatusCode;
 
 /// All (or most) of the possible errors that can happen in Oshi Smash.
 #[derive(thiserror::Error, Debug)]
@@ -29,14 +32,10 @@ pub enum Error {
 
     #[error("couldn't find the DB handle extension")]
     MissingDbHandleExtension,
-    #[error("need to vote for previous vtubers")]
-    MaxVisitedIsLessThanCurrent,
     #[error("invalid form")]
     InvalidForm(FormRejection),
     #[error("not the expected form format. e.g expected an object but got a string")]
     InvalidFormFormat,
-    #[error("couldn't find the extension")]
-    MissingExtension,
 
     // TODO: Choose one only
     #[error("couldn't parse into a `Stack` ({0})")]
@@ -47,6 +46,26 @@ pub enum Error {
     InvalidClientData,
     #[error("ya banned from voting")]
     NotAllowedToVote,
+    #[error("no VTubers match the filter")]
+    EmptyDeck,
+    #[error("no such VTuber")]
+    VTuberNotFound,
+    #[error("only moderators can do this")]
+    NotAdmin,
+    #[error("the request didn't come from one of the app's pages")]
+    CrossSiteRequest,
+    #[error("too many suggestions were made today")]
+    TooManySuggestions,
+    #[error("invalid suggestion ({0})")]
+    InvalidSuggestion(suggestions::ParseError),
+    #[error("no such pending suggestion")]
+    SuggestionNotFound,
+    #[error("the VTuber was already reported, and it hasn't been looked at yet")]
+    AlreadyReported,
+    #[error("invalid report ({0})")]
+    InvalidReport(reports::ParseError),
+    #[error("no such open report")]
+    ReportNotFound,
 }
 
 impl From<tokio_postgres::Error> for Error {
@@ -91,81 +110,20 @@ impl From<serde_json::Error> for Error {
     }
 }
 
-impl From<ExtensionRejection> for Error {
-    fn from(_: ExtensionRejection) -> Self {
-        Error::MissingDbHandleExtension
+impl From<suggestions::ParseError> for Error {
+    fn from(e: suggestions::ParseError) -> Self {
+        Error::InvalidSuggestion(e)
     }
 }
 
-// TODO: Move out to `oshismash_web`
-impl IntoResponse for Error {
-    fn into_response(self: Error) -> Response {
-        match self {
-            Error::UnableToQuery(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "E001: Failed when attempting to query the database",
-            ),
-            Error::FailedToSetupDb(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "E002: Failed to setup database connection",
-            ),
-            Error::InvalidGuest => (StatusCode::UNAUTHORIZED, "E003: Not allowed to access this"),
-            Error::PoolError(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "E004: Failed to get client from DB pool",
-            ),
-            Error::VoteParseError(e) => {
-                println!("{}", e);
-                (
-                    StatusCode::UNPROCESSABLE_ENTITY,
-                    "E005: Failed to parse vote entry",
-                )
-            }
-            Error::FailedToParseStack(_e) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "E006: Failed to parse card stack",
-            ),
-            Error::StackParseFailed => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "E006: Failed to parse card stack",
-            ),
-            Error::MissingDbHandleExtension => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "E007: Missing DB handle extension",
-            ),
-            Error::InvalidClientData => (
-                StatusCode::UNPROCESSABLE_ENTITY,
-                "E008: You can only vote for a VTuber that's currently displayed",
-            ),
-            Error::NotAllowedToVote => (
-                StatusCode::FORBIDDEN,
-                "E009: You have to vote for the previous entries first.",
-            ),
-            Error::MaxVisitedIsLessThanCurrent => (
-                StatusCode::FORBIDDEN,
-                "E010: You have to vote for the previous entries first.",
-            ),
-            Error::InvalidForm(_) => (StatusCode::BAD_REQUEST, "E11: Form data is not valid"),
-            Error::MissingExtension => todo!(),
-            Error::InvalidFormFormat => (
-                StatusCode::UNPROCESSABLE_ENTITY,
-                "E12: Form data is not of the expected format.",
-            ),
-            Error::VoteError(e) => match e {
-                vote::VoteError::QueryPrepFailed(_) => (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "E13: Vote was not counted. Something went wrong in the server.",
-                ),
-                vote::VoteError::QueryFailed(_) => (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "E13: Vote was not counted. Something went wrong in the server.",
-                ),
-                vote::VoteError::InvalidDbValue => (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "E14: Vote was counted but something went wrong while handling the DB result.",
-                ),
-            },
-        }
-        .into_response()
+impl From<reports::ParseError> for Error {
+    fn from(e: reports::ParseError) -> Self {
+        Error::InvalidReport(e)
+    }
+}
+
+impl From<ExtensionRejection> for Error {
+    fn from(_: ExtensionRejection) -> Self {
+        Error::MissingDbHandleExtension
     }
 }
@@ -0,0 +1,361 @@
+use deadpool_postgres::Object;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio_postgres::{types::Type, Row};
+
+use super::vtubers::Region;
+use crate::oshismash;
+
+/// Narrows down the deck to the VTubers of an org, group, and/or region. Fields
+/// that are `None` aren't filtered by. Mirrors `app.DECK_FILTER` in the DB.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub struct DeckFilter {
+    pub org_id: Option<i32>,
+    pub group_id: Option<i32>,
+    pub region: Option<Region>,
+}
+
+impl DeckFilter {
+    /// Builds a filter from the raw values of the `org`, `group`, and `region`
+    /// query parameters. Empty or malformed values are ignored rather than
+    /// rejected since they come from shared links.
+    pub fn from_params(org: Option<&str>, group: Option<&str>, region: Option<&str>) -> Self {
+        DeckFilter {
+            org_id: org.and_then(|org| org.trim().parse().ok()),
+            group_id: group.and_then(|group| group.trim().parse().ok()),
+            region: region.and_then(Region::from_code),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.org_id.is_none() && self.group_id.is_none() && self.region.is_none()
+    }
+
+    /// Encodes the filter as a query string, without the leading `?`. It's
+    /// empty if nothing is being filtered.
+    pub fn to_query(&self) -> String {
+        let mut params = Vec::new();
+
+        if let Some(org_id) = self.org_id {
+            params.push(format!("org={}", org_id));
+        }
+
+        if let Some(group_id) = self.group_id {
+            params.push(format!("group={}", group_id));
+        }
+
+        if let Some(region) = &self.region {
+            params.push(format!("region={}", region.code()));
+        }
+
+        params.join("&")
+    }
+
+    /// Appends the filter to a path, e.g `/12` becomes `/12?org=2`.
+    pub fn link(&self, path: &str) -> String {
+        if self.is_empty() {
+            path.to_string()
+        } else {
+            format!("{}?{}", path, self.to_query())
+        }
+    }
+}
+
+/// Whether a guest can see a VTuber in a filtered deck.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Allowed,
+    /// The VTuber doesn't match the filter.
+    NotInDeck {
+        resume_at: i64,
+    },
+    /// The guest hasn't voted for the VTubers before this one yet.
+    Locked {
+        resume_at: i64,
+    },
+    /// Nobody matches the filter.
+    EmptyDeck,
+}
+
+/// Checks if a guest can see a VTuber given the filter. Guests can only go as
+/// far as the first VTuber in the deck that they haven't voted for yet.
+pub async fn get_access(
+    client: &Object,
+    vtuber_id: i64,
+    guest_id: &str,
+    filter: &DeckFilter,
+) -> Result<Access, oshismash::Error> {
+    let row = query_access(client, Some(vtuber_id), guest_id, filter).await?;
+
+    let in_deck: bool = row.get("in_deck");
+    let reachable: bool = row.get("reachable");
+    let resume_at: Option<i64> = row.get("resume_at");
+
+    let access = match (resume_at, in_deck, reachable) {
+        (None, _, _) => Access::EmptyDeck,
+        (Some(resume_at), false, _) => Access::NotInDeck { resume_at },
+        (Some(resume_at), true, false) => Access::Locked { resume_at },
+        (Some(_), true, true) => Access::Allowed,
+    };
+
+    Ok(access)
+}
+
+/// Gets the VTuber where the guest should continue from in the filtered deck.
+/// It's `None` if nobody matches the filter.
+pub async fn get_resume_point(
+    client: &Object,
+    guest_id: &str,
+    filter: &DeckFilter,
+) -> Result<Option<i64>, oshismash::Error> {
+    let row = query_access(client, None, guest_id, filter).await?;
+
+    Ok(row.get("resume_at"))
+}
+
+async fn query_access(
+    client: &Object,
+    vtuber_id: Option<i64>,
+    guest_id: &str,
+    filter: &DeckFilter,
+) -> Result<Row, oshismash::Error> {
+    let statement = "
+        SELECT *
+          FROM app.get_deck_access(
+            $1::BIGINT,
+            $2::UUID,
+            ROW($3::INTEGER, $4::INTEGER, $5::app.REGION)::app.DECK_FILTER
+          )
+    ";
+
+    let statement = client
+        .prepare_typed_cached(
+            statement,
+            &[Type::INT8, Type::TEXT, Type::INT4, Type::INT4, Type::TEXT],
+        )
+        .await?;
+
+    let row = client
+        .query_one(
+            &statement,
+            &[
+                &vtuber_id,
+                &guest_id,
+                &filter.org_id,
+                &filter.group_id,
+                &filter.region.as_ref().map(Region::code),
+            ],
+        )
+        .await?;
+
+    Ok(row)
+}
+
+/// Turns the shuffled deck on or off for a guest. Each guest gets their own
+/// order, which stays the same for as long as it's on.
+pub async fn set_shuffle(
+    client: &Object,
+    guest_id: &str,
+    shuffled: bool,
+) -> Result<(), oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached(
+            "SELECT app.set_deck_shuffle($1::UUID, $2)",
+            &[Type::TEXT, Type::BOOL],
+        )
+        .await?;
+
+    client.execute(&statement, &[&guest_id, &shuffled]).await?;
+
+    Ok(())
+}
+
+pub async fn is_shuffled(client: &Object, guest_id: &str) -> Result<bool, oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached(
+            "SELECT exists(
+                SELECT *
+                  FROM app.guests
+                  WHERE guest_id = $1::UUID
+                    AND deck_seed IS NOT NULL
+            )",
+            &[Type::TEXT],
+        )
+        .await?;
+
+    let is_shuffled: bool = client
+        .query_one(&statement, &[&guest_id])
+        .await?
+        .get("exists");
+
+    Ok(is_shuffled)
+}
+
+/// A VTuber in the deck, in the usual order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeckEntry {
+    pub vtuber_id: i64,
+    pub name: String,
+}
+
+/// Gets the whole deck in the usual order, unfiltered and unshuffled.
+pub async fn get_order(client: &Object) -> Result<Vec<DeckEntry>, oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached(
+            "SELECT deck.vtuber_id, vtubers.name
+               FROM app.deck AS deck
+               JOIN app.vtubers AS vtubers
+                 ON deck.vtuber_id = vtubers.vtuber_id
+               ORDER BY deck.position",
+            &[],
+        )
+        .await?;
+
+    let entries = client
+        .query(&statement, &[])
+        .await?
+        .into_iter()
+        .map(|row| DeckEntry {
+            vtuber_id: row.get("vtuber_id"),
+            name: row.get("name"),
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+/// A VTuber that was taken out of the chain. Their votes (and so their spot
+/// on the leaderboard) are kept.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RetiredVTuber {
+    pub vtuber_id: i64,
+    pub name: String,
+    pub slug: String,
+    pub retired_at: String,
+    /// Who they came right after, if that VTuber is still in the chain. This
+    /// is where they go back to by default.
+    pub retired_after: Option<i64>,
+}
+
+/// Gets the retired VTubers, most recently retired first.
+pub async fn get_retired(client: &Object) -> Result<Vec<RetiredVTuber>, oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached("SELECT * FROM app.get_retired_vtubers()", &[])
+        .await?;
+
+    let value: Value = client
+        .query_one(&statement, &[])
+        .await?
+        .get("get_retired_vtubers");
+
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Takes a VTuber out of the chain, and links their neighbors to each other.
+pub async fn retire(client: &Object, vtuber_id: i64) -> Result<(), oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached("SELECT app.retire_vtuber($1)", &[Type::INT8])
+        .await?;
+
+    let retired: bool = client
+        .query_one(&statement, &[&vtuber_id])
+        .await?
+        .get("retire_vtuber");
+
+    if retired {
+        Ok(())
+    } else {
+        Err(oshismash::Error::VTuberNotFound)
+    }
+}
+
+/// Puts a retired VTuber back into the chain, right after `after`. `None` puts
+/// them first.
+pub async fn restore(
+    client: &Object,
+    vtuber_id: i64,
+    after: Option<i64>,
+) -> Result<(), oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached(
+            "SELECT app.restore_vtuber($1, $2)",
+            &[Type::INT8, Type::INT8],
+        )
+        .await?;
+
+    let restored: bool = client
+        .query_one(&statement, &[&vtuber_id, &after])
+        .await?
+        .get("restore_vtuber");
+
+    if restored {
+        Ok(())
+    } else {
+        Err(oshismash::Error::VTuberNotFound)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GroupOption {
+    pub group_id: i32,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OrgOption {
+    pub org_id: i32,
+    pub name: String,
+    pub groups: Vec<GroupOption>,
+}
+
+/// Everything that the deck can be filtered by.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DeckOptions {
+    pub orgs: Vec<OrgOption>,
+    pub regions: Vec<Region>,
+}
+
+pub async fn get_options(client: &Object) -> Result<DeckOptions, oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached("SELECT * FROM app.get_deck_options()", &[])
+        .await?;
+
+    let value: Value = client
+        .query_one(&statement, &[])
+        .await?
+        .get("get_deck_options");
+
+    Ok(serde_json::from_value(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DeckFilter;
+    use crate::oshismash::vtubers::Region;
+
+    #[test]
+    fn filter_ignores_empty_and_malformed_params() {
+        assert_eq!(
+            DeckFilter::from_params(Some("2"), Some(""), Some("en")),
+            DeckFilter {
+                org_id: Some(2),
+                group_id: None,
+                region: Some(Region::En),
+            }
+        );
+
+        assert!(DeckFilter::from_params(Some("abc"), None, Some("mars")).is_empty());
+    }
+
+    #[test]
+    fn filter_links_keep_the_query() {
+        let filter = DeckFilter {
+            org_id: Some(1),
+            group_id: Some(4),
+            region: Some(Region::Jp),
+        };
+
+        assert_eq!(filter.link("/12"), "/12?org=1&group=4&region=jp");
+        assert_eq!(DeckFilter::default().link("/12"), "/12");
+    }
+}
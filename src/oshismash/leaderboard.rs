@@ -0,0 +1,98 @@
+use deadpool_postgres::Object;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio_postgres::types::Type;
+
+use super::{locale::Locale, vtubers::Org};
+use crate::oshismash;
+
+/// A VTuber's standing in the leaderboard.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Entry {
+    pub vtuber_id: i64,
+    pub name: String,
+    pub img: Option<String>,
+    pub org_name: Org,
+    pub smashes: i64,
+    pub passes: i64,
+    pub skips: i64,
+}
+
+impl Entry {
+    /// Share of smashes out of the smashes and passes. Skips don't count since
+    /// the guests that skipped don't know the VTuber.
+    pub fn smash_ratio(&self) -> Option<f64> {
+        ratio(self.smashes, self.smashes + self.passes)
+    }
+
+    /// How well known the VTuber is, which is the share of votes that weren't
+    /// skips.
+    pub fn awareness(&self) -> Option<f64> {
+        ratio(
+            self.smashes + self.passes,
+            self.smashes + self.passes + self.skips,
+        )
+    }
+}
+
+fn ratio(numerator: i64, denominator: i64) -> Option<f64> {
+    if denominator == 0 {
+        None
+    } else {
+        Some(numerator as f64 / denominator as f64)
+    }
+}
+
+/// Gets every VTuber, ranked by their smash ratio.
+pub async fn get_leaderboard(
+    client: &Object,
+    locale: Locale,
+) -> Result<Vec<Entry>, oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached(
+            "SELECT * FROM app.get_leaderboard($1::app.LOCALE)",
+            &[Type::TEXT],
+        )
+        .await?;
+
+    let value: Value = client
+        .query_one(&statement, &[&locale.code()])
+        .await?
+        .get("get_leaderboard");
+
+    Ok(serde_json::from_value(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Entry;
+    use crate::oshismash::vtubers::Org;
+
+    fn entry(smashes: i64, passes: i64, skips: i64) -> Entry {
+        Entry {
+            vtuber_id: 1,
+            name: "Nyatasha Nyanners".to_string(),
+            img: None,
+            org_name: Org::Vshojo,
+            smashes,
+            passes,
+            skips,
+        }
+    }
+
+    #[test]
+    fn skips_only_affect_awareness() {
+        let found = entry(3, 1, 4);
+
+        assert_eq!(found.smash_ratio(), Some(0.75));
+        assert_eq!(found.awareness(), Some(0.5));
+    }
+
+    #[test]
+    fn no_votes_has_no_ratios() {
+        assert_eq!(entry(0, 0, 0).smash_ratio(), None);
+        assert_eq!(entry(0, 0, 0).awareness(), None);
+        assert_eq!(entry(0, 0, 2).smash_ratio(), None);
+        assert_eq!(entry(0, 0, 2).awareness(), Some(0.0));
+    }
+}
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+/// Languages that the UI, and the VTubers' names and descriptions, can be
+/// displayed in. Mirrors `app.LOCALE` in the DB.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "en")]
+    En,
+    #[serde(rename = "ja")]
+    Ja,
+    #[serde(rename = "zh")]
+    Zh,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::En, Locale::Ja, Locale::Zh];
+
+    /// Code used for the cookie, the `lang` attribute, and the DB enum.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Ja => "ja",
+            Locale::Zh => "zh",
+        }
+    }
+
+    /// Name of the language in the language itself.
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::Ja => "日本語",
+            Locale::Zh => "中文",
+        }
+    }
+
+    /// Parses a language tag, like `ja` or `en-US`. Only the primary subtag
+    /// matters since there's only one variant of each language.
+    pub fn from_code(tag: &str) -> Option<Locale> {
+        let primary = tag.trim().split('-').next()?.to_ascii_lowercase();
+
+        match primary.as_str() {
+            "en" => Some(Locale::En),
+            "ja" => Some(Locale::Ja),
+            "zh" => Some(Locale::Zh),
+            _ => None,
+        }
+    }
+
+    /// Picks the supported locale that the client prefers the most from the
+    /// value of an `Accept-Language` header. e.g `fr-CH, ja;q=0.9, en;q=0.8`
+    /// gives `Locale::Ja`.
+    pub fn from_accept_language(header: &str) -> Option<Locale> {
+        let mut tags: Vec<(&str, f32)> = header
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';');
+                let tag = parts.next()?.trim();
+
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+
+                if tag.is_empty() || quality <= 0.0 {
+                    None
+                } else {
+                    Some((tag, quality))
+                }
+            })
+            .collect();
+
+        // Stable sort, so tags with the same quality keep the client's order.
+        tags.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+
+        tags.into_iter().find_map(|(tag, _)| Locale::from_code(tag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Locale;
+
+    #[test]
+    fn parse_language_tags() {
+        assert_eq!(Locale::from_code("ja"), Some(Locale::Ja));
+        assert_eq!(Locale::from_code("en-US"), Some(Locale::En));
+        assert_eq!(Locale::from_code("zh-Hans-CN"), Some(Locale::Zh));
+        assert_eq!(Locale::from_code("fr"), None);
+        assert_eq!(Locale::from_code(""), None);
+    }
+
+    #[test]
+    fn accept_language_picks_preferred_supported_locale() {
+        assert_eq!(
+            Locale::from_accept_language("fr-CH, fr;q=0.9, ja;q=0.8, en;q=0.7"),
+            Some(Locale::Ja)
+        );
+
+        assert_eq!(
+            Locale::from_accept_language("en;q=0.5, zh-CN"),
+            Some(Locale::Zh)
+        );
+
+        assert_eq!(Locale::from_accept_language("ja, en"), Some(Locale::Ja));
+    }
+
+    #[test]
+    fn accept_language_ignores_unsupported_and_rejected() {
+        assert_eq!(Locale::from_accept_language("ja;q=0, de"), None);
+        assert_eq!(Locale::from_accept_language("*"), None);
+        assert_eq!(Locale::from_accept_language(""), None);
+        assert_eq!(
+            Locale::from_accept_language("ja;q=abc, en"),
+            Some(Locale::En)
+        );
+    }
+}
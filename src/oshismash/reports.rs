@@ -0,0 +1,271 @@
+use deadpool_postgres::Object;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio_postgres::types::Type;
+
+use crate::oshismash;
+
+const MAX_DETAILS_LENGTH: usize = 1000;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("no such report reason `{0}`")]
+    InvalidReason(String),
+    #[error("`details` is too long")]
+    DetailsTooLong,
+    #[error("details are required when the reason is `other`")]
+    MissingDetails,
+}
+
+/// Why a guest reported a VTuber's card.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// The VTuber (or their agency) wants to be removed.
+    #[serde(rename = "takedown")]
+    Takedown,
+    #[serde(rename = "wrong_image")]
+    WrongImage,
+    #[serde(rename = "wrong_info")]
+    WrongInfo,
+    #[serde(rename = "other")]
+    Other,
+}
+
+impl Reason {
+    pub const ALL: [Reason; 4] = [
+        Reason::Takedown,
+        Reason::WrongImage,
+        Reason::WrongInfo,
+        Reason::Other,
+    ];
+
+    /// Code used for the form, and the DB enum.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Reason::Takedown => "takedown",
+            Reason::WrongImage => "wrong_image",
+            Reason::WrongInfo => "wrong_info",
+            Reason::Other => "other",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Reason> {
+        Reason::ALL
+            .into_iter()
+            .find(|reason| reason.code() == code.trim())
+    }
+}
+
+/// A report that a guest is making.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewReport {
+    pub reason: Reason,
+    pub details: String,
+}
+
+impl NewReport {
+    /// Cleans up the fields of the report form. Details are optional, unless
+    /// none of the reasons fit.
+    pub fn parse(reason: &str, details: &str) -> Result<NewReport, ParseError> {
+        let reason = Reason::from_code(reason)
+            .ok_or_else(|| ParseError::InvalidReason(reason.to_string()))?;
+        let details = details.trim();
+
+        if details.chars().count() > MAX_DETAILS_LENGTH {
+            return Err(ParseError::DetailsTooLong);
+        }
+
+        if reason == Reason::Other && details.is_empty() {
+            return Err(ParseError::MissingDetails);
+        }
+
+        Ok(NewReport {
+            reason,
+            details: details.to_string(),
+        })
+    }
+}
+
+/// Stores a guest's report for moderators to look at. A guest can't report the
+/// same VTuber again until their last report has been looked at.
+pub async fn submit(
+    client: &Object,
+    guest_id: &str,
+    vtuber_id: i64,
+    report: &NewReport,
+) -> Result<i64, oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached(
+            "SELECT app.report_vtuber($1::UUID, $2, $3::app.REPORT_REASON, $4)",
+            &[Type::TEXT, Type::INT8, Type::TEXT, Type::TEXT],
+        )
+        .await?;
+
+    let report_id: Option<i64> = client
+        .query_one(
+            &statement,
+            &[
+                &guest_id,
+                &vtuber_id,
+                &report.reason.code(),
+                &report.details,
+            ],
+        )
+        .await?
+        .get("report_vtuber");
+
+    report_id.ok_or(oshismash::Error::AlreadyReported)
+}
+
+/// A report waiting in the moderation queue.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub report_id: i64,
+    pub vtuber_id: i64,
+    pub vtuber_name: String,
+    pub slug: String,
+    /// The VTuber might've been hidden by hand since this was reported.
+    pub hidden: bool,
+    pub reason: Reason,
+    pub details: String,
+    pub created_at: String,
+}
+
+/// Gets the reports that haven't been looked at yet, oldest first.
+pub async fn get_open(client: &Object) -> Result<Vec<Report>, oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached("SELECT * FROM app.get_open_reports()", &[])
+        .await?;
+
+    let value: Value = client
+        .query_one(&statement, &[])
+        .await?
+        .get("get_open_reports");
+
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Hides the reported VTuber, which resolves every open report on them.
+pub async fn resolve(client: &Object, report_id: i64) -> Result<(), oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached("SELECT app.resolve_report($1)", &[Type::INT8])
+        .await?;
+
+    let resolved: bool = client
+        .query_one(&statement, &[&report_id])
+        .await?
+        .get("resolve_report");
+
+    if resolved {
+        Ok(())
+    } else {
+        Err(oshismash::Error::ReportNotFound)
+    }
+}
+
+pub async fn dismiss(client: &Object, report_id: i64) -> Result<(), oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached("SELECT app.dismiss_report($1)", &[Type::INT8])
+        .await?;
+
+    let dismissed: bool = client
+        .query_one(&statement, &[&report_id])
+        .await?
+        .get("dismiss_report");
+
+    if dismissed {
+        Ok(())
+    } else {
+        Err(oshismash::Error::ReportNotFound)
+    }
+}
+
+/// A VTuber that was taken out of the deck.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct HiddenVTuber {
+    pub vtuber_id: i64,
+    pub name: String,
+    pub slug: String,
+}
+
+pub async fn get_hidden(client: &Object) -> Result<Vec<HiddenVTuber>, oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached("SELECT * FROM app.get_hidden_vtubers()", &[])
+        .await?;
+
+    let value: Value = client
+        .query_one(&statement, &[])
+        .await?
+        .get("get_hidden_vtubers");
+
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Hides a VTuber from (or puts them back into) the deck, the leaderboard, and
+/// search. Their votes are kept either way.
+pub async fn set_hidden(
+    client: &Object,
+    vtuber_id: i64,
+    hidden: bool,
+) -> Result<(), oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached(
+            "SELECT app.set_vtuber_hidden($1, $2)",
+            &[Type::INT8, Type::BOOL],
+        )
+        .await?;
+
+    let updated: bool = client
+        .query_one(&statement, &[&vtuber_id, &hidden])
+        .await?
+        .get("set_vtuber_hidden");
+
+    if updated {
+        Ok(())
+    } else {
+        Err(oshismash::Error::VTuberNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NewReport, ParseError, Reason};
+
+    #[test]
+    fn reason_codes_roundtrip() {
+        for reason in Reason::ALL {
+            assert_eq!(Reason::from_code(reason.code()), Some(reason));
+        }
+
+        assert_eq!(Reason::from_code("spam"), None);
+    }
+
+    #[test]
+    fn parse_report() {
+        assert_eq!(
+            NewReport::parse("wrong_image", "  That's her old model "),
+            Ok(NewReport {
+                reason: Reason::WrongImage,
+                details: "That's her old model".to_string(),
+            })
+        );
+
+        assert_eq!(
+            NewReport::parse("takedown", ""),
+            Ok(NewReport {
+                reason: Reason::Takedown,
+                details: String::new(),
+            })
+        );
+
+        assert_eq!(
+            NewReport::parse("other", "   "),
+            Err(ParseError::MissingDetails)
+        );
+
+        assert_eq!(
+            NewReport::parse("wrong_info", &"a".repeat(1001)),
+            Err(ParseError::DetailsTooLong)
+        );
+    }
+}
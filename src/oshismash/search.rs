@@ -0,0 +1,74 @@
+use deadpool_postgres::Object;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio_postgres::types::Type;
+
+use super::{locale::Locale, vtubers::Org};
+use crate::oshismash;
+
+/// Longest query that gets sent to the DB. Anything longer is cut off.
+const MAX_QUERY_LENGTH: usize = 64;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SearchResult {
+    pub id: i64,
+    pub name: String,
+    pub img: Option<String>,
+    pub org_name: Org,
+}
+
+/// Trims the query, and cuts it off if it's too long. Results to `None` if
+/// there's nothing left to search for.
+pub fn normalize_query(query: &str) -> Option<String> {
+    let query: String = query.trim().chars().take(MAX_QUERY_LENGTH).collect();
+    let query = query.trim();
+
+    if query.is_empty() {
+        None
+    } else {
+        Some(query.to_string())
+    }
+}
+
+/// Searches for VTubers by their names, aliases, and descriptions. The best
+/// matches come first.
+pub async fn search(
+    client: &Object,
+    query: &str,
+    locale: Locale,
+    max_results: i32,
+) -> Result<Vec<SearchResult>, oshismash::Error> {
+    let query = match normalize_query(query) {
+        Some(query) => query,
+        None => return Ok(Vec::new()),
+    };
+
+    let statement = client
+        .prepare_typed_cached(
+            "SELECT * FROM app.search_vtubers($1, $2::app.LOCALE, $3)",
+            &[Type::TEXT, Type::TEXT, Type::INT4],
+        )
+        .await?;
+
+    let value: Value = client
+        .query_one(&statement, &[&query, &locale.code(), &max_results])
+        .await?
+        .get("search_vtubers");
+
+    Ok(serde_json::from_value(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_query;
+
+    #[test]
+    fn normalize_trims_and_caps_queries() {
+        assert_eq!(normalize_query("  nyan  "), Some("nyan".to_string()));
+        assert_eq!(normalize_query("   "), None);
+        assert_eq!(
+            normalize_query(&"あ".repeat(100)).unwrap().chars().count(),
+            64
+        );
+    }
+}
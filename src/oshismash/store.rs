@@ -0,0 +1,147 @@
+#[cfg(test)]
+pub(crate) mod memory;
+
+use std::{ops::Deref, sync::Arc};
+
+use axum::async_trait;
+
+use super::{
+    deck::{self, Access, DeckFilter},
+    guests::{self, Guest},
+    locale::Locale,
+    vote::{self, Vote},
+    vtubers::{self, Details, Stack, VTuberId},
+};
+use crate::{db, oshismash};
+
+/// Where guests, their votes, and the VTubers they vote on are kept. Handlers
+/// of the voting flow go through this rather than a DB client, so that they
+/// can be run against `memory::MemoryStore` in tests instead of Postgres.
+#[async_trait]
+pub trait Store: Send + Sync {
+    /// Creates an anonymous guest
+    async fn create_guest(&self) -> Result<Guest, oshismash::Error>;
+
+    /// Votes for a VTuber, and gives back the stack for the next one. Guests
+    /// have to have voted for every VTuber before this one in the deck.
+    async fn vote(
+        &self,
+        vote: Vote,
+        locale: Locale,
+        filter: &DeckFilter,
+    ) -> Result<Stack, oshismash::Error>;
+
+    async fn get_vote_stack(
+        &self,
+        vtuber_id: &VTuberId,
+        guest_id: &str,
+        locale: Locale,
+        filter: &DeckFilter,
+    ) -> Result<Stack, oshismash::Error>;
+
+    /// Checks if a guest can see a VTuber given the filter.
+    async fn get_access(
+        &self,
+        vtuber_id: i64,
+        guest_id: &str,
+        filter: &DeckFilter,
+    ) -> Result<Access, oshismash::Error>;
+
+    async fn get_slug(&self, vtuber_id: i64) -> Result<Option<String>, oshismash::Error>;
+
+    async fn get_id_by_slug(&self, slug: &str) -> Result<Option<i64>, oshismash::Error>;
+
+    async fn get_details(
+        &self,
+        vtuber_id: i64,
+        locale: Locale,
+    ) -> Result<Option<Details>, oshismash::Error>;
+}
+
+/// A `Store` that's shared between requests, as an extension.
+#[derive(Clone)]
+pub struct Handle(Arc<dyn Store>);
+
+impl Handle {
+    pub fn new(store: Arc<dyn Store>) -> Handle {
+        Handle(store)
+    }
+}
+
+impl Deref for Handle {
+    type Target = dyn Store;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+/// Each call checks out its own client, so calls that don't have to see the
+/// latest writes can go to the replica.
+#[async_trait]
+impl Store for db::Handle {
+    async fn create_guest(&self) -> Result<Guest, oshismash::Error> {
+        let client = self.client().await?;
+
+        guests::create_guest(&client).await
+    }
+
+    async fn vote(
+        &self,
+        vote: Vote,
+        locale: Locale,
+        filter: &DeckFilter,
+    ) -> Result<Stack, oshismash::Error> {
+        let client = self.client().await?;
+
+        vote::vote(&client, vote, locale, filter).await
+    }
+
+    async fn get_vote_stack(
+        &self,
+        vtuber_id: &VTuberId,
+        guest_id: &str,
+        locale: Locale,
+        filter: &DeckFilter,
+    ) -> Result<Stack, oshismash::Error> {
+        let client = self.client().await?;
+        let stack =
+            vtubers::get_vote_stack(&client, vtuber_id, guest_id.to_string(), locale, filter)
+                .await?;
+
+        Ok(stack)
+    }
+
+    async fn get_access(
+        &self,
+        vtuber_id: i64,
+        guest_id: &str,
+        filter: &DeckFilter,
+    ) -> Result<Access, oshismash::Error> {
+        let client = self.client().await?;
+
+        deck::get_access(&client, vtuber_id, guest_id, filter).await
+    }
+
+    async fn get_slug(&self, vtuber_id: i64) -> Result<Option<String>, oshismash::Error> {
+        let client = self.client().await?;
+
+        vtubers::get_slug(&client, vtuber_id).await
+    }
+
+    async fn get_id_by_slug(&self, slug: &str) -> Result<Option<i64>, oshismash::Error> {
+        let client = self.client().await?;
+
+        vtubers::get_id_by_slug(&client, slug).await
+    }
+
+    async fn get_details(
+        &self,
+        vtuber_id: i64,
+        locale: Locale,
+    ) -> Result<Option<Details>, oshismash::Error> {
+        let client = self.reader().await?;
+
+        vtubers::get_details(&client, vtuber_id, locale).await
+    }
+}
@@ -0,0 +1,546 @@
+use std::{cmp::Ordering, sync::Mutex};
+
+use axum::async_trait;
+use uuid::Uuid;
+
+use super::Store;
+use crate::oshismash::{
+    self,
+    deck::{Access, DeckFilter},
+    guests::{Guest, GuestId},
+    locale::Locale,
+    vote::{Stat, UserAction, Vote},
+    vtubers::{self, Breakdown, Details, Metrics, Org, Region, Stack, VTuber, VTuberId},
+};
+
+/// A VTuber in the deck of a `MemoryStore`.
+#[derive(Debug, Clone)]
+pub struct Listing {
+    pub id: i64,
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    pub img: Option<String>,
+    pub org_id: i32,
+    pub org_name: Org,
+    pub group_id: Option<i32>,
+    pub group_name: Option<String>,
+    pub region: Region,
+}
+
+impl Listing {
+    /// A Hololive EN VTuber named after their slug, which is enough for most
+    /// tests. The fields can be changed for the ones that filter.
+    pub fn new(id: i64, slug: &str) -> Listing {
+        Listing {
+            id,
+            slug: slug.to_string(),
+            name: slug.to_string(),
+            description: String::new(),
+            img: None,
+            org_id: 1,
+            org_name: Org::Hololive,
+            group_id: None,
+            group_name: None,
+            region: Region::En,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Ballot {
+    guest_id: String,
+    vtuber_id: i64,
+    action: UserAction,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    guests: Vec<String>,
+    /// In the order they were first cast. Changing a vote keeps its place.
+    votes: Vec<Ballot>,
+}
+
+/// Keeps everything in memory, for tests that shouldn't need Postgres. It
+/// follows the same rules as the DB, except that the deck is never shuffled,
+/// translated, or hidden from, and the stats aren't windowed by time.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    /// In deck order
+    deck: Vec<Listing>,
+    state: Mutex<State>,
+}
+
+impl MemoryStore {
+    pub fn new(deck: Vec<Listing>) -> MemoryStore {
+        MemoryStore {
+            deck,
+            state: Mutex::default(),
+        }
+    }
+
+    fn listing(&self, vtuber_id: i64) -> Option<&Listing> {
+        self.deck.iter().find(|listing| listing.id == vtuber_id)
+    }
+
+    fn position(&self, vtuber_id: i64) -> Option<usize> {
+        self.deck.iter().position(|listing| listing.id == vtuber_id)
+    }
+
+    /// Positions of the VTubers that match the filter, in deck order.
+    fn filtered(&self, filter: &DeckFilter) -> Vec<usize> {
+        self.deck
+            .iter()
+            .enumerate()
+            .filter(|(_, listing)| {
+                filter
+                    .org_id
+                    .map_or(true, |org_id| listing.org_id == org_id)
+                    && filter
+                        .group_id
+                        .map_or(true, |group_id| listing.group_id == Some(group_id))
+                    && filter
+                        .region
+                        .map_or(true, |region| listing.region == region)
+            })
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    /// The closest VTubers before and after the given one that match the
+    /// filter. The given VTuber itself doesn't have to match.
+    fn neighbors(&self, vtuber_id: i64, filter: &DeckFilter) -> (Option<i64>, Option<i64>) {
+        let target = match self.position(vtuber_id) {
+            Some(target) => target,
+            None => return (None, None),
+        };
+
+        let filtered = self.filtered(filter);
+        let prev = filtered.iter().rev().find(|&&position| position < target);
+        let next = filtered.iter().find(|&&position| position > target);
+
+        (
+            prev.map(|&position| self.deck[position].id),
+            next.map(|&position| self.deck[position].id),
+        )
+    }
+
+    fn access(&self, state: &State, vtuber_id: i64, guest_id: &str, filter: &DeckFilter) -> Access {
+        let filtered = self.filtered(filter);
+
+        let frontier = filtered.iter().copied().find(|&position| {
+            !state.votes.iter().any(|ballot| {
+                ballot.guest_id == guest_id && ballot.vtuber_id == self.deck[position].id
+            })
+        });
+
+        let resume_at = match frontier.or_else(|| filtered.first().copied()) {
+            Some(position) => self.deck[position].id,
+            None => return Access::EmptyDeck,
+        };
+
+        let target = filtered
+            .iter()
+            .copied()
+            .find(|&position| self.deck[position].id == vtuber_id);
+
+        match (target, frontier) {
+            (None, _) => Access::NotInDeck { resume_at },
+            (Some(target), Some(frontier)) if target > frontier => Access::Locked { resume_at },
+            (Some(_), _) => Access::Allowed,
+        }
+    }
+
+    fn metrics(&self, state: &State, vtuber_id: i64) -> Metrics {
+        let mut metrics = Metrics {
+            smashes: 0,
+            passes: 0,
+            skips: 0,
+        };
+
+        for ballot in state
+            .votes
+            .iter()
+            .filter(|ballot| ballot.vtuber_id == vtuber_id)
+        {
+            match ballot.action {
+                UserAction::Smashed => metrics.smashes += 1,
+                UserAction::Passed => metrics.passes += 1,
+                UserAction::Skipped => metrics.skips += 1,
+            }
+        }
+
+        metrics
+    }
+
+    fn stack(
+        &self,
+        state: &State,
+        prev_vtuber_id: Option<i64>,
+        current_vtuber_id: Option<i64>,
+        guest_id: &str,
+        filter: &DeckFilter,
+    ) -> Result<Stack, oshismash::Error> {
+        let current = current_vtuber_id
+            .and_then(|vtuber_id| self.listing(vtuber_id))
+            .map(|listing| {
+                let (prev, next) = self.neighbors(listing.id, filter);
+                let slug = |vtuber_id: Option<i64>| {
+                    vtuber_id
+                        .and_then(|vtuber_id| self.listing(vtuber_id))
+                        .map(|listing| listing.slug.clone())
+                };
+
+                VTuber {
+                    id: listing.id,
+                    slug: listing.slug.clone(),
+                    name: listing.name.clone(),
+                    description: listing.description.clone(),
+                    org_name: listing.org_name.clone(),
+                    group_name: listing.group_name.clone(),
+                    region: listing.region,
+                    next,
+                    next_slug: slug(next),
+                    prev,
+                    prev_slug: slug(prev),
+                    img: listing.img.clone(),
+                }
+            });
+
+        let prev_result = prev_vtuber_id
+            .and_then(|vtuber_id| self.listing(vtuber_id))
+            .map(|listing| {
+                let metrics = self.metrics(state, listing.id);
+
+                Stat {
+                    vtuber_id: listing.id,
+                    slug: listing.slug.clone(),
+                    name: listing.name.clone(),
+                    img: listing.img.clone(),
+                    smashes: metrics.smashes,
+                    passes: metrics.passes,
+                    skips: metrics.skips,
+                }
+            });
+
+        let guest_votes = state
+            .votes
+            .iter()
+            .filter(|ballot| ballot.guest_id == guest_id);
+
+        let voted = guest_votes.clone().map(|ballot| ballot.vtuber_id).collect();
+        let vote_for_current = guest_votes
+            .filter(|ballot| Some(ballot.vtuber_id) == current_vtuber_id)
+            .map(|ballot| ballot.action.clone())
+            .next();
+
+        match (prev_result, current) {
+            (None, None) => Err(vtubers::Error::ValueParseFailed.into()),
+            (Some(prev_result), None) => Ok(Stack::NoCurrent { prev_result, voted }),
+            (None, Some(current)) => Ok(Stack::NoPrev {
+                current,
+                voted,
+                vote_for_current,
+            }),
+            (Some(prev_result), Some(current)) => Ok(Stack::HasBoth {
+                prev_result,
+                current,
+                voted,
+                vote_for_current,
+            }),
+        }
+    }
+}
+
+/// How VTubers are ranked within their org: by smash ratio, then by smashes.
+/// The ones nobody smashed or passed yet come last.
+fn rank_key(metrics: &Metrics) -> (Option<f64>, i64) {
+    let votes = metrics.smashes + metrics.passes;
+    let ratio = (votes > 0).then(|| metrics.smashes as f64 / votes as f64);
+
+    (ratio, metrics.smashes)
+}
+
+#[async_trait]
+impl Store for MemoryStore {
+    async fn create_guest(&self) -> Result<Guest, oshismash::Error> {
+        let mut state = self.state.lock().unwrap();
+        let guest_id = Uuid::from_u128(state.guests.len() as u128 + 1).to_string();
+
+        state.guests.push(guest_id.clone());
+
+        Ok(Guest {
+            guest_id: GuestId(guest_id),
+        })
+    }
+
+    async fn vote(
+        &self,
+        vote: Vote,
+        _locale: Locale,
+        filter: &DeckFilter,
+    ) -> Result<Stack, oshismash::Error> {
+        let mut state = self.state.lock().unwrap();
+        let guest_id = vote.guest_id.to_string();
+
+        if !state.guests.contains(&guest_id) {
+            return Err(oshismash::Error::InvalidGuest);
+        }
+
+        if self.access(&state, vote.vtuber_id, &guest_id, filter) != Access::Allowed {
+            return Err(oshismash::Error::NotAllowedToVote);
+        }
+
+        let ballot = state
+            .votes
+            .iter_mut()
+            .find(|ballot| ballot.guest_id == guest_id && ballot.vtuber_id == vote.vtuber_id);
+
+        match ballot {
+            Some(ballot) => ballot.action = vote.action,
+            None => state.votes.push(Ballot {
+                guest_id: guest_id.clone(),
+                vtuber_id: vote.vtuber_id,
+                action: vote.action,
+            }),
+        }
+
+        let (_, next) = self.neighbors(vote.vtuber_id, filter);
+
+        self.stack(&state, Some(vote.vtuber_id), next, &guest_id, filter)
+    }
+
+    async fn get_vote_stack(
+        &self,
+        vtuber_id: &VTuberId,
+        guest_id: &str,
+        _locale: Locale,
+        filter: &DeckFilter,
+    ) -> Result<Stack, oshismash::Error> {
+        let state = self.state.lock().unwrap();
+
+        match *vtuber_id {
+            VTuberId::LastVisited(id) => {
+                let (_, next) = self.neighbors(id, filter);
+
+                self.stack(&state, Some(id), next, guest_id, filter)
+            }
+            VTuberId::Current(id) => {
+                let (prev, _) = self.neighbors(id, filter);
+
+                // Like the DB, there's no stack for VTubers that don't exist.
+                let current = self.listing(id).map(|listing| listing.id);
+                let prev = current.and(prev);
+
+                self.stack(&state, prev, current, guest_id, filter)
+            }
+        }
+    }
+
+    async fn get_access(
+        &self,
+        vtuber_id: i64,
+        guest_id: &str,
+        filter: &DeckFilter,
+    ) -> Result<Access, oshismash::Error> {
+        let state = self.state.lock().unwrap();
+
+        Ok(self.access(&state, vtuber_id, guest_id, filter))
+    }
+
+    async fn get_slug(&self, vtuber_id: i64) -> Result<Option<String>, oshismash::Error> {
+        Ok(self.listing(vtuber_id).map(|listing| listing.slug.clone()))
+    }
+
+    async fn get_id_by_slug(&self, slug: &str) -> Result<Option<i64>, oshismash::Error> {
+        let listing = self.deck.iter().find(|listing| listing.slug == slug);
+
+        Ok(listing.map(|listing| listing.id))
+    }
+
+    async fn get_details(
+        &self,
+        vtuber_id: i64,
+        _locale: Locale,
+    ) -> Result<Option<Details>, oshismash::Error> {
+        let state = self.state.lock().unwrap();
+
+        let listing = match self.listing(vtuber_id) {
+            Some(listing) => listing,
+            None => return Ok(None),
+        };
+
+        let metrics = self.metrics(&state, listing.id);
+        let org = self
+            .deck
+            .iter()
+            .filter(|other| other.org_id == listing.org_id);
+
+        let org_size = org.clone().count() as i64;
+        let ahead = org
+            .filter(|other| {
+                let other = rank_key(&self.metrics(&state, other.id));
+
+                other.partial_cmp(&rank_key(&metrics)) == Some(Ordering::Greater)
+            })
+            .count() as i64;
+
+        Ok(Some(Details {
+            id: listing.id,
+            name: listing.name.clone(),
+            description: listing.description.clone(),
+            img: listing.img.clone(),
+            org_name: listing.org_name.clone(),
+            group_name: listing.group_name.clone(),
+            region: listing.region,
+            youtube: None,
+            twitch: None,
+            twitter: None,
+            stats: Breakdown {
+                all_time: metrics,
+                last_week: metrics,
+                last_month: metrics,
+            },
+            org_rank: ahead + 1,
+            org_size,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{Listing, MemoryStore};
+    use crate::oshismash::{
+        deck::{Access, DeckFilter},
+        locale::Locale,
+        store::Store,
+        vote::{UserAction, Vote},
+        vtubers::{Region, Stack, VTuberId},
+        Error,
+    };
+
+    fn store() -> MemoryStore {
+        MemoryStore::new(vec![
+            Listing::new(1, "nyatasha-nyanners"),
+            Listing {
+                region: Region::Jp,
+                ..Listing::new(2, "veibae")
+            },
+            Listing::new(3, "silvervale"),
+        ])
+    }
+
+    fn vote(guest_id: &str, vtuber_id: i64, action: UserAction) -> Vote {
+        Vote {
+            vtuber_id,
+            guest_id: Uuid::parse_str(guest_id).unwrap(),
+            action,
+        }
+    }
+
+    #[tokio::test]
+    async fn guests_cant_skip_ahead() {
+        let store = store();
+        let guest = store.create_guest().await.unwrap().guest_id.0;
+        let filter = DeckFilter::default();
+
+        assert_eq!(
+            store.get_access(2, &guest, &filter).await.unwrap(),
+            Access::Locked { resume_at: 1 }
+        );
+        assert!(matches!(
+            store
+                .vote(vote(&guest, 2, UserAction::Smashed), Locale::En, &filter)
+                .await,
+            Err(Error::NotAllowedToVote)
+        ));
+
+        store
+            .vote(vote(&guest, 1, UserAction::Smashed), Locale::En, &filter)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.get_access(2, &guest, &filter).await.unwrap(),
+            Access::Allowed
+        );
+    }
+
+    #[tokio::test]
+    async fn votes_move_guests_along_the_filtered_deck() {
+        let store = store();
+        let guest = store.create_guest().await.unwrap().guest_id.0;
+        let filter = DeckFilter {
+            region: Some(Region::En),
+            ..DeckFilter::default()
+        };
+
+        assert_eq!(
+            store.get_access(2, &guest, &filter).await.unwrap(),
+            Access::NotInDeck { resume_at: 1 }
+        );
+
+        let stack = store
+            .vote(vote(&guest, 1, UserAction::Passed), Locale::En, &filter)
+            .await
+            .unwrap();
+
+        assert_eq!(stack.get_current().map(|vtuber| vtuber.id), Some(3));
+        assert_eq!(stack.get_last_voted_stat().map(|stat| stat.passes), Some(1));
+
+        let stack = store
+            .vote(vote(&guest, 3, UserAction::Smashed), Locale::En, &filter)
+            .await
+            .unwrap();
+
+        assert!(matches!(stack, Stack::NoCurrent { .. }));
+        assert_eq!(stack.get_vote_list(), vec![1, 3]);
+    }
+
+    #[tokio::test]
+    async fn changed_votes_are_counted_once() {
+        let store = store();
+        let guest = store.create_guest().await.unwrap().guest_id.0;
+        let filter = DeckFilter::default();
+
+        for action in [UserAction::Smashed, UserAction::Passed] {
+            store
+                .vote(vote(&guest, 1, action), Locale::En, &filter)
+                .await
+                .unwrap();
+        }
+
+        let stack = store
+            .get_vote_stack(&VTuberId::Current(1), &guest, Locale::En, &filter)
+            .await
+            .unwrap();
+
+        assert_eq!(stack.get_vote_list(), vec![1]);
+        assert!(matches!(
+            stack,
+            Stack::NoPrev {
+                vote_for_current: Some(UserAction::Passed),
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn unknown_guests_cant_vote() {
+        let store = store();
+        let guest = Uuid::from_u128(42).to_string();
+
+        assert!(matches!(
+            store
+                .vote(
+                    vote(&guest, 1, UserAction::Smashed),
+                    Locale::En,
+                    &DeckFilter::default()
+                )
+                .await,
+            Err(Error::InvalidGuest)
+        ));
+    }
+}
@@ -0,0 +1,309 @@
+use deadpool_postgres::Object;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio_postgres::types::Type;
+
+use super::vtubers::Region;
+use crate::oshismash;
+
+const MAX_NAME_LENGTH: usize = 100;
+const MAX_REASON_LENGTH: usize = 1000;
+const MAX_LINKS: usize = 5;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("a name is required")]
+    MissingName,
+    #[error("`{0}` is too long")]
+    TooLong(&'static str),
+    #[error("links have to start with `https://` (got `{0}`)")]
+    InvalidLink(String),
+    #[error("at most {} links can be given", MAX_LINKS)]
+    TooManyLinks,
+}
+
+/// A VTuber that a guest wants added.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewSuggestion {
+    pub name: String,
+    pub org_name: String,
+    pub links: Vec<String>,
+    pub reason: String,
+}
+
+impl NewSuggestion {
+    /// Cleans up the fields of the suggestion form. Links are one per line, and
+    /// blank lines are ignored.
+    pub fn parse(
+        name: &str,
+        org_name: &str,
+        links: &str,
+        reason: &str,
+    ) -> Result<NewSuggestion, ParseError> {
+        let name = name.trim();
+        let org_name = org_name.trim();
+        let reason = reason.trim();
+
+        if name.is_empty() {
+            return Err(ParseError::MissingName);
+        }
+
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return Err(ParseError::TooLong("name"));
+        }
+
+        if org_name.chars().count() > MAX_NAME_LENGTH {
+            return Err(ParseError::TooLong("org"));
+        }
+
+        if reason.chars().count() > MAX_REASON_LENGTH {
+            return Err(ParseError::TooLong("reason"));
+        }
+
+        let links: Vec<String> = links
+            .lines()
+            .map(str::trim)
+            .filter(|link| !link.is_empty())
+            .map(String::from)
+            .collect();
+
+        if links.len() > MAX_LINKS {
+            return Err(ParseError::TooManyLinks);
+        }
+
+        for link in &links {
+            check_link(link)?;
+        }
+
+        Ok(NewSuggestion {
+            name: name.to_string(),
+            org_name: org_name.to_string(),
+            links,
+            reason: reason.to_string(),
+        })
+    }
+}
+
+fn check_link(link: &str) -> Result<(), ParseError> {
+    if link.starts_with("https://") && !link.contains(char::is_whitespace) {
+        Ok(())
+    } else {
+        Err(ParseError::InvalidLink(link.to_string()))
+    }
+}
+
+/// Stores a guest's suggestion for moderators to look at. Guests can only make
+/// a few a day.
+pub async fn submit(
+    client: &Object,
+    guest_id: &str,
+    suggestion: &NewSuggestion,
+) -> Result<i64, oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached(
+            "SELECT app.suggest_vtuber($1::UUID, $2, $3, $4, $5)",
+            &[
+                Type::TEXT,
+                Type::TEXT,
+                Type::TEXT,
+                Type::TEXT_ARRAY,
+                Type::TEXT,
+            ],
+        )
+        .await?;
+
+    let suggestion_id: Option<i64> = client
+        .query_one(
+            &statement,
+            &[
+                &guest_id,
+                &suggestion.name,
+                &suggestion.org_name,
+                &suggestion.links,
+                &suggestion.reason,
+            ],
+        )
+        .await?
+        .get("suggest_vtuber");
+
+    suggestion_id.ok_or(oshismash::Error::TooManySuggestions)
+}
+
+/// A suggestion waiting in the moderation queue.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub suggestion_id: i64,
+    pub name: String,
+    pub org_name: String,
+    pub links: Vec<String>,
+    pub reason: String,
+    pub created_at: String,
+}
+
+/// Gets the suggestions that haven't been approved or rejected yet, oldest
+/// first.
+pub async fn get_pending(client: &Object) -> Result<Vec<Suggestion>, oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached("SELECT * FROM app.get_pending_suggestions()", &[])
+        .await?;
+
+    let value: Value = client
+        .query_one(&statement, &[])
+        .await?
+        .get("get_pending_suggestions");
+
+    Ok(serde_json::from_value(value)?)
+}
+
+/// What a moderator fills in when approving a suggestion. This is what the
+/// VTuber is added with, rather than what the guest wrote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Approval {
+    pub suggestion_id: i64,
+    pub name: String,
+    pub description: String,
+    pub org_id: i32,
+    pub group_id: Option<i32>,
+    pub region: Region,
+    pub img_source: Option<String>,
+    pub youtube: Option<String>,
+    pub twitch: Option<String>,
+    pub twitter: Option<String>,
+    /// VTuber that the new one goes right after. `None` puts them first.
+    pub after: Option<i64>,
+}
+
+impl Approval {
+    pub fn validate(&self) -> Result<(), ParseError> {
+        if self.name.trim().is_empty() {
+            return Err(ParseError::MissingName);
+        }
+
+        [&self.img_source, &self.youtube, &self.twitch, &self.twitter]
+            .into_iter()
+            .flatten()
+            .try_for_each(|link| check_link(link))
+    }
+}
+
+/// Adds the suggested VTuber, and splices them into the chain. Gives back the
+/// new VTuber's ID.
+pub async fn approve(client: &Object, approval: &Approval) -> Result<i64, oshismash::Error> {
+    approval.validate()?;
+
+    let statement = client
+        .prepare_typed_cached(
+            "SELECT app.approve_suggestion(
+                $1,
+                $2,
+                $3,
+                $4,
+                $5,
+                $6::app.REGION,
+                $7,
+                $8,
+                $9,
+                $10,
+                $11
+            )",
+            &[
+                Type::INT8,
+                Type::TEXT,
+                Type::TEXT,
+                Type::INT4,
+                Type::INT4,
+                Type::TEXT,
+                Type::TEXT,
+                Type::TEXT,
+                Type::TEXT,
+                Type::TEXT,
+                Type::INT8,
+            ],
+        )
+        .await?;
+
+    let vtuber_id: Option<i64> = client
+        .query_one(
+            &statement,
+            &[
+                &approval.suggestion_id,
+                &approval.name.trim(),
+                &approval.description.trim(),
+                &approval.org_id,
+                &approval.group_id,
+                &approval.region.code(),
+                &approval.img_source,
+                &approval.youtube,
+                &approval.twitch,
+                &approval.twitter,
+                &approval.after,
+            ],
+        )
+        .await?
+        .get("approve_suggestion");
+
+    vtuber_id.ok_or(oshismash::Error::SuggestionNotFound)
+}
+
+pub async fn reject(client: &Object, suggestion_id: i64) -> Result<(), oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached("SELECT app.reject_suggestion($1)", &[Type::INT8])
+        .await?;
+
+    let rejected: bool = client
+        .query_one(&statement, &[&suggestion_id])
+        .await?
+        .get("reject_suggestion");
+
+    if rejected {
+        Ok(())
+    } else {
+        Err(oshismash::Error::SuggestionNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NewSuggestion, ParseError};
+
+    #[test]
+    fn parse_suggestion() {
+        let found = NewSuggestion::parse(
+            "  Nyatasha Nyanners ",
+            "VShojo",
+            "https://youtube.com/c/Nyanners\n\n  https://twitch.tv/nyanners  \n",
+            "She's a weirdo",
+        );
+
+        assert_eq!(
+            found,
+            Ok(NewSuggestion {
+                name: "Nyatasha Nyanners".to_string(),
+                org_name: "VShojo".to_string(),
+                links: vec![
+                    "https://youtube.com/c/Nyanners".to_string(),
+                    "https://twitch.tv/nyanners".to_string(),
+                ],
+                reason: "She's a weirdo".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_suggestions_are_rejected() {
+        assert_eq!(
+            NewSuggestion::parse(" ", "VShojo", "", ""),
+            Err(ParseError::MissingName)
+        );
+
+        assert_eq!(
+            NewSuggestion::parse("Nyanners", "", "javascript:alert(1)", ""),
+            Err(ParseError::InvalidLink("javascript:alert(1)".to_string()))
+        );
+
+        assert_eq!(
+            NewSuggestion::parse("Nyanners", "", "", &"a".repeat(1001)),
+            Err(ParseError::TooLong("reason"))
+        );
+    }
+}
@@ -1,11 +1,15 @@
-use axum::response::IntoResponse;
-use hyper::StatusCode;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio_postgres::types::Type;
 use uuid::Uuid;
 
-use super::vtubers::Stack;
+use crate::oshismash;
+
+use super::{
+    deck::DeckFilter,
+    locale::Locale,
+    vtubers::{Region, Stack},
+};
 
 // TODO: Implement error
 #[derive(thiserror::Error, Debug, PartialEq)]
@@ -20,33 +24,6 @@ pub enum ParseError {
     MissingField(String),
 }
 
-impl IntoResponse for ParseError {
-    fn into_response(self) -> axum::response::Response {
-        // TODO: Move this out to `oshismash_web`
-        // TODO: Replace the text with an HTML template
-        let res = match self {
-            ParseError::InvalidAction(_action) => (
-                StatusCode::BAD_REQUEST,
-                "Replace this with an actual HTML template",
-            ),
-            ParseError::MissingField(_) => (
-                StatusCode::BAD_REQUEST,
-                "Replace this with an actual HTML template",
-            ),
-            ParseError::InvalidVtuberIdFormat(_) => (
-                StatusCode::BAD_REQUEST,
-                "Replace this with an actual HTML template",
-            ),
-            ParseError::InvalidGuestIdFormat(_) => (
-                StatusCode::BAD_REQUEST,
-                "Replace this with an actual HTML template",
-            ),
-        };
-
-        res.into_response()
-    }
-}
-
 #[derive(thiserror::Error, Debug)]
 pub enum VoteError {
     #[error("failed to prepare vote query ({0})")]
@@ -63,6 +40,10 @@ pub enum UserAction {
     Smashed,
     #[serde(rename = "passed")]
     Passed,
+    /// For when the guest doesn't know the VTuber. It moves them along the deck
+    /// without counting towards the smash/pass ratio.
+    #[serde(rename = "skipped")]
+    Skipped,
 }
 
 impl UserAction {
@@ -70,6 +51,7 @@ impl UserAction {
         match action {
             "smashed" => Some(UserAction::Smashed),
             "passed" => Some(UserAction::Passed),
+            "skipped" => Some(UserAction::Skipped),
             _ => None,
         }
     }
@@ -125,28 +107,48 @@ impl Vote {
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Stat {
     pub vtuber_id: i64,
+    pub slug: String,
     pub name: String,
     pub img: Option<String>,
     pub smashes: i64,
     pub passes: i64,
+    pub skips: i64,
 }
 
-/// Votes for a VTuber
+/// Votes for a VTuber. The guest is checked, and so is whether they're allowed
+/// to vote for the VTuber yet, in the same query.
 pub async fn vote(
     client: &deadpool_postgres::Object,
     vote_entry: Vote,
-) -> Result<Stack, VoteError> {
+    locale: Locale,
+    filter: &DeckFilter,
+) -> Result<Stack, oshismash::Error> {
     let action = match vote_entry.action {
         UserAction::Smashed => "smashed",
         UserAction::Passed => "passed",
+        UserAction::Skipped => "skipped",
     };
 
     println!("Vote Entry: {:?}", vote_entry);
 
     let vote_statement = client
-        .prepare_typed(
-            "SELECT * FROM app.vote($1 :: UUID, $2 :: BIGINT, $3 :: app.ACTION)",
-            &[Type::TEXT, Type::INT8, Type::TEXT],
+        .prepare_typed_cached(
+            "SELECT * FROM app.cast_vote(
+                $1 :: UUID,
+                $2 :: BIGINT,
+                $3 :: app.ACTION,
+                $4 :: app.LOCALE,
+                ROW($5 :: INTEGER, $6 :: INTEGER, $7 :: app.REGION) :: app.DECK_FILTER
+            )",
+            &[
+                Type::TEXT,
+                Type::INT8,
+                Type::TEXT,
+                Type::TEXT,
+                Type::INT4,
+                Type::INT4,
+                Type::TEXT,
+            ],
         )
         .await
         .map_err(|e| {
@@ -161,14 +163,22 @@ pub async fn vote(
                 &vote_entry.guest_id.to_string(),
                 &vote_entry.vtuber_id,
                 &action,
+                &locale.code(),
+                &filter.org_id,
+                &filter.group_id,
+                &filter.region.as_ref().map(Region::code),
             ],
         )
         .await
-        .map_err(|e| {
-            println!("{e}");
-            VoteError::QueryFailed(e)
+        .map_err(|e| match e.code().map(|code| code.code()) {
+            Some("Z0003") => oshismash::Error::InvalidGuest,
+            Some("Z0004") => oshismash::Error::NotAllowedToVote,
+            _ => {
+                println!("{e}");
+                oshismash::Error::VoteError(VoteError::QueryFailed(e))
+            }
         })?
-        .get("vote");
+        .get("cast_vote");
 
     println!("{:?}", val);
 
@@ -204,14 +214,27 @@ mod tests {
             action: UserAction::Passed,
         };
 
+        let vote_skipped = json!({
+            "vtuber_id": "1",
+            "guest_id": "0b76fdde-9910-402d-b7c2-97c02247b5fd",
+            "action": "skipped"
+        });
+
         let expected_smashed = Vote {
             vtuber_id: 1,
             guest_id,
             action: UserAction::Smashed,
         };
 
+        let expected_skipped = Vote {
+            vtuber_id: 1,
+            guest_id,
+            action: UserAction::Skipped,
+        };
+
         assert!(Vote::from(vote_passed).unwrap() == expected_passed);
         assert!(Vote::from(vote_smashed).unwrap() == expected_smashed);
+        assert!(Vote::from(vote_skipped).unwrap() == expected_skipped);
     }
 
     #[test]
@@ -2,8 +2,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio_postgres::types::Type;
 
-use super::vote::UserAction;
-use crate::oshismash::vote::Stat;
+use super::{deck::DeckFilter, locale::Locale, vote::UserAction};
+use crate::oshismash::{self, vote::Stat};
 
 /// `oshismash::vtubers::Error` represents whatever error `oshismash::vtubers`
 /// might run into.
@@ -170,15 +170,56 @@ impl ToString for Org {
     }
 }
 
+/// Where a VTuber mainly streams for. Mirrors `app.REGION` in the DB.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+    #[serde(rename = "cn")]
+    Cn,
+    #[serde(rename = "en")]
+    En,
+    #[serde(rename = "jp")]
+    Jp,
+    #[serde(rename = "none")]
+    None,
+}
+
+impl Region {
+    /// Code used for the query string, and the DB enum.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Region::Cn => "cn",
+            Region::En => "en",
+            Region::Jp => "jp",
+            Region::None => "none",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Region> {
+        match code.trim() {
+            "cn" => Some(Region::Cn),
+            "en" => Some(Region::En),
+            "jp" => Some(Region::Jp),
+            "none" => Some(Region::None),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct VTuber {
     pub id: i64,
+    pub slug: String,
     pub name: String,
     pub description: String,
     pub org_name: Org,
+    pub group_name: Option<String>,
+    pub region: Region,
     pub next: Option<i64>,
+    pub next_slug: Option<String>,
     pub prev: Option<i64>,
-    pub img: String,
+    pub prev_slug: Option<String>,
+    /// Key of the processed image, if there's one yet.
+    pub img: Option<String>,
 }
 
 #[derive(Debug)]
@@ -198,17 +239,132 @@ impl VTuberId {
     }
 }
 
+/// How a VTuber's page was asked for. Pages are linked to by slug, but the
+/// older numeric URLs still have to work.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathId {
+    Id(i64),
+    Slug(String),
+}
+
+impl PathId {
+    pub fn parse(path: &str) -> PathId {
+        match path.parse::<i64>() {
+            Ok(id) => PathId::Id(id),
+            Err(_) => PathId::Slug(path.to_string()),
+        }
+    }
+}
+
+/// Gets the slug of a VTuber. Results to `None` if there's no such VTuber.
+pub async fn get_slug(
+    client: &deadpool_postgres::Object,
+    vtuber_id: i64,
+) -> Result<Option<String>, oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached(
+            "SELECT slug FROM app.vtubers WHERE vtuber_id = $1",
+            &[Type::INT8],
+        )
+        .await?;
+
+    let row = client.query_opt(&statement, &[&vtuber_id]).await?;
+
+    Ok(row.map(|row| row.get("slug")))
+}
+
+/// Gets the ID of the VTuber with the slug. Results to `None` if there's no
+/// such VTuber.
+pub async fn get_id_by_slug(
+    client: &deadpool_postgres::Object,
+    slug: &str,
+) -> Result<Option<i64>, oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached(
+            "SELECT vtuber_id FROM app.vtubers WHERE slug = $1",
+            &[Type::TEXT],
+        )
+        .await?;
+
+    let row = client.query_opt(&statement, &[&slug]).await?;
+
+    Ok(row.map(|row| row.get("vtuber_id")))
+}
+
+/// Smash, pass, and skip counts of a VTuber over some period.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Metrics {
+    pub smashes: i64,
+    pub passes: i64,
+    pub skips: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Breakdown {
+    pub all_time: Metrics,
+    pub last_week: Metrics,
+    pub last_month: Metrics,
+}
+
+/// Everything about a VTuber that's shown in their profile.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Details {
+    pub id: i64,
+    pub name: String,
+    pub description: String,
+    pub img: Option<String>,
+    pub org_name: Org,
+    pub group_name: Option<String>,
+    pub region: Region,
+    pub youtube: Option<String>,
+    pub twitch: Option<String>,
+    pub twitter: Option<String>,
+    pub stats: Breakdown,
+    /// Rank within their org by smash ratio, starting from 1.
+    pub org_rank: i64,
+    /// Number of VTubers in their org.
+    pub org_size: i64,
+}
+
+/// Gets the profile of a VTuber. Results to `None` if there's no such VTuber.
+pub async fn get_details(
+    client: &deadpool_postgres::Object,
+    vtuber_id: i64,
+    locale: Locale,
+) -> Result<Option<Details>, oshismash::Error> {
+    let statement = client
+        .prepare_typed_cached(
+            "SELECT * FROM app.get_vtuber_details($1::BIGINT, $2::app.LOCALE)",
+            &[Type::INT8, Type::TEXT],
+        )
+        .await?;
+
+    let value: Option<Value> = client
+        .query_one(&statement, &[&vtuber_id, &locale.code()])
+        .await?
+        .get("get_vtuber_details");
+
+    match value {
+        Some(value) => Ok(Some(serde_json::from_value(value)?)),
+        None => Ok(None),
+    }
+}
+
 pub async fn get_vote_stack(
     client: &deadpool_postgres::Object,
     vtuber_id: &VTuberId,
     guest_id: String,
+    locale: Locale,
+    filter: &DeckFilter,
 ) -> Result<Stack, Error> {
     let value = match vtuber_id {
-        VTuberId::LastVisited(id) => query_vote_stack_from_previous(client, *id, guest_id)
-            .await
-            .and_then(|row| Ok(row.get::<&str, Value>("get_vote_stack_from_previous"))),
+        VTuberId::LastVisited(id) => {
+            query_vote_stack_from_previous(client, *id, guest_id, locale, filter)
+                .await
+                .and_then(|row| Ok(row.get::<&str, Value>("get_vote_stack_from_previous")))
+        }
 
-        VTuberId::Current(id) => query_vote_stack_from_current(client, *id, guest_id)
+        VTuberId::Current(id) => query_vote_stack_from_current(client, *id, guest_id, locale, filter)
             .await
             .and_then(|row| Ok(row.get::<&str, Value>("get_vote_stack_from_current"))),
     }?;
@@ -222,16 +378,33 @@ async fn query_vote_stack_from_previous(
     client: &deadpool_postgres::Object,
     prev_vtuber_id: i64,
     guest_id: String,
+    locale: Locale,
+    filter: &DeckFilter,
 ) -> Result<tokio_postgres::Row, tokio_postgres::Error> {
     let statement = client
-        .prepare_typed(
-            "SELECT * FROM app.get_vote_stack_from_previous($1::BIGINT, $2::UUID)",
-            &[Type::INT8, Type::TEXT],
+        .prepare_typed_cached(
+            "SELECT * FROM app.get_vote_stack_from_previous(
+                $1::BIGINT,
+                $2::UUID,
+                $3::app.LOCALE,
+                ROW($4::INTEGER, $5::INTEGER, $6::app.REGION)::app.DECK_FILTER
+            )",
+            &[Type::INT8, Type::TEXT, Type::TEXT, Type::INT4, Type::INT4, Type::TEXT],
         )
         .await?;
 
     client
-        .query_one(&statement, &[&prev_vtuber_id, &guest_id])
+        .query_one(
+            &statement,
+            &[
+                &prev_vtuber_id,
+                &guest_id,
+                &locale.code(),
+                &filter.org_id,
+                &filter.group_id,
+                &filter.region.as_ref().map(Region::code),
+            ],
+        )
         .await
 }
 
@@ -239,16 +412,33 @@ async fn query_vote_stack_from_current(
     client: &deadpool_postgres::Object,
     current_vtuber_id: i64,
     guest_id: String,
+    locale: Locale,
+    filter: &DeckFilter,
 ) -> Result<tokio_postgres::Row, tokio_postgres::Error> {
     let statement = client
-        .prepare_typed(
-            "SELECT * FROM app.get_vote_stack_from_current($1, $2::UUID)",
-            &[Type::INT8, Type::TEXT],
+        .prepare_typed_cached(
+            "SELECT * FROM app.get_vote_stack_from_current(
+                $1,
+                $2::UUID,
+                $3::app.LOCALE,
+                ROW($4::INTEGER, $5::INTEGER, $6::app.REGION)::app.DECK_FILTER
+            )",
+            &[Type::INT8, Type::TEXT, Type::TEXT, Type::INT4, Type::INT4, Type::TEXT],
         )
         .await?;
 
     client
-        .query_one(&statement, &[&current_vtuber_id, &guest_id])
+        .query_one(
+            &statement,
+            &[
+                &current_vtuber_id,
+                &guest_id,
+                &locale.code(),
+                &filter.org_id,
+                &filter.group_id,
+                &filter.region.as_ref().map(Region::code),
+            ],
+        )
         .await
 }
 
@@ -256,10 +446,10 @@ async fn query_vote_stack_from_current(
 mod tests {
     use serde_json::{json, Value};
 
-    use super::Stack;
+    use super::{Details, Metrics, PathId, Stack};
     use crate::oshismash::{
         vote::{Stat, UserAction},
-        vtubers::{VTuber, Org},
+        vtubers::{Org, Region, VTuber},
     };
 
     fn mock_has_current_no_prev() -> Value {
@@ -267,11 +457,16 @@ mod tests {
             "current": {
                 "description": "A weirdo",
                 "id": 1,
+                "slug": "nyatasha-nyanners",
                 "img": "https://www.vshojo.com/wp-content/uploads/nyanners-full_solo.png",
                 "name": "Nyatasha Nyanners",
                 "next": 2,
+                "next_slug": "veibae",
                 "org_name": "VShojo",
+                "group_name": Value::Null,
+                "region": "en",
                 "prev": Value::Null,
+                "prev_slug": Value::Null,
             },
             "results": Value::Null,
             "vote_for_current": "smashed",
@@ -293,6 +488,7 @@ mod tests {
             "current": Value::Null,
             "results": {
                 "vtuber_id": 2,
+                "slug": "veibae",
                 "name": "Veibae",
                 "description": "A weirdo",
                 "img": "https://www.vshojo.com/wp-content/uploads/nyanners-full_solo.png",
@@ -300,6 +496,7 @@ mod tests {
                 "prev": 1,
                 "smashes": 4,
                 "passes": 1,
+                "skips": 2,
             },
             "vote_for_current": "smashed",
             "voted": [1, 2, 3, 4],
@@ -311,14 +508,20 @@ mod tests {
             "current": {
                 "description": "A weirdo",
                 "id": 1,
+                "slug": "nyatasha-nyanners",
                 "img": "https://www.vshojo.com/wp-content/uploads/nyanners-full_solo.png",
                 "name": "Nyatasha Nyanners",
                 "next": 2,
+                "next_slug": "veibae",
                 "org_name": "VShojo",
+                "group_name": Value::Null,
+                "region": "en",
                 "prev": Value::Null,
+                "prev_slug": Value::Null,
             },
             "results": {
                 "vtuber_id": 2,
+                "slug": "veibae",
                 "name": "Veibae",
                 "description": "A weirdo",
                 "img": "https://www.vshojo.com/wp-content/uploads/nyanners-full_solo.png",
@@ -326,6 +529,7 @@ mod tests {
                 "prev": 1,
                 "smashes": 4,
                 "passes": 1,
+                "skips": 2,
             },
             "vote_for_current": "smashed",
             "voted": [1, 2, 3, 4]
@@ -344,13 +548,20 @@ mod tests {
             Stack::NoPrev {
                 current: VTuber {
                     id: 1,
+                    slug: "nyatasha-nyanners".to_string(),
                     name: "Nyatasha Nyanners".to_string(),
                     description: "A weirdo".to_string(),
                     org_name: Org::Vshojo,
+                    group_name: None,
+                    region: Region::En,
                     next: Some(2),
+                    next_slug: Some("veibae".to_string()),
                     prev: None,
-                    img: "https://www.vshojo.com/wp-content/uploads/nyanners-full_solo.png"
-                        .to_string(),
+                    prev_slug: None,
+                    img: Some(
+                        "https://www.vshojo.com/wp-content/uploads/nyanners-full_solo.png"
+                            .to_string()
+                    ),
                 },
                 voted: voted_ids,
                 vote_for_current: Some(UserAction::Smashed)
@@ -370,6 +581,7 @@ mod tests {
             Stack::NoCurrent {
                 prev_result: Stat {
                     vtuber_id: 2,
+                    slug: "veibae".to_string(),
                     name: "Veibae".to_string(),
                     img: Some(
                         "https://www.vshojo.com/wp-content/uploads/nyanners-full_solo.png"
@@ -377,6 +589,7 @@ mod tests {
                     ),
                     smashes: 4,
                     passes: 1,
+                    skips: 2,
                 },
                 voted: voted_ids,
             }
@@ -395,6 +608,7 @@ mod tests {
             Stack::HasBoth {
                 prev_result: Stat {
                     vtuber_id: 2,
+                    slug: "veibae".to_string(),
                     name: "Veibae".to_string(),
                     img: Some(
                         "https://www.vshojo.com/wp-content/uploads/nyanners-full_solo.png"
@@ -402,16 +616,24 @@ mod tests {
                     ),
                     smashes: 4,
                     passes: 1,
+                    skips: 2,
                 },
                 current: VTuber {
                     id: 1,
+                    slug: "nyatasha-nyanners".to_string(),
                     name: "Nyatasha Nyanners".to_string(),
                     description: "A weirdo".to_string(),
                     org_name: Org::Vshojo,
+                    group_name: None,
+                    region: Region::En,
                     next: Some(2),
+                    next_slug: Some("veibae".to_string()),
                     prev: None,
-                    img: "https://www.vshojo.com/wp-content/uploads/nyanners-full_solo.png"
-                        .to_string(),
+                    prev_slug: None,
+                    img: Some(
+                        "https://www.vshojo.com/wp-content/uploads/nyanners-full_solo.png"
+                            .to_string()
+                    ),
                 },
                 voted: voted_ids,
                 vote_for_current: Some(UserAction::Smashed)
@@ -425,4 +647,49 @@ mod tests {
 
         assert!(found.clone().is_none());
     }
+
+    #[test]
+    fn parse_details() {
+        let found: Details = serde_json::from_value(json!({
+            "id": 1,
+            "name": "Nyatasha Nyanners",
+            "description": "A weirdo",
+            "img": Value::Null,
+            "org_name": "VShojo",
+            "group_name": Value::Null,
+            "region": "en",
+            "youtube": "https://youtube.com/c/Nyanners",
+            "twitch": Value::Null,
+            "twitter": Value::Null,
+            "stats": {
+                "all_time": { "smashes": 10, "passes": 4, "skips": 1 },
+                "last_week": { "smashes": 0, "passes": 0, "skips": 0 },
+                "last_month": { "smashes": 3, "passes": 1, "skips": 0 },
+            },
+            "org_rank": 2,
+            "org_size": 5,
+        }))
+        .unwrap();
+
+        assert_eq!(found.youtube, Some("https://youtube.com/c/Nyanners".to_string()));
+        assert_eq!(
+            found.stats.all_time,
+            Metrics {
+                smashes: 10,
+                passes: 4,
+                skips: 1,
+            }
+        );
+        assert_eq!(found.org_rank, 2);
+    }
+
+    #[test]
+    fn numeric_paths_are_ids() {
+        assert_eq!(PathId::parse("12"), PathId::Id(12));
+        assert_eq!(
+            PathId::parse("nyatasha-nyanners"),
+            PathId::Slug("nyatasha-nyanners".to_string())
+        );
+        assert_eq!(PathId::parse("vtuber-12"), PathId::Slug("vtuber-12".to_string()));
+    }
 }
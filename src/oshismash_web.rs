@@ -1,7 +1,14 @@
 pub(crate) mod components;
+pub(crate) mod error;
 pub(crate) mod handlers;
+pub(crate) mod i18n;
 pub(crate) mod views;
 
+mod admin;
 mod client_data;
 mod cookie_util;
+mod deck;
+mod flash;
+pub(crate) mod shutdown;
+mod urls;
 mod vtubers;
@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use axum::{
+    async_trait,
+    extract::{FromRequest, RequestParts, TypedHeader},
+    headers::{authorization::Basic, Authorization},
+    Extension,
+};
+use hyper::{
+    header::{ORIGIN, REFERER},
+    HeaderMap,
+};
+
+use crate::{config, oshismash, oshismash_web::urls::BaseUrl};
+
+/// Proof that the request comes from a moderator. Moderators sign in with HTTP
+/// basic auth, with any username, and the admin token as the password. Nobody
+/// can if there's no admin token.
+///
+/// Browsers send the credentials along with any request to the app, even one
+/// made by a form on another site. So anything other than a `GET` also has to
+/// come from one of the app's own pages.
+#[derive(Debug)]
+pub struct Admin;
+
+#[async_trait]
+impl<B> FromRequest<B> for Admin
+where
+    B: Send,
+{
+    type Rejection = oshismash::Error;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let Extension(app_config) = req.extract::<Extension<Arc<config::AppConfig>>>().await?;
+        let credentials = req.extract::<TypedHeader<Authorization<Basic>>>().await;
+
+        match (&app_config.admin_token, credentials) {
+            (Some(admin_token), Ok(TypedHeader(Authorization(basic))))
+                if constant_time_eq(admin_token.as_bytes(), basic.password().as_bytes()) => {}
+            _ => return Err(oshismash::Error::NotAdmin),
+        }
+
+        if req.method().is_safe() {
+            return Ok(Admin);
+        }
+
+        let base_url = req.extract::<BaseUrl>().await?;
+
+        if is_from_the_app(&base_url, req.headers()) {
+            Ok(Admin)
+        } else {
+            Err(oshismash::Error::CrossSiteRequest)
+        }
+    }
+}
+
+/// Checks `Origin`, or `Referer` for the browsers that leave `Origin` out.
+/// Requests with neither don't count, since the one making them can't be told.
+fn is_from_the_app(base_url: &BaseUrl, headers: &HeaderMap) -> bool {
+    if let Some(origin) = headers.get(ORIGIN) {
+        return origin.to_str().ok() == Some(base_url.origin());
+    }
+
+    let referer = headers
+        .get(REFERER)
+        .and_then(|referer| referer.to_str().ok());
+
+    matches!(referer, Some(referer) if base_url.contains(referer))
+}
+
+/// Compares the whole token every time so that how long it takes doesn't give
+/// away how much of it was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::{HeaderMap, Uri};
+
+    use super::{constant_time_eq, is_from_the_app};
+    use crate::{config::Layers, oshismash_web::urls::BaseUrl};
+
+    fn is_from(headers: &[(&'static str, &str)]) -> bool {
+        let mut layers = Layers::default();
+
+        layers.add_env(|var| {
+            let value = match var {
+                "APP__STATIC_ASSETS" => Some("."),
+                "APP__PUBLIC_URL" => Some("https://oshismash.com"),
+                "APP__BASE_PATH" => Some("/oshismash"),
+                "DATABASE_URL" => Some("postgres://oshismash@localhost/oshismash"),
+                _ => None,
+            };
+
+            value.map(String::from)
+        });
+
+        let app_config = layers.build().unwrap();
+        let base_url = BaseUrl::new(&app_config, &Uri::from_static("/"), &HeaderMap::new());
+
+        let headers = headers
+            .iter()
+            .map(|(name, value)| (name.parse().unwrap(), value.parse().unwrap()))
+            .collect::<HeaderMap>();
+
+        is_from_the_app(&base_url, &headers)
+    }
+
+    #[test]
+    fn changes_have_to_come_from_the_app() {
+        assert!(is_from(&[("origin", "https://oshismash.com")]));
+        assert!(is_from(&[(
+            "referer",
+            "https://oshismash.com/oshismash/admin/reports"
+        )]));
+
+        assert!(!is_from(&[]));
+        assert!(!is_from(&[("origin", "null")]));
+        assert!(!is_from(&[("origin", "https://evil.example")]));
+        assert!(!is_from(&[("referer", "https://evil.example/oshismash")]));
+        assert!(!is_from(&[
+            ("origin", "https://evil.example"),
+            ("referer", "https://oshismash.com/oshismash/admin/reports"),
+        ]));
+    }
+
+    #[test]
+    fn tokens_have_to_match_exactly() {
+        assert!(constant_time_eq(b"hunter2", b"hunter2"));
+        assert!(!constant_time_eq(b"hunter2", b"hunter3"));
+        assert!(!constant_time_eq(b"hunter2", b"hunter"));
+        assert!(!constant_time_eq(b"", b"hunter2"));
+    }
+}
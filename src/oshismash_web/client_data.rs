@@ -1,23 +1,13 @@
-use std::sync::Arc;
-
-use axum::{
-    async_trait,
-    extract::{FromRequest, Path},
-    Extension,
-};
+use axum::{async_trait, extract::FromRequest, Extension};
 use axum_extra::extract::CookieJar;
 
-use crate::{
-    db,
-    oshismash::{self, guests, vtubers::VTuberId},
-};
+use crate::oshismash::{self, store, vtubers::VTuberId};
 
 /// Contains the settings and other data from the client-side of things.
 #[derive(Debug)]
 pub struct ClientData {
     pub guest_id: String,
     pub vtuber_id: VTuberId,
-    pub max_visited: i64,
 }
 
 /// Represents the VTuber in the client's UI. This information is stored in 2
@@ -65,45 +55,30 @@ where
     ) -> Result<Self, Self::Rejection> {
         // NOTE: It's infallible so I guess it's safe to unwrap?
         let jar = req.extract::<CookieJar>().await.unwrap();
-        let db = req.extract::<Extension<Arc<db::Handle>>>().await?;
-        let vtuber_id_path = req.extract::<Path<String>>().await;
+        let Extension(store) = req.extract::<Extension<store::Handle>>().await?;
 
-        let vtuber_id = match vtuber_id_path {
-            Ok(Path(path)) => match path.parse::<i64>() {
-                Ok(id) => VTuberId::Current(id),
-                Err(_) => VTuberId::Current(1),
-            },
-            Err(_) => match VTuberId::try_from(&jar) {
-                Ok(vtuber_id) => vtuber_id,
-                Err(_) => VTuberId::Current(1),
-            },
+        // The VTuber in the path, if any, is resolved by the handler since it
+        // needs a DB lookup, and unknown ones should 404.
+        let vtuber_id = match VTuberId::try_from(&jar) {
+            Ok(vtuber_id) => vtuber_id,
+            Err(_) => VTuberId::Current(1),
         };
 
         let guest_id = jar.get("id").and_then(|c| Some(c.value().to_string()));
 
-        let max_visited = jar
-            .get("max_visited")
-            .and_then(|c| c.value().parse::<i64>().ok())
-            .or(Some(1));
-
-        match (guest_id, max_visited) {
-            (Some(guest_id), Some(max_visited)) => Ok(ClientData {
+        match guest_id {
+            Some(guest_id) => Ok(ClientData {
                 vtuber_id,
                 guest_id,
-                max_visited,
             }),
-            (None, Some(max_visited)) => {
-                let client = db.pool.get().await?;
+            None => {
+                let guest = store.create_guest().await?;
 
-                guests::create_guest(&client).await.and_then(|g| {
-                    Ok(ClientData {
-                        vtuber_id,
-                        guest_id: g.guest_id.0,
-                        max_visited,
-                    })
+                Ok(ClientData {
+                    vtuber_id,
+                    guest_id: guest.guest_id.0,
                 })
             }
-            _ => Err(oshismash::Error::InvalidClientData),
         }
     }
 }
@@ -1,2 +1,3 @@
 pub mod card;
 pub mod icon;
+pub mod image;
@@ -0,0 +1,49 @@
+use maud::{html, Markup};
+
+use crate::{
+    media::{self, Format, Variant, WIDTHS},
+    oshismash_web::urls,
+};
+
+/// Shown for VTubers whose image hasn't been processed yet.
+pub const PLACEHOLDER: &str = "/assets/placeholder.svg";
+
+pub fn url(base_path: &str, key: &str, variant: Variant) -> String {
+    urls::path(
+        base_path,
+        &format!("/media/{}/{}", key, variant.file_name()),
+    )
+}
+
+/// Variants that are the same unscaled copy of a small source are left out,
+/// and the last one is given the source's real width.
+fn srcset(base_path: &str, key: &str, format: Format) -> String {
+    media::real_widths(key)
+        .into_iter()
+        .map(|(width, real_width)| {
+            format!(
+                "{} {}w",
+                url(base_path, key, Variant { width, format }),
+                real_width
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Picture of a VTuber. `sizes` is how wide it's displayed, as in the `sizes`
+/// attribute, so the browser can pick the smallest variant that fits.
+pub fn vtuber(base_path: &str, img: Option<&str>, alt: &str, sizes: &str, class: &str) -> Markup {
+    match img {
+        Some(key) => html! {
+            picture class="contents" {
+                source type=(Format::Avif.content_type()) srcset=(srcset(base_path, key, Format::Avif)) sizes=(sizes);
+                source type=(Format::Webp.content_type()) srcset=(srcset(base_path, key, Format::Webp)) sizes=(sizes);
+                img class=(class) alt=(alt) loading="lazy" src=(url(base_path, key, Variant { width: WIDTHS[1], format: Format::Webp }));
+            }
+        },
+        None => html! {
+            img class=(class) alt=(alt) src=(urls::path(base_path, PLACEHOLDER));
+        },
+    }
+}
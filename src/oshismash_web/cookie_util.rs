@@ -1,4 +1,5 @@
 use cookie::{time::Duration, Cookie, SameSite};
+use hyper::{header::COOKIE, HeaderMap};
 
 pub fn create<'a, K, V>(name: K, value: V) -> Cookie<'a>
 where
@@ -14,3 +15,16 @@ where
 
     cookie
 }
+
+/// Gets the value of a cookie straight from the request headers. Useful in
+/// middleware, where there's no `CookieJar` extractor.
+pub fn get(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| Cookie::parse(pair.trim()).ok())
+        .find(|cookie| cookie.name() == name)
+        .map(|cookie| cookie.value().to_string())
+}
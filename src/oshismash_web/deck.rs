@@ -0,0 +1,37 @@
+use axum::{
+    async_trait,
+    extract::{FromRequest, Query, RequestParts},
+};
+use serde::Deserialize;
+
+use crate::oshismash::{self, deck::DeckFilter};
+
+/// Raw query parameters of the filter. They're kept as strings since the
+/// picker submits empty values for "any".
+#[derive(Deserialize)]
+struct FilterParams {
+    org: Option<String>,
+    group: Option<String>,
+    region: Option<String>,
+}
+
+#[async_trait]
+impl<B> FromRequest<B> for DeckFilter
+where
+    B: Send,
+{
+    type Rejection = oshismash::Error;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let filter = match req.extract::<Query<FilterParams>>().await {
+            Ok(Query(params)) => DeckFilter::from_params(
+                params.org.as_deref(),
+                params.group.as_deref(),
+                params.region.as_deref(),
+            ),
+            Err(_) => DeckFilter::default(),
+        };
+
+        Ok(filter)
+    }
+}
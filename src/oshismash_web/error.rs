@@ -0,0 +1,292 @@
+use std::sync::Arc;
+
+use axum::{
+    http::{
+        header::{ACCEPT, CONTENT_LENGTH, REFERER, RETRY_AFTER, WWW_AUTHENTICATE},
+        HeaderValue, Request,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use deadpool_postgres::PoolError;
+use hyper::StatusCode;
+use serde_json::json;
+
+use super::{
+    i18n::{self, ErrorMessage, Text},
+    urls::{self, BaseUrl},
+    views,
+};
+use crate::{
+    config,
+    oshismash::{locale::Locale, vote, Error},
+};
+
+/// How long clients should wait before retrying when the server is too busy
+const RETRY_AFTER_SECONDS: &str = "5";
+
+impl IntoResponse for Error {
+    fn into_response(self: Error) -> Response {
+        // Makes browsers ask for the admin token.
+        let challenge = matches!(self, Error::NotAdmin);
+
+        // Every DB connection is in use (or the server is shutting down), so
+        // clients are told to back off rather than left waiting.
+        let busy = matches!(
+            self,
+            Error::PoolError(PoolError::Timeout(_) | PoolError::Closed)
+        );
+
+        let (status, code, text) = match self {
+            Error::UnableToQuery(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "E001",
+                Text::ErrQueryFailed,
+            ),
+            Error::FailedToSetupDb(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "E002",
+                Text::ErrDbSetupFailed,
+            ),
+            Error::InvalidGuest => (StatusCode::UNAUTHORIZED, "E003", Text::ErrInvalidGuest),
+            Error::PoolError(PoolError::Timeout(_) | PoolError::Closed) => {
+                (StatusCode::SERVICE_UNAVAILABLE, "E25", Text::ErrBusy)
+            }
+            Error::PoolError(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "E004",
+                Text::ErrPoolFailed,
+            ),
+            Error::VoteParseError(e) => {
+                println!("{}", e);
+                (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    "E005",
+                    Text::ErrVoteParseFailed,
+                )
+            }
+            Error::FailedToParseStack(_e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "E006",
+                Text::ErrStackParseFailed,
+            ),
+            Error::StackParseFailed => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "E006",
+                Text::ErrStackParseFailed,
+            ),
+            Error::MissingDbHandleExtension => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "E007",
+                Text::ErrMissingDbHandle,
+            ),
+            Error::InvalidClientData => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "E008",
+                Text::ErrVoteNotDisplayed,
+            ),
+            Error::NotAllowedToVote => (StatusCode::FORBIDDEN, "E009", Text::ErrVotePreviousFirst),
+            Error::InvalidForm(_) => (StatusCode::BAD_REQUEST, "E11", Text::ErrInvalidForm),
+            Error::EmptyDeck => (StatusCode::NOT_FOUND, "E16", Text::ErrEmptyDeck),
+            Error::VTuberNotFound => (StatusCode::NOT_FOUND, "E17", Text::ErrVTuberNotFound),
+            Error::NotAdmin => (StatusCode::UNAUTHORIZED, "E18", Text::ErrNotAdmin),
+            Error::CrossSiteRequest => (StatusCode::FORBIDDEN, "E26", Text::ErrCrossSiteRequest),
+            Error::TooManySuggestions => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "E19",
+                Text::ErrTooManySuggestions,
+            ),
+            Error::InvalidSuggestion(_) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "E20",
+                Text::ErrInvalidSuggestion,
+            ),
+            Error::SuggestionNotFound => {
+                (StatusCode::NOT_FOUND, "E21", Text::ErrSuggestionNotFound)
+            }
+            Error::AlreadyReported => (StatusCode::CONFLICT, "E22", Text::ErrAlreadyReported),
+            Error::InvalidReport(_) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "E23",
+                Text::ErrInvalidReport,
+            ),
+            Error::ReportNotFound => (StatusCode::NOT_FOUND, "E24", Text::ErrReportNotFound),
+            Error::InvalidFormFormat => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "E12",
+                Text::ErrInvalidFormFormat,
+            ),
+            Error::VoteError(e) => match e {
+                vote::VoteError::QueryPrepFailed(_) => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "E13",
+                    Text::ErrVoteNotCounted,
+                ),
+                vote::VoteError::QueryFailed(_) => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "E13",
+                    Text::ErrVoteNotCounted,
+                ),
+                vote::VoteError::InvalidDbValue => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "E14",
+                    Text::ErrVoteResultFailed,
+                ),
+            },
+        };
+
+        // The plain text body is only a fallback. `render` replaces it with a
+        // page in the client's locale using the extension.
+        let message = ErrorMessage { code, text };
+
+        let mut res = (status, Extension(message), message.localize(Locale::En)).into_response();
+
+        if challenge {
+            res.headers_mut().insert(
+                WWW_AUTHENTICATE,
+                HeaderValue::from_static("Basic realm=\"oshismash admin\""),
+            );
+        }
+
+        if busy {
+            res.headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from_static(RETRY_AFTER_SECONDS));
+        }
+
+        res
+    }
+}
+
+impl IntoResponse for vote::ParseError {
+    fn into_response(self) -> Response {
+        Error::VoteParseError(self).into_response()
+    }
+}
+
+/// Middleware that renders the errors of failed requests. Browsers get an HTML
+/// page with a link back to where they were, while API clients that prefer
+/// JSON get `{ "code": ..., "message": ... }`.
+pub async fn render<B>(req: Request<B>, next: Next<B>) -> Response {
+    let locale = req
+        .extensions()
+        .get::<Locale>()
+        .copied()
+        .unwrap_or_default();
+
+    let wants_json = req
+        .headers()
+        .get(ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .map_or(false, prefers_json);
+
+    let base_path = req
+        .extensions()
+        .get::<Arc<config::AppConfig>>()
+        .map(|app_config| app_config.base_path.clone())
+        .unwrap_or_default();
+
+    // Only go back to the referring page if it's one of ours.
+    let back_url = match (
+        req.headers().get(REFERER).and_then(|r| r.to_str().ok()),
+        req.extensions().get::<Arc<config::AppConfig>>(),
+    ) {
+        (Some(referer), Some(app_config))
+            if BaseUrl::new(app_config, req.uri(), req.headers()).contains(referer) =>
+        {
+            referer.to_string()
+        }
+        _ => urls::path(&base_path, "/"),
+    };
+
+    let res = next.run(req).await;
+
+    let message = match res.extensions().get::<ErrorMessage>() {
+        Some(message) => *message,
+        None => return res,
+    };
+
+    let (mut parts, _) = res.into_parts();
+
+    let page = if wants_json {
+        Json(json!({
+            "code": message.code,
+            "message": message.explain(locale),
+        }))
+        .into_response()
+    } else {
+        views::root::render(
+            locale,
+            &base_path,
+            &views::root::Meta::new(i18n::t(locale, Text::ErrorTitle), locale),
+            None,
+            views::error::render(locale, &base_path, &message, parts.status, &back_url),
+        )
+        .into_response()
+    };
+
+    let (page_parts, body) = page.into_parts();
+
+    parts.headers.remove(CONTENT_LENGTH);
+    parts.headers.extend(page_parts.headers);
+
+    Response::from_parts(parts, body)
+}
+
+/// Whether an `Accept` header prefers JSON over HTML. Wildcards don't count,
+/// so clients that accept anything get HTML.
+fn prefers_json(accept: &str) -> bool {
+    let quality_of = |media_type: &str| {
+        accept
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';');
+                let range = parts.next()?.trim();
+
+                if range.eq_ignore_ascii_case(media_type) {
+                    parts
+                        .find_map(|param| param.trim().strip_prefix("q="))
+                        .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())
+                } else {
+                    None
+                }
+            })
+            .fold(0.0_f32, f32::max)
+    };
+
+    quality_of("application/json") > quality_of("text/html")
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::response::IntoResponse;
+    use deadpool_postgres::PoolError;
+    use hyper::{header::RETRY_AFTER, StatusCode};
+
+    use super::prefers_json;
+    use crate::oshismash::Error;
+
+    #[test]
+    fn busy_pools_ask_clients_to_retry() {
+        let res = Error::PoolError(PoolError::Closed).into_response();
+
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(res.headers()[RETRY_AFTER], "5");
+    }
+
+    #[test]
+    fn browsers_get_html() {
+        assert!(!prefers_json(
+            "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8"
+        ));
+        assert!(!prefers_json("*/*"));
+        assert!(!prefers_json("text/html, application/json"));
+    }
+
+    #[test]
+    fn api_clients_get_json() {
+        assert!(prefers_json("application/json"));
+        assert!(prefers_json("application/json, text/html;q=0.5"));
+        assert!(prefers_json("text/html;q=0.1, application/json;q=0.2"));
+    }
+}
@@ -0,0 +1,171 @@
+use axum_extra::extract::cookie::{Cookie, CookieJar};
+
+use super::{cookie_util, i18n::Text};
+
+const FLASH_COOKIE: &str = "flash";
+
+/// One-shot messages that survive a redirect. These are stored in the `flash`
+/// cookie, and removed once they're rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flash {
+    /// Guest tried to skip ahead of the VTubers they haven't voted for yet.
+    VotePreviousFirst,
+    /// Guest changed their vote for a VTuber they already voted for.
+    VoteChanged,
+    /// Guest suggested a VTuber to be added.
+    SuggestionReceived,
+    /// Moderator approved a suggestion.
+    SuggestionApproved,
+    /// Moderator rejected a suggestion.
+    SuggestionRejected,
+    /// Guest reported a VTuber's card.
+    ReportReceived,
+    /// Moderator hid a reported VTuber.
+    ReportResolved,
+    /// Moderator dismissed a report.
+    ReportDismissed,
+    /// Moderator put a hidden VTuber back into the deck.
+    VTuberUnhidden,
+    /// Moderator took a VTuber out of the chain.
+    VTuberRetired,
+    /// Moderator put a retired VTuber back into the chain.
+    VTuberRestored,
+}
+
+impl Flash {
+    fn key(&self) -> &'static str {
+        match self {
+            Flash::VotePreviousFirst => "vote_previous_first",
+            Flash::VoteChanged => "vote_changed",
+            Flash::SuggestionReceived => "suggestion_received",
+            Flash::SuggestionApproved => "suggestion_approved",
+            Flash::SuggestionRejected => "suggestion_rejected",
+            Flash::ReportReceived => "report_received",
+            Flash::ReportResolved => "report_resolved",
+            Flash::ReportDismissed => "report_dismissed",
+            Flash::VTuberUnhidden => "vtuber_unhidden",
+            Flash::VTuberRetired => "vtuber_retired",
+            Flash::VTuberRestored => "vtuber_restored",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Flash> {
+        match key {
+            "vote_previous_first" => Some(Flash::VotePreviousFirst),
+            "vote_changed" => Some(Flash::VoteChanged),
+            "suggestion_received" => Some(Flash::SuggestionReceived),
+            "suggestion_approved" => Some(Flash::SuggestionApproved),
+            "suggestion_rejected" => Some(Flash::SuggestionRejected),
+            "report_received" => Some(Flash::ReportReceived),
+            "report_resolved" => Some(Flash::ReportResolved),
+            "report_dismissed" => Some(Flash::ReportDismissed),
+            "vtuber_unhidden" => Some(Flash::VTuberUnhidden),
+            "vtuber_retired" => Some(Flash::VTuberRetired),
+            "vtuber_restored" => Some(Flash::VTuberRestored),
+            _ => None,
+        }
+    }
+
+    pub fn text(&self) -> Text {
+        match self {
+            Flash::VotePreviousFirst => Text::ErrVotePreviousFirst,
+            Flash::VoteChanged => Text::FlashVoteChanged,
+            Flash::SuggestionReceived => Text::FlashSuggestionReceived,
+            Flash::SuggestionApproved => Text::FlashSuggestionApproved,
+            Flash::SuggestionRejected => Text::FlashSuggestionRejected,
+            Flash::ReportReceived => Text::FlashReportReceived,
+            Flash::ReportResolved => Text::FlashReportResolved,
+            Flash::ReportDismissed => Text::FlashReportDismissed,
+            Flash::VTuberUnhidden => Text::FlashVTuberUnhidden,
+            Flash::VTuberRetired => Text::FlashVTuberRetired,
+            Flash::VTuberRestored => Text::FlashVTuberRestored,
+        }
+    }
+
+    /// Whether the message is a warning, rather than a confirmation.
+    pub fn is_warning(&self) -> bool {
+        match self {
+            Flash::VotePreviousFirst => true,
+            Flash::VoteChanged
+            | Flash::SuggestionReceived
+            | Flash::SuggestionApproved
+            | Flash::SuggestionRejected
+            | Flash::ReportReceived
+            | Flash::ReportResolved
+            | Flash::ReportDismissed
+            | Flash::VTuberUnhidden
+            | Flash::VTuberRetired
+            | Flash::VTuberRestored => false,
+        }
+    }
+}
+
+/// Sets the flash message to be shown after the redirect.
+pub fn set(jar: CookieJar, flash: Flash) -> CookieJar {
+    let mut cookie = cookie_util::create(FLASH_COOKIE, flash.key());
+    cookie.set_path("/");
+
+    jar.add(cookie)
+}
+
+/// Takes the flash message out of the jar, if there's any. The cookie is
+/// removed so the message only shows up once.
+pub fn take(jar: CookieJar) -> (CookieJar, Option<Flash>) {
+    match jar.get(FLASH_COOKIE).map(|c| Flash::from_key(c.value())) {
+        Some(flash) => {
+            let mut cookie = Cookie::named(FLASH_COOKIE);
+            cookie.set_path("/");
+
+            (jar.remove(cookie), flash)
+        }
+        None => (jar, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum_extra::extract::cookie::{Cookie, CookieJar};
+
+    use super::{set, take, Flash, FLASH_COOKIE};
+
+    #[test]
+    fn flash_roundtrip() {
+        for flash in [
+            Flash::VotePreviousFirst,
+            Flash::VoteChanged,
+            Flash::SuggestionReceived,
+            Flash::SuggestionApproved,
+            Flash::SuggestionRejected,
+            Flash::ReportReceived,
+            Flash::ReportResolved,
+            Flash::ReportDismissed,
+            Flash::VTuberUnhidden,
+            Flash::VTuberRetired,
+            Flash::VTuberRestored,
+        ] {
+            assert_eq!(Flash::from_key(flash.key()), Some(flash));
+        }
+    }
+
+    #[test]
+    fn take_is_one_shot() {
+        let jar = set(CookieJar::new(), Flash::VoteChanged);
+        assert_eq!(jar.get(FLASH_COOKIE).unwrap().value(), "vote_changed");
+
+        let (jar, flash) = take(jar);
+        assert_eq!(flash, Some(Flash::VoteChanged));
+        assert!(jar.get(FLASH_COOKIE).is_none());
+
+        let (_, flash) = take(jar);
+        assert_eq!(flash, None);
+    }
+
+    #[test]
+    fn unknown_flash_is_ignored() {
+        let jar = CookieJar::new().add(Cookie::new(FLASH_COOKIE, "<script>"));
+
+        let (jar, flash) = take(jar);
+        assert_eq!(flash, None);
+        assert!(jar.get(FLASH_COOKIE).is_none());
+    }
+}
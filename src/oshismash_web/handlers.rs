@@ -1,2 +1,12 @@
+pub mod admin;
+pub mod deck;
+pub mod health;
+pub mod leaderboard;
+pub mod locale;
+pub mod metrics;
+pub mod media;
+pub mod reports;
+pub mod search;
+pub mod suggestions;
 pub mod vote;
 pub mod vtuber;
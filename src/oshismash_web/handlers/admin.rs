@@ -0,0 +1,289 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Form, Path},
+    Extension,
+};
+use axum_extra::extract::cookie;
+use hyper::{HeaderMap, StatusCode};
+use maud::Markup;
+use serde::Deserialize;
+
+use crate::oshismash::{
+    self, deck,
+    locale::Locale,
+    reports,
+    suggestions::{self, Approval},
+    vtubers::Region,
+};
+use crate::oshismash_web::admin::Admin;
+use crate::oshismash_web::flash::{self, Flash};
+use crate::oshismash_web::urls;
+use crate::oshismash_web::views;
+use crate::{config, db};
+
+/// Moderation queue of the suggestions that guests made.
+pub async fn suggestions(
+    _: Admin,
+    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    Extension(locale): Extension<Locale>,
+    jar: cookie::CookieJar,
+) -> Result<(cookie::CookieJar, Markup), oshismash::Error> {
+    let client = db_handle.pool.get().await?;
+
+    let pending = suggestions::get_pending(&client).await?;
+    let options = deck::get_options(&client).await?;
+    let order = deck::get_order(&client).await?;
+
+    let (jar, flash) = flash::take(jar);
+
+    Ok((
+        jar,
+        views::root::render(
+            locale,
+            &app_config.base_path,
+            &views::root::Meta::new("Suggestions", locale),
+            flash,
+            views::admin::suggestions(&app_config.base_path, &pending, &options, &order),
+        ),
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct ApprovalForm {
+    name: String,
+    #[serde(default)]
+    description: String,
+    org: i32,
+    #[serde(default)]
+    group: String,
+    #[serde(default)]
+    region: String,
+    #[serde(default)]
+    img_source: String,
+    #[serde(default)]
+    youtube: String,
+    #[serde(default)]
+    twitch: String,
+    #[serde(default)]
+    twitter: String,
+    #[serde(default)]
+    after: String,
+}
+
+/// Blank fields are left out rather than stored as empty strings.
+fn non_empty(value: String) -> Option<String> {
+    match value.trim() {
+        "" => None,
+        value => Some(value.to_string()),
+    }
+}
+
+pub async fn approve(
+    _: Admin,
+    Path(suggestion_id): Path<i64>,
+    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    jar: cookie::CookieJar,
+    Form(form): Form<ApprovalForm>,
+) -> Result<(StatusCode, HeaderMap, cookie::CookieJar), oshismash::Error> {
+    let approval = Approval {
+        suggestion_id,
+        name: form.name,
+        description: form.description,
+        org_id: form.org,
+        group_id: form.group.trim().parse().ok(),
+        region: Region::from_code(&form.region).unwrap_or(Region::None),
+        img_source: non_empty(form.img_source),
+        youtube: non_empty(form.youtube),
+        twitch: non_empty(form.twitch),
+        twitter: non_empty(form.twitter),
+        after: form.after.trim().parse().ok(),
+    };
+
+    let client = db_handle.pool.get().await?;
+    suggestions::approve(&client, &approval).await?;
+
+    Ok(back_to(
+        &app_config,
+        "/admin/suggestions",
+        flash::set(jar, Flash::SuggestionApproved),
+    ))
+}
+
+pub async fn reject(
+    _: Admin,
+    Path(suggestion_id): Path<i64>,
+    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    jar: cookie::CookieJar,
+) -> Result<(StatusCode, HeaderMap, cookie::CookieJar), oshismash::Error> {
+    let client = db_handle.pool.get().await?;
+    suggestions::reject(&client, suggestion_id).await?;
+
+    Ok(back_to(
+        &app_config,
+        "/admin/suggestions",
+        flash::set(jar, Flash::SuggestionRejected),
+    ))
+}
+
+/// Moderation queue of the reports that guests made, along with the VTubers
+/// that are hidden.
+pub async fn reports(
+    _: Admin,
+    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    Extension(locale): Extension<Locale>,
+    jar: cookie::CookieJar,
+) -> Result<(cookie::CookieJar, Markup), oshismash::Error> {
+    let client = db_handle.pool.get().await?;
+
+    let open = reports::get_open(&client).await?;
+    let hidden = reports::get_hidden(&client).await?;
+
+    let (jar, flash) = flash::take(jar);
+
+    Ok((
+        jar,
+        views::root::render(
+            locale,
+            &app_config.base_path,
+            &views::root::Meta::new("Reports", locale),
+            flash,
+            views::admin::reports(&app_config.base_path, &open, &hidden),
+        ),
+    ))
+}
+
+/// Hides the reported VTuber.
+pub async fn resolve_report(
+    _: Admin,
+    Path(report_id): Path<i64>,
+    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    jar: cookie::CookieJar,
+) -> Result<(StatusCode, HeaderMap, cookie::CookieJar), oshismash::Error> {
+    let client = db_handle.pool.get().await?;
+    reports::resolve(&client, report_id).await?;
+
+    Ok(back_to(
+        &app_config,
+        "/admin/reports",
+        flash::set(jar, Flash::ReportResolved),
+    ))
+}
+
+pub async fn dismiss_report(
+    _: Admin,
+    Path(report_id): Path<i64>,
+    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    jar: cookie::CookieJar,
+) -> Result<(StatusCode, HeaderMap, cookie::CookieJar), oshismash::Error> {
+    let client = db_handle.pool.get().await?;
+    reports::dismiss(&client, report_id).await?;
+
+    Ok(back_to(
+        &app_config,
+        "/admin/reports",
+        flash::set(jar, Flash::ReportDismissed),
+    ))
+}
+
+/// Puts a hidden VTuber back into the deck, where they were before.
+pub async fn unhide(
+    _: Admin,
+    Path(vtuber_id): Path<i64>,
+    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    jar: cookie::CookieJar,
+) -> Result<(StatusCode, HeaderMap, cookie::CookieJar), oshismash::Error> {
+    let client = db_handle.pool.get().await?;
+    reports::set_hidden(&client, vtuber_id, false).await?;
+
+    Ok(back_to(
+        &app_config,
+        "/admin/reports",
+        flash::set(jar, Flash::VTuberUnhidden),
+    ))
+}
+
+/// The deck in its usual order, and the VTubers that were retired from it.
+pub async fn vtubers(
+    _: Admin,
+    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    Extension(locale): Extension<Locale>,
+    jar: cookie::CookieJar,
+) -> Result<(cookie::CookieJar, Markup), oshismash::Error> {
+    let client = db_handle.pool.get().await?;
+
+    let order = deck::get_order(&client).await?;
+    let retired = deck::get_retired(&client).await?;
+
+    let (jar, flash) = flash::take(jar);
+
+    Ok((
+        jar,
+        views::root::render(
+            locale,
+            &app_config.base_path,
+            &views::root::Meta::new("VTubers", locale),
+            flash,
+            views::admin::vtubers(&app_config.base_path, &order, &retired),
+        ),
+    ))
+}
+
+pub async fn retire(
+    _: Admin,
+    Path(vtuber_id): Path<i64>,
+    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    jar: cookie::CookieJar,
+) -> Result<(StatusCode, HeaderMap, cookie::CookieJar), oshismash::Error> {
+    let client = db_handle.pool.get().await?;
+    deck::retire(&client, vtuber_id).await?;
+
+    Ok(back_to(
+        &app_config,
+        "/admin/vtubers",
+        flash::set(jar, Flash::VTuberRetired),
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct RestoreForm {
+    #[serde(default)]
+    after: String,
+}
+
+pub async fn restore(
+    _: Admin,
+    Path(vtuber_id): Path<i64>,
+    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    jar: cookie::CookieJar,
+    Form(form): Form<RestoreForm>,
+) -> Result<(StatusCode, HeaderMap, cookie::CookieJar), oshismash::Error> {
+    let client = db_handle.pool.get().await?;
+    deck::restore(&client, vtuber_id, form.after.trim().parse().ok()).await?;
+
+    Ok(back_to(
+        &app_config,
+        "/admin/vtubers",
+        flash::set(jar, Flash::VTuberRestored),
+    ))
+}
+
+fn back_to(
+    app_config: &config::AppConfig,
+    path: &str,
+    jar: cookie::CookieJar,
+) -> (StatusCode, HeaderMap, cookie::CookieJar) {
+    let headers = urls::redirect(&app_config.base_path, path);
+
+    (StatusCode::FOUND, headers, jar)
+}
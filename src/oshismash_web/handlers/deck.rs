@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use axum::{extract::Form, Extension};
+use axum_extra::extract::cookie;
+use hyper::{HeaderMap, StatusCode};
+use maud::Markup;
+use serde::Deserialize;
+
+use crate::oshismash::{
+    self,
+    deck::{self, DeckFilter},
+    locale::Locale,
+    store,
+};
+use crate::oshismash_web::client_data::ClientData;
+use crate::oshismash_web::cookie_util;
+use crate::oshismash_web::handlers::vtuber;
+use crate::oshismash_web::i18n::{self, Text};
+use crate::oshismash_web::urls;
+use crate::oshismash_web::views;
+use crate::{config, db};
+
+/// Lets guests pick what the deck is filtered by. The current filter, if any,
+/// is preselected.
+pub async fn show_filters(
+    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    Extension(locale): Extension<Locale>,
+    client_data: ClientData,
+    filter: DeckFilter,
+    jar: cookie::CookieJar,
+) -> Result<(cookie::CookieJar, Markup), oshismash::Error> {
+    let client = db_handle.pool.get().await?;
+    let options = deck::get_options(&client).await?;
+    let shuffled = deck::is_shuffled(&client, &client_data.guest_id).await?;
+
+    let jar = jar.add(cookie_util::create("id", client_data.guest_id));
+
+    Ok((
+        jar,
+        views::root::render(
+            locale,
+            &app_config.base_path,
+            &views::root::Meta::new(i18n::t(locale, Text::Filters), locale),
+            None,
+            views::deck::render(locale, &app_config.base_path, &options, &filter, shuffled),
+        ),
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct ShuffleForm {
+    shuffled: bool,
+}
+
+/// Turns the shuffled deck on or off, then continues from wherever the guest
+/// should be in the new order.
+pub async fn set_shuffle(
+    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(store): Extension<store::Handle>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    client_data: ClientData,
+    filter: DeckFilter,
+    jar: cookie::CookieJar,
+    Form(form): Form<ShuffleForm>,
+) -> Result<(StatusCode, HeaderMap, cookie::CookieJar), oshismash::Error> {
+    let client = db_handle.pool.get().await?;
+
+    deck::set_shuffle(&client, &client_data.guest_id, form.shuffled).await?;
+
+    let resume_at = deck::get_resume_point(&client, &client_data.guest_id, &filter)
+        .await?
+        .ok_or(oshismash::Error::EmptyDeck)?;
+
+    let jar = jar
+        .add(cookie_util::create("current", resume_at))
+        .add(cookie_util::create("last_visited", "none"))
+        .add(cookie_util::create("id", client_data.guest_id));
+
+    let path = vtuber::deck_path(&*store, resume_at, &filter).await?;
+    let headers = urls::redirect(&app_config.base_path, &path);
+
+    Ok((StatusCode::FOUND, headers, jar))
+}
@@ -0,0 +1,19 @@
+use axum::Extension;
+use hyper::StatusCode;
+
+use crate::oshismash_web::shutdown::Readiness;
+
+/// Whether the process is up at all. This stays fine while shutting down, so
+/// that the process isn't killed before it's done draining.
+pub async fn live() -> &'static str {
+    "ok"
+}
+
+/// Whether the server should be sent new requests.
+pub async fn ready(Extension(readiness): Extension<Readiness>) -> (StatusCode, &'static str) {
+    if readiness.is_ready() {
+        (StatusCode::OK, "ok")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "draining")
+    }
+}
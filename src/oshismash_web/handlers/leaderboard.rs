@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use axum::Extension;
+use maud::Markup;
+
+use crate::oshismash::{self, leaderboard, locale::Locale};
+use crate::oshismash_web::i18n::{self, Text};
+use crate::oshismash_web::views;
+use crate::{config, db};
+
+pub async fn show(
+    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    Extension(locale): Extension<Locale>,
+) -> Result<Markup, oshismash::Error> {
+    let client = db_handle.reader().await?;
+    let entries = leaderboard::get_leaderboard(&client, locale).await?;
+
+    Ok(views::root::render(
+        locale,
+        &app_config.base_path,
+        &views::root::Meta::new(i18n::t(locale, Text::Leaderboard), locale),
+        None,
+        views::leaderboard::render(locale, &app_config.base_path, &entries),
+    ))
+}
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::Extension;
+use axum_extra::extract::cookie;
+use hyper::header::{LOCATION, REFERER};
+use hyper::{HeaderMap, StatusCode};
+
+use crate::config;
+use crate::oshismash::locale::Locale;
+use crate::oshismash_web::urls::{self, BaseUrl};
+use crate::oshismash_web::{cookie_util, i18n};
+
+/// Overrides the locale negotiated from `Accept-Language`, then sends the guest
+/// back to the page they were on.
+pub async fn set_locale(
+    Path(code): Path<String>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    base_url: BaseUrl,
+    request_headers: HeaderMap,
+    jar: cookie::CookieJar,
+) -> (StatusCode, HeaderMap, cookie::CookieJar) {
+    let jar = match Locale::from_code(&code) {
+        Some(locale) => jar.add(cookie_util::create(i18n::LOCALE_COOKIE, locale.code())),
+        None => jar,
+    };
+
+    // Only go back to the referring page if it's one of ours.
+    let back = request_headers.get(REFERER).filter(|referer| {
+        referer
+            .to_str()
+            .ok()
+            .map_or(false, |url| base_url.contains(url))
+    });
+
+    let headers = match back {
+        Some(referer) => HeaderMap::from_iter([(LOCATION, referer.clone())]),
+        None => urls::redirect(&app_config.base_path, "/"),
+    };
+
+    (StatusCode::FOUND, headers, jar)
+}
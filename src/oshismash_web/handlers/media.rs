@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, Extension};
+use hyper::{
+    header::{CACHE_CONTROL, CONTENT_TYPE},
+    HeaderMap, StatusCode,
+};
+
+use crate::config;
+use crate::media::{self, Variant};
+
+/// Serves the processed VTuber images. Keys are hashes of the source, so the
+/// files behind a URL never change and can be cached for as long as browsers
+/// allow. Anything missing is a plain 404 so that it isn't cached.
+pub async fn show(
+    Path((key, file_name)): Path<(String, String)>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+) -> Result<(HeaderMap, Vec<u8>), StatusCode> {
+    let variant = Variant::from_file_name(&file_name)
+        .filter(|_| media::is_valid_key(&key))
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let bytes = media::Store::new(&app_config.media_path)
+        .read(&key, variant)
+        .await
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+
+    let mut headers = HeaderMap::new();
+
+    headers.insert(CONTENT_TYPE, variant.format.content_type().parse().unwrap());
+    headers.insert(
+        CACHE_CONTROL,
+        "public, max-age=31536000, immutable".parse().unwrap(),
+    );
+
+    Ok((headers, bytes))
+}
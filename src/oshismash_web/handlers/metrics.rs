@@ -0,0 +1,97 @@
+use std::{fmt::Write, sync::Arc};
+
+use axum::Extension;
+use hyper::{
+    header::{HeaderValue, CONTENT_TYPE},
+    HeaderMap,
+};
+
+use crate::db::{self, PoolMetrics};
+
+/// The DB pool metrics in Prometheus' text format.
+pub async fn show(Extension(db_handle): Extension<Arc<db::Handle>>) -> (HeaderMap, String) {
+    let mut headers = HeaderMap::new();
+
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; version=0.0.4"),
+    );
+
+    (headers, render(&db_handle.metrics()))
+}
+
+/// Name, description, and value of each gauge
+type Gauge = (&'static str, &'static str, fn(&PoolMetrics) -> usize);
+
+const GAUGES: [Gauge; 5] = [
+    (
+        "oshismash_db_pool_max_size",
+        "Most connections the pool can have open.",
+        |m| m.max_size,
+    ),
+    (
+        "oshismash_db_pool_size",
+        "Connections that are open.",
+        |m| m.size,
+    ),
+    (
+        "oshismash_db_pool_available",
+        "Open connections that aren't in use.",
+        |m| m.available,
+    ),
+    (
+        "oshismash_db_pool_waiting",
+        "Requests waiting for a connection.",
+        |m| m.waiting,
+    ),
+    (
+        "oshismash_db_cached_statements",
+        "Prepared statements cached by the last connection handed out.",
+        |m| m.cached_statements,
+    ),
+];
+
+fn render(pools: &[(&str, PoolMetrics)]) -> String {
+    let mut text = String::new();
+
+    for (name, help, value) in GAUGES {
+        // Writing to a `String` can't fail.
+        let _ = writeln!(text, "# HELP {} {}", name, help);
+        let _ = writeln!(text, "# TYPE {} gauge", name);
+
+        for (pool, pool_metrics) in pools {
+            let _ = writeln!(
+                text,
+                "{}{{pool=\"{}\"}} {}",
+                name,
+                pool,
+                value(pool_metrics)
+            );
+        }
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+    use crate::db::PoolMetrics;
+
+    #[test]
+    fn renders_every_pool() {
+        let metrics = PoolMetrics {
+            max_size: 22,
+            size: 3,
+            available: 1,
+            waiting: 0,
+            cached_statements: 12,
+        };
+
+        let text = render(&[("primary", metrics), ("replica", metrics)]);
+
+        assert!(text.contains("# TYPE oshismash_db_pool_size gauge\n"));
+        assert!(text.contains("oshismash_db_pool_size{pool=\"primary\"} 3\n"));
+        assert!(text.contains("oshismash_db_cached_statements{pool=\"replica\"} 12\n"));
+    }
+}
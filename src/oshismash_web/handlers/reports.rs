@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Form, Path},
+    Extension,
+};
+use axum_extra::extract::cookie;
+use hyper::{HeaderMap, StatusCode};
+use maud::Markup;
+use serde::Deserialize;
+
+use crate::oshismash::{
+    self,
+    locale::Locale,
+    reports::{self, NewReport},
+    store, vtubers,
+};
+use crate::oshismash_web::client_data::ClientData;
+use crate::oshismash_web::cookie_util;
+use crate::oshismash_web::flash::{self, Flash};
+use crate::oshismash_web::i18n::{self, Text};
+use crate::oshismash_web::urls;
+use crate::oshismash_web::views;
+use crate::{config, db};
+
+/// Form for guests to report a problem with a VTuber's card. Hidden VTubers
+/// can't be reported since there's nothing left to do about them.
+pub async fn show_form(
+    Path(vtuber_id): Path<String>,
+    Extension(store): Extension<store::Handle>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    Extension(locale): Extension<Locale>,
+    client_data: ClientData,
+    jar: cookie::CookieJar,
+) -> Result<(cookie::CookieJar, Markup), oshismash::Error> {
+    let vtuber_id = vtuber_id
+        .parse::<i64>()
+        .map_err(|_| oshismash::Error::VTuberNotFound)?;
+
+    let vtuber = store
+        .get_details(vtuber_id, locale)
+        .await?
+        .ok_or(oshismash::Error::VTuberNotFound)?;
+
+    let jar = jar.add(cookie_util::create("id", client_data.guest_id));
+    let (jar, flash) = flash::take(jar);
+
+    Ok((
+        jar,
+        views::root::render(
+            locale,
+            &app_config.base_path,
+            &views::root::Meta::new(i18n::t(locale, Text::Report), locale),
+            flash,
+            views::reports::render(locale, &app_config.base_path, &vtuber),
+        ),
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct ReportForm {
+    reason: String,
+    #[serde(default)]
+    details: String,
+}
+
+/// Sends the guest back to the card they reported.
+pub async fn submit(
+    Path(vtuber_id): Path<String>,
+    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    client_data: ClientData,
+    jar: cookie::CookieJar,
+    Form(form): Form<ReportForm>,
+) -> Result<(StatusCode, HeaderMap, cookie::CookieJar), oshismash::Error> {
+    let vtuber_id = vtuber_id
+        .parse::<i64>()
+        .map_err(|_| oshismash::Error::VTuberNotFound)?;
+
+    let report = NewReport::parse(&form.reason, &form.details)?;
+
+    let client = db_handle.pool.get().await?;
+
+    let slug = vtubers::get_slug(&client, vtuber_id)
+        .await?
+        .ok_or(oshismash::Error::VTuberNotFound)?;
+
+    reports::submit(&client, &client_data.guest_id, vtuber_id, &report).await?;
+
+    let jar =
+        flash::set(jar, Flash::ReportReceived).add(cookie_util::create("id", client_data.guest_id));
+
+    let headers = urls::redirect(&app_config.base_path, &format!("/{}", slug));
+
+    Ok((StatusCode::FOUND, headers, jar))
+}
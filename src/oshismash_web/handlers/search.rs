@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use axum::{extract::Query, Extension, Json};
+use maud::Markup;
+use serde::Deserialize;
+
+use crate::oshismash::{
+    self,
+    locale::Locale,
+    search::{self, SearchResult},
+};
+use crate::oshismash_web::i18n::{self, Text};
+use crate::oshismash_web::views;
+use crate::{config, db};
+
+const MAX_RESULTS: i32 = 20;
+const MAX_SUGGESTIONS: i32 = 8;
+
+#[derive(Deserialize)]
+pub struct SearchParams {
+    q: Option<String>,
+}
+
+/// Search page that works without JS. The results are just a list of links to
+/// the VTubers' profiles.
+pub async fn show(
+    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    Extension(locale): Extension<Locale>,
+    Query(params): Query<SearchParams>,
+) -> Result<Markup, oshismash::Error> {
+    let query = params.q.unwrap_or_default();
+
+    let results = match search::normalize_query(&query) {
+        Some(_) => {
+            let client = db_handle.reader().await?;
+            Some(search::search(&client, &query, locale, MAX_RESULTS).await?)
+        }
+        None => None,
+    };
+
+    Ok(views::root::render(
+        locale,
+        &app_config.base_path,
+        &views::root::Meta::new(i18n::t(locale, Text::Search), locale),
+        None,
+        views::search::render(locale, &app_config.base_path, &query, results.as_deref()),
+    ))
+}
+
+/// Suggestions for `search.js` while the guest is typing.
+pub async fn suggestions(
+    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(locale): Extension<Locale>,
+    Query(params): Query<SearchParams>,
+) -> Result<Json<Vec<SearchResult>>, oshismash::Error> {
+    let query = params.q.unwrap_or_default();
+
+    if search::normalize_query(&query).is_none() {
+        return Ok(Json(Vec::new()));
+    }
+
+    let client = db_handle.reader().await?;
+    let results = search::search(&client, &query, locale, MAX_SUGGESTIONS).await?;
+
+    Ok(Json(results))
+}
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use axum::{extract::Form, Extension};
+use axum_extra::extract::cookie;
+use hyper::{HeaderMap, StatusCode};
+use maud::Markup;
+use serde::Deserialize;
+
+use crate::oshismash::{
+    self,
+    locale::Locale,
+    suggestions::{self, NewSuggestion},
+};
+use crate::oshismash_web::client_data::ClientData;
+use crate::oshismash_web::cookie_util;
+use crate::oshismash_web::flash::{self, Flash};
+use crate::oshismash_web::i18n::{self, Text};
+use crate::oshismash_web::urls;
+use crate::oshismash_web::views;
+use crate::{config, db};
+
+/// Form for guests to suggest a VTuber that isn't in the deck yet.
+pub async fn show_form(
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    Extension(locale): Extension<Locale>,
+    client_data: ClientData,
+    jar: cookie::CookieJar,
+) -> (cookie::CookieJar, Markup) {
+    let jar = jar.add(cookie_util::create("id", client_data.guest_id));
+    let (jar, flash) = flash::take(jar);
+
+    (
+        jar,
+        views::root::render(
+            locale,
+            &app_config.base_path,
+            &views::root::Meta::new(i18n::t(locale, Text::SuggestVTuber), locale),
+            flash,
+            views::suggestions::render(locale, &app_config.base_path),
+        ),
+    )
+}
+
+#[derive(Deserialize)]
+pub struct SuggestionForm {
+    name: String,
+    #[serde(default)]
+    org: String,
+    #[serde(default)]
+    links: String,
+    #[serde(default)]
+    reason: String,
+}
+
+pub async fn submit(
+    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(app_config): Extension<Arc<config::AppConfig>>,
+    client_data: ClientData,
+    jar: cookie::CookieJar,
+    Form(form): Form<SuggestionForm>,
+) -> Result<(StatusCode, HeaderMap, cookie::CookieJar), oshismash::Error> {
+    let suggestion = NewSuggestion::parse(&form.name, &form.org, &form.links, &form.reason)?;
+
+    let client = db_handle.pool.get().await?;
+    suggestions::submit(&client, &client_data.guest_id, &suggestion).await?;
+
+    let jar = flash::set(jar, Flash::SuggestionReceived)
+        .add(cookie_util::create("id", client_data.guest_id));
+
+    let headers = urls::redirect(&app_config.base_path, "/suggest");
+
+    Ok((StatusCode::FOUND, headers, jar))
+}
@@ -3,19 +3,21 @@ use std::sync::Arc;
 use axum::extract::{Form, FromRequest};
 use axum::{async_trait, BoxError, Extension};
 use axum_extra::extract::{cookie, CookieJar};
-use hyper::header::LOCATION;
 use hyper::{HeaderMap, StatusCode};
 use maud::{html, Markup};
 use serde_json::Value;
 
-use crate::oshismash::guests;
+use crate::oshismash::deck::DeckFilter;
 use crate::oshismash::guests::GuestId;
+use crate::oshismash::locale::Locale;
+use crate::oshismash::store;
 use crate::oshismash::vote::ParseError;
 use crate::oshismash::vote::Vote;
 use crate::oshismash::vtubers::VTuberId;
-use crate::oshismash_web::client_data::ClientData;
 use crate::oshismash_web::cookie_util;
-use crate::{db, oshismash, config};
+use crate::oshismash_web::flash::{self, Flash};
+use crate::oshismash_web::urls;
+use crate::{oshismash, config};
 
 #[async_trait]
 impl<B> FromRequest<B> for Vote
@@ -43,102 +45,90 @@ where
                     .ok_or(ParseError::MissingField(String::from("guest_id")))
             });
 
-        let db = req.extract::<Extension<Arc<db::Handle>>>().await;
-
-        // Hadouken'd :(
-        match (form_data, guest_id, db) {
-            (Ok(Form(Value::Object(mut form_data))), Ok(GuestId(guest_id)), Ok(db)) => {
-                match db.client().await {
-                    Ok(client) => match guests::is_valid(&client, guest_id.as_str()).await {
-                        Ok(true) => {
-                            form_data.insert(String::from("guest_id"), Value::String(guest_id));
-
-                            let result = Vote::from(Value::Object(form_data))?;
-                            Ok(result)
-                        }
-                        Ok(false) => Err(oshismash::Error::InvalidGuest),
-                        Err(e) => Err(e),
-                    },
-                    Err(e) => Err(oshismash::Error::from(e)),
-                }
+        // The guest is checked when the vote is cast, which saves a query.
+        match (form_data, guest_id) {
+            (Ok(Form(Value::Object(mut form_data))), Ok(GuestId(guest_id))) => {
+                form_data.insert(String::from("guest_id"), Value::String(guest_id));
+
+                let result = Vote::from(Value::Object(form_data))?;
+                Ok(result)
             }
-            (Ok(Form(_)), _, _) => Err(oshismash::Error::InvalidFormFormat),
-            (_, _, Err(_)) => Err(oshismash::Error::MissingExtension),
-            (_, Err(e), _) => Err(oshismash::Error::VoteParseError(e)),
-            (Err(e), _, _) => Err(oshismash::Error::InvalidForm(e)),
+            (Ok(Form(_)), _) => Err(oshismash::Error::InvalidFormFormat),
+            (_, Err(e)) => Err(oshismash::Error::VoteParseError(e)),
+            (Err(e), _) => Err(oshismash::Error::InvalidForm(e)),
         }
     }
 }
 
-/// Handles the voting for a VTuber
+/// Handles the voting for a VTuber. Guests aren't allowed to vote for VTubers
+/// that come after the first one they haven't voted for yet, or for ones
+/// outside of the filtered deck, which is checked when the vote is cast.
 pub async fn vote(
-    Extension(db_handle): Extension<Arc<db::Handle>>,
+    Extension(store): Extension<store::Handle>,
     Extension(app_config): Extension<Arc<config::AppConfig>>,
-    client_data: ClientData,
+    Extension(locale): Extension<Locale>,
     vote: Vote,
+    filter: DeckFilter,
     jar: cookie::CookieJar,
 ) -> Result<(StatusCode, HeaderMap, CookieJar, Markup), oshismash::Error> {
-    // Guest is not allowed to vote if ever the max visited entry is less than
-    // the target VTuber ID. But this assumes that the VTubers are in order.
-    if client_data.max_visited < vote.vtuber_id {
-        Err(oshismash::Error::NotAllowedToVote)
+    let vtuber_id = VTuberId::try_from(&jar).unwrap_or(VTuberId::Current(1));
+
+    // Votes that were already cast are being changed, which the guest gets
+    // a confirmation for after the redirect.
+    let is_changing_vote = jar.get("voted").map_or(false, |voted| {
+        voted
+            .value()
+            .split(',')
+            .any(|id| id == vote.vtuber_id.to_string())
+    });
+
+    let stack = store.vote(vote.clone(), locale, &filter).await?;
+
+    let vote_list = stack
+        .get_vote_list()
+        .into_iter()
+        .fold("".to_string(), |acc, vote| match acc.as_str() {
+            "" => vote.to_string(),
+            acc => format!("{},{}", acc, vote),
+        });
+
+    let jar = jar.add(cookie_util::create("voted", vote_list));
+
+    let jar = if is_changing_vote {
+        flash::set(jar, Flash::VoteChanged)
     } else {
-        let db_client = db_handle.client().await?;
-        let stack = oshismash::vote::vote(&db_client, vote.clone()).await?;
-
-        let vote_list =
-            stack
-                .get_vote_list()
-                .into_iter()
-                .fold("".to_string(), |acc, vote| match acc.as_str() {
-                    "" => vote.to_string(),
-                    acc => format!("{},{}", acc, vote),
-                });
-
-        let jar = jar.add(cookie_util::create("voted", vote_list));
-
-        // TODO(sekun): Move to middleware. I think it's possible.
-        //
-        // This might make no sense, and maybe there's a better way to do this, but
-        // the cookies are set this way. If the `current` cookie is set with an
-        // actual value, that is the VTuber's ID, then `last_visited` should be set
-        // to `none` because there's literally no use for it. The only time it is
-        // ever used is when `current` is `none`.
-        match stack.get_current() {
-            Some(vtuber) => {
+        jar
+    };
+
+    // TODO(sekun): Move to middleware. I think it's possible.
+    //
+    // This might make no sense, and maybe there's a better way to do this, but
+    // the cookies are set this way. If the `current` cookie is set with an
+    // actual value, that is the VTuber's ID, then `last_visited` should be set
+    // to `none` because there's literally no use for it. The only time it is
+    // ever used is when `current` is `none`.
+    match stack.get_current() {
+        Some(vtuber) => {
+            let jar = jar
+                .add(cookie_util::create("current", vtuber.id))
+                .add(cookie_util::create("last_visited", "none"));
+
+            let path = filter.link(&format!("/{}", vtuber.slug));
+            let headers = urls::redirect(&app_config.base_path, &path);
+
+            Ok((StatusCode::FOUND, headers, jar, html! {}))
+        }
+        None => match vtuber_id {
+            VTuberId::Current(id) => {
                 let jar = jar
-                    .add(cookie_util::create("current", vtuber.id))
-                    .add(cookie_util::create("last_visited", "none"));
-
-                let jar = if vtuber.id > client_data.max_visited {
-                    jar.add(cookie_util::create("max_visited", vtuber.id))
-                } else {
-                    jar
-                };
-
-                let mut headers = HeaderMap::new();
-                // TODO: Refactor this
-                let url = format!("{}/{}", app_config.base_url(), vtuber.id);
+                    .add(cookie_util::create("last_visited", id))
+                    .add(cookie_util::create("current", "none"));
 
-                headers.insert(LOCATION, url.parse().unwrap());
+                let headers = urls::redirect(&app_config.base_path, &filter.link("/"));
 
                 Ok((StatusCode::FOUND, headers, jar, html! {}))
             }
-            None => match client_data.vtuber_id {
-                VTuberId::Current(id) => {
-                    let jar = jar
-                        .add(cookie_util::create("last_visited", id))
-                        .add(cookie_util::create("current", "none"));
-
-                    let mut headers = HeaderMap::new();
-
-                    // TODO: Refactor this
-                    headers.insert(LOCATION, app_config.base_url().parse().unwrap());
-
-                    Ok((StatusCode::FOUND, headers, jar, html! {}))
-                }
-                VTuberId::LastVisited(_) => Err(oshismash::Error::InvalidClientData),
-            },
-        }
+            VTuberId::LastVisited(_) => Err(oshismash::Error::InvalidClientData),
+        },
     }
 }
@@ -1,17 +1,20 @@
-use std::sync::Arc;
-
-use axum::Extension;
+use axum::{extract::Path, Extension};
 use axum_extra::extract::cookie;
-use hyper::{header::LOCATION, HeaderMap, StatusCode};
+use hyper::{HeaderMap, StatusCode};
 use maud::{html, Markup};
 
-use crate::{db, config};
 use crate::oshismash_web::client_data::ClientData;
+use crate::oshismash_web::flash::{self, Flash};
+use crate::oshismash_web::i18n::{self, Text};
+use crate::oshismash_web::urls::{self, BaseUrl};
 use crate::oshismash_web::views;
 use crate::{
     oshismash::{
         self,
-        vtubers::{self, Stack, VTuberId},
+        deck::{Access, DeckFilter},
+        locale::Locale,
+        store::{self, Store},
+        vtubers::{PathId, VTuberId},
     },
     oshismash_web::cookie_util,
 };
@@ -20,8 +23,10 @@ use crate::{
 pub async fn show_from_cookie(
     jar: cookie::CookieJar,
     client_data: ClientData,
-    Extension(db_handle): Extension<Arc<db::Handle>>,
-    Extension(app_config): Extension<Arc<config::AppConfig>>
+    Extension(store): Extension<store::Handle>,
+    Extension(locale): Extension<Locale>,
+    base_url: BaseUrl,
+    filter: DeckFilter,
 ) -> Result<(StatusCode, HeaderMap, cookie::CookieJar, Markup), oshismash::Error> {
     // NOTE: Am I supposed to move the cookie stuff to `tower`/middleware?
     // Cookies:
@@ -37,32 +42,48 @@ pub async fn show_from_cookie(
     }
     .add(cookie_util::create("id", client_data.guest_id.clone()));
 
-    let mut headers = HeaderMap::new();
-
     match client_data.vtuber_id {
         VTuberId::Current(id) => {
-            // TODO: Use app config to generate URL.
-            let url = format!("{}/{}", app_config.base_url(), id);
-            headers.insert(LOCATION, url.parse().unwrap());
+            let path = deck_path(&*store, id, &filter).await?;
+            let headers = urls::redirect(base_url.base_path(), &path);
             Ok((StatusCode::FOUND, headers, jar, html! {}))
         }
-        VTuberId::LastVisited(_) => {
-            let client = db_handle.pool.get().await?;
+        VTuberId::LastVisited(id) => {
+            // The guest might've finished a deck with a different filter, so
+            // they're sent to where they left off in this one instead.
+            match store.get_access(id, &client_data.guest_id, &filter).await? {
+                Access::Allowed => {}
+                Access::NotInDeck { resume_at } | Access::Locked { resume_at } => {
+                    let path = deck_path(&*store, resume_at, &filter).await?;
+                    let headers = urls::redirect(base_url.base_path(), &path);
+
+                    return Ok((StatusCode::FOUND, headers, jar, html! {}));
+                }
+                Access::EmptyDeck => return Err(oshismash::Error::EmptyDeck),
+            }
+
+            let stack = store
+                .get_vote_stack(
+                    &client_data.vtuber_id,
+                    &client_data.guest_id,
+                    locale,
+                    &filter,
+                )
+                .await?;
 
-            let stack = vtubers::get_vote_stack(
-                &client,
-                &client_data.vtuber_id,
-                client_data.guest_id.clone(),
-            )
-            .await?;
+            let (jar, flash) = flash::take(jar);
 
             let render = (
                 StatusCode::OK,
-                headers,
+                HeaderMap::new(),
                 jar,
                 views::root::render(
-                    "Oshi Smash: Smash or Pass Your Oshis!",
-                    views::vote::render(stack),
+                    locale,
+                    base_url.base_path(),
+                    &views::root::Meta::new(i18n::t(locale, Text::PageTitle), locale)
+                        .with_url(base_url.as_str().to_string()),
+                    flash,
+                    views::vote::render(stack, locale, base_url.base_path(), &filter),
                 ),
             );
 
@@ -71,24 +92,90 @@ pub async fn show_from_cookie(
     }
 }
 
+/// Path to a VTuber's card in the deck, without the base path. Cards are
+/// linked to by slug, so this is what redirects should go through.
+pub async fn deck_path(
+    store: &dyn Store,
+    vtuber_id: i64,
+    filter: &DeckFilter,
+) -> Result<String, oshismash::Error> {
+    let slug = store
+        .get_slug(vtuber_id)
+        .await?
+        .ok_or(oshismash::Error::VTuberNotFound)?;
+
+    Ok(filter.link(&format!("/{}", slug)))
+}
+
 pub async fn show_given_id(
-    Extension(db_handle): Extension<Arc<db::Handle>>,
-    client_data: ClientData,
+    Path(path): Path<String>,
+    Extension(store): Extension<store::Handle>,
+    Extension(locale): Extension<Locale>,
+    base_url: BaseUrl,
+    mut client_data: ClientData,
+    filter: DeckFilter,
     jar: cookie::CookieJar,
-) -> Result<(cookie::CookieJar, Stack), oshismash::Error> {
+) -> Result<(StatusCode, HeaderMap, cookie::CookieJar, Markup), oshismash::Error> {
+    // Numeric URLs were the only ones before slugs, so they're kept around for
+    // old links but permanently redirect to the slug.
+    match PathId::parse(&path) {
+        PathId::Id(id) => {
+            let path = deck_path(&*store, id, &filter).await?;
+            let headers = urls::redirect(base_url.base_path(), &path);
+
+            let jar = jar.add(cookie_util::create("id", client_data.guest_id));
+
+            return Ok((StatusCode::MOVED_PERMANENTLY, headers, jar, html! {}));
+        }
+        PathId::Slug(slug) => {
+            let id = store
+                .get_id_by_slug(&slug)
+                .await?
+                .ok_or(oshismash::Error::VTuberNotFound)?;
+
+            client_data.vtuber_id = VTuberId::Current(id);
+        }
+    }
+
     println!("From ID: {:?}", client_data);
-    let client = db_handle.pool.get().await?;
-
-    let stack = vtubers::get_vote_stack(
-        &client,
-        &client_data.vtuber_id,
-        client_data.guest_id.clone(),
-    )
-    .await
-    .map_err(|e| {
-        println!("{:?}", e);
-        e
-    })?;
+
+    // Guests can't skip ahead of the VTubers they haven't voted for yet, nor
+    // see the ones outside of the filter, so they're sent back to the furthest
+    // one they're allowed to see.
+    if let VTuberId::Current(id) = client_data.vtuber_id {
+        let redirect = match store.get_access(id, &client_data.guest_id, &filter).await? {
+            Access::Allowed => None,
+            Access::NotInDeck { resume_at } => Some((resume_at, None)),
+            Access::Locked { resume_at } => Some((resume_at, Some(Flash::VotePreviousFirst))),
+            Access::EmptyDeck => return Err(oshismash::Error::EmptyDeck),
+        };
+
+        if let Some((resume_at, flash)) = redirect {
+            let jar = match flash {
+                Some(flash) => flash::set(jar, flash),
+                None => jar,
+            }
+            .add(cookie_util::create("id", client_data.guest_id));
+
+            let path = deck_path(&*store, resume_at, &filter).await?;
+            let headers = urls::redirect(base_url.base_path(), &path);
+
+            return Ok((StatusCode::FOUND, headers, jar, html! {}));
+        }
+    }
+
+    let stack = store
+        .get_vote_stack(
+            &client_data.vtuber_id,
+            &client_data.guest_id,
+            locale,
+            &filter,
+        )
+        .await
+        .map_err(|e| {
+            println!("{:?}", e);
+            e
+        })?;
 
     println!("{:?}", stack);
 
@@ -112,39 +199,60 @@ pub async fn show_given_id(
     println!("{}", visited_list);
 
     let jar = match client_data.vtuber_id {
-        VTuberId::Current(id) => {
-            if client_data.max_visited < id {
-                // TODO: Redirect with flash message
-                Err(oshismash::Error::MaxVisitedIsLessThanCurrent)
-            } else {
-                let jar = jar
-                    .add(cookie_util::create("current", id))
-                    .add(cookie_util::create("last_visited", "none"));
-
-                let jar = if let None = jar.get("max_visited") {
-                    jar.add(cookie_util::create("max_visited", client_data.max_visited))
-                } else {
-                    jar
-                };
-
-                Ok(jar)
-            }
-        }
-
-        VTuberId::LastVisited(id) => {
-            let jar = jar
-                .add(cookie_util::create("last_visited", id))
-                .add(cookie_util::create("current", "none"));
+        VTuberId::Current(id) => jar
+            .add(cookie_util::create("current", id))
+            .add(cookie_util::create("last_visited", "none")),
 
-            Ok(jar)
-        }
-    }?
+        VTuberId::LastVisited(id) => jar
+            .add(cookie_util::create("last_visited", id))
+            .add(cookie_util::create("current", "none")),
+    }
     .add(cookie_util::create("id", client_data.guest_id))
     .add(cookie_util::create("voted", visited_list));
 
-    Ok((jar, stack))
+    let (jar, flash) = flash::take(jar);
+
+    let meta = match stack.get_current() {
+        Some(vtuber) => views::root::Meta::from_vtuber(vtuber, base_url.as_str(), locale),
+        None => views::root::Meta::new(i18n::t(locale, Text::PageTitle), locale),
+    };
+
+    Ok((
+        StatusCode::OK,
+        HeaderMap::new(),
+        jar,
+        views::root::render(
+            locale,
+            base_url.base_path(),
+            &meta,
+            flash,
+            views::vote::render(stack, locale, base_url.base_path(), &filter),
+        ),
+    ))
 }
 
-pub async fn details() -> Result<(), oshismash::Error> {
-    Ok(())
+/// Profile of a VTuber. This is outside of the voting flow, so it doesn't need
+/// (or touch) any of the guest's cookies.
+pub async fn details(
+    Path(vtuber_id): Path<String>,
+    Extension(store): Extension<store::Handle>,
+    Extension(locale): Extension<Locale>,
+    base_url: BaseUrl,
+) -> Result<Markup, oshismash::Error> {
+    let vtuber_id = vtuber_id
+        .parse::<i64>()
+        .map_err(|_| oshismash::Error::VTuberNotFound)?;
+
+    let details = store
+        .get_details(vtuber_id, locale)
+        .await?
+        .ok_or(oshismash::Error::VTuberNotFound)?;
+
+    Ok(views::root::render(
+        locale,
+        base_url.base_path(),
+        &views::root::Meta::from_details(&details, base_url.as_str(), locale),
+        None,
+        views::details::render(locale, base_url.base_path(), &details),
+    ))
 }
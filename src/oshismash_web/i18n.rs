@@ -0,0 +1,191 @@
+mod en;
+mod ja;
+mod zh;
+
+use axum::{
+    http::{header, Request},
+    middleware::Next,
+    response::Response,
+};
+
+use super::cookie_util;
+use crate::oshismash::{locale::Locale, vtubers::Region};
+
+/// Name of the cookie that overrides the locale from `Accept-Language`.
+pub const LOCALE_COOKIE: &str = "locale";
+
+/// Every translatable text in the UI. Texts with placeholders (e.g `{name}`)
+/// are filled in with `str::replace`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    PageTitle,
+    DefaultDescription,
+    /// Placeholders: `{name}`
+    VTuberTitle,
+    /// Placeholders: `{name}`, `{org}`, `{description}`
+    VTuberDescription,
+    Home,
+    MadeBy,
+    /// Placeholders: `{name}`
+    WhatOthersVotedFor,
+    Passes,
+    Smashes,
+    Pass,
+    Smash,
+    Skip,
+    /// Placeholders: `{count}`
+    SkipsCount,
+    Leaderboard,
+    SmashRatio,
+    Awareness,
+    Skips,
+    Profile,
+    AllTime,
+    LastWeek,
+    LastMonth,
+    /// Placeholders: `{rank}`, `{size}`, `{org}`
+    OrgRank,
+    Search,
+    SearchPlaceholder,
+    /// Placeholders: `{query}`
+    NoSearchResults,
+    SuggestVTuber,
+    SuggestHint,
+    SuggestName,
+    SuggestOrg,
+    SuggestLinks,
+    SuggestLinksHint,
+    SuggestReason,
+    SuggestSubmit,
+    Report,
+    /// Placeholders: `{name}`
+    ReportVTuber,
+    ReportHint,
+    ReportReason,
+    ReportReasonTakedown,
+    ReportReasonWrongImage,
+    ReportReasonWrongInfo,
+    ReportReasonOther,
+    ReportDetails,
+    ReportSubmit,
+    TouchGrass,
+    FlashVoteChanged,
+    FlashSuggestionReceived,
+    FlashSuggestionApproved,
+    FlashSuggestionRejected,
+    FlashReportReceived,
+    FlashReportResolved,
+    FlashReportDismissed,
+    FlashVTuberUnhidden,
+    FlashVTuberRetired,
+    FlashVTuberRestored,
+    Filters,
+    FilterOrg,
+    FilterGroup,
+    FilterRegion,
+    FilterAny,
+    FilterStart,
+    ShuffleOn,
+    ShuffleOff,
+    ShuffleHint,
+    RegionCn,
+    RegionEn,
+    RegionJp,
+    RegionNone,
+
+    ErrQueryFailed,
+    ErrDbSetupFailed,
+    ErrInvalidGuest,
+    ErrPoolFailed,
+    ErrVoteParseFailed,
+    ErrStackParseFailed,
+    ErrMissingDbHandle,
+    ErrVoteNotDisplayed,
+    ErrVotePreviousFirst,
+    ErrInvalidForm,
+    ErrInvalidFormFormat,
+    ErrVoteNotCounted,
+    ErrVoteResultFailed,
+    ErrEmptyDeck,
+    ErrVTuberNotFound,
+    ErrNotAdmin,
+    ErrCrossSiteRequest,
+    ErrTooManySuggestions,
+    ErrInvalidSuggestion,
+    ErrSuggestionNotFound,
+    ErrAlreadyReported,
+    ErrInvalidReport,
+    ErrReportNotFound,
+    ErrBusy,
+
+    ErrorTitle,
+    ErrorClientHint,
+    ErrorServerHint,
+    GoBack,
+}
+
+/// Translates `text` to `locale`. English is the default, and is what the other
+/// locales fall back to if a text hasn't been translated yet.
+pub fn t(locale: Locale, text: Text) -> &'static str {
+    let translated = match locale {
+        Locale::En => None,
+        Locale::Ja => ja::translate(text),
+        Locale::Zh => zh::translate(text),
+    };
+
+    translated.unwrap_or_else(|| en::translate(text))
+}
+
+/// Name of a region in `locale`.
+pub fn region_name(locale: Locale, region: &Region) -> &'static str {
+    match region {
+        Region::Cn => t(locale, Text::RegionCn),
+        Region::En => t(locale, Text::RegionEn),
+        Region::Jp => t(locale, Text::RegionJp),
+        Region::None => t(locale, Text::RegionNone),
+    }
+}
+
+/// The message of an `oshismash::Error`. This gets attached to the response as
+/// an extension so `error::render` can render it once the locale is known.
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorMessage {
+    pub code: &'static str,
+    pub text: Text,
+}
+
+impl ErrorMessage {
+    pub fn localize(&self, locale: Locale) -> String {
+        format!("{}: {}", self.code, t(locale, self.text))
+    }
+
+    /// Explanation of the error, without the code.
+    pub fn explain(&self, locale: Locale) -> &'static str {
+        t(locale, self.text)
+    }
+}
+
+/// Figures out the locale of the client. The `locale` cookie takes precedence
+/// over `Accept-Language`.
+pub fn negotiate_locale<B>(req: &Request<B>) -> Locale {
+    let from_cookie =
+        cookie_util::get(req.headers(), LOCALE_COOKIE).and_then(|c| Locale::from_code(&c));
+
+    from_cookie
+        .or_else(|| {
+            req.headers()
+                .get(header::ACCEPT_LANGUAGE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(Locale::from_accept_language)
+        })
+        .unwrap_or_default()
+}
+
+/// Middleware that makes the client's `Locale` available to handlers, and to
+/// the middleware after it, as an extension.
+pub async fn negotiate<B>(mut req: Request<B>, next: Next<B>) -> Response {
+    let locale = negotiate_locale(&req);
+    req.extensions_mut().insert(locale);
+
+    next.run(req).await
+}
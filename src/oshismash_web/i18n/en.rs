@@ -0,0 +1,121 @@
+use super::Text;
+
+pub fn translate(text: Text) -> &'static str {
+    match text {
+        Text::PageTitle => "Oshi Smash: Smash or Pass Your Oshis!",
+        Text::DefaultDescription => {
+            "Smash or pass your favorite VTubers, and see what everyone else voted for."
+        }
+        Text::VTuberTitle => "{name} | Oshi Smash",
+        Text::VTuberDescription => "Smash or pass {name} ({org})? {description}",
+        Text::Home => "Home",
+        Text::MadeBy => "Made by ",
+        Text::WhatOthersVotedFor => "What others voted for {name}",
+        Text::Passes => "Passes",
+        Text::Smashes => "Smashes",
+        Text::Pass => "Pass",
+        Text::Smash => "Smash",
+        Text::Skip => "Don't know them",
+        Text::SkipsCount => "{count} didn't know them",
+        Text::Leaderboard => "Leaderboard",
+        Text::SmashRatio => "Smash rate",
+        Text::Awareness => "Known by",
+        Text::Skips => "Skips",
+        Text::Profile => "Profile",
+        Text::AllTime => "All time",
+        Text::LastWeek => "Last 7 days",
+        Text::LastMonth => "Last 30 days",
+        Text::OrgRank => "#{rank} of {size} in {org}",
+        Text::Search => "Search",
+        Text::SearchPlaceholder => "Search for a VTuber",
+        Text::NoSearchResults => "No VTubers found for \"{query}\".",
+        Text::SuggestVTuber => "Suggest a VTuber",
+        Text::SuggestHint => {
+            "Is your oshi missing? Let us know, and they might show up in the deck."
+        }
+        Text::SuggestName => "Name",
+        Text::SuggestOrg => "Org (if any)",
+        Text::SuggestLinks => "Links",
+        Text::SuggestLinksHint => "Their YouTube, Twitch, or Twitter. One per line.",
+        Text::SuggestReason => "Why should they be added?",
+        Text::SuggestSubmit => "Send",
+        Text::Report => "Report",
+        Text::ReportVTuber => "Report {name}",
+        Text::ReportHint => {
+            "Let us know what's wrong with this card, and a moderator will look at it."
+        }
+        Text::ReportReason => "What's wrong?",
+        Text::ReportReasonTakedown => "They (or their agency) want to be removed",
+        Text::ReportReasonWrongImage => "The image is wrong",
+        Text::ReportReasonWrongInfo => "The name or description is wrong",
+        Text::ReportReasonOther => "Something else",
+        Text::ReportDetails => "Details",
+        Text::ReportSubmit => "Send",
+        Text::TouchGrass => "You can touch grass now.",
+        Text::FlashVoteChanged => "Your vote was changed.",
+        Text::FlashSuggestionReceived => "Thanks! Your suggestion will be looked at soon.",
+        Text::FlashSuggestionApproved => "The suggestion was approved, and added to the deck.",
+        Text::FlashSuggestionRejected => "The suggestion was rejected.",
+        Text::FlashReportReceived => "Thanks! A moderator will look at your report soon.",
+        Text::FlashReportResolved => "The VTuber was hidden.",
+        Text::FlashReportDismissed => "The report was dismissed.",
+        Text::FlashVTuberUnhidden => "The VTuber is back in the deck.",
+        Text::FlashVTuberRetired => "The VTuber was retired.",
+        Text::FlashVTuberRestored => "The VTuber was restored.",
+        Text::Filters => "Filters",
+        Text::FilterOrg => "Org",
+        Text::FilterGroup => "Group",
+        Text::FilterRegion => "Region",
+        Text::FilterAny => "Any",
+        Text::FilterStart => "Start",
+        Text::ShuffleOn => "Shuffle the deck",
+        Text::ShuffleOff => "Go back to the usual order",
+        Text::ShuffleHint => {
+            "You get your own order, so the first few VTubers don't get all the votes."
+        }
+        Text::RegionCn => "Chinese",
+        Text::RegionEn => "English",
+        Text::RegionJp => "Japanese",
+        Text::RegionNone => "Other",
+
+        Text::ErrQueryFailed => "Failed when attempting to query the database",
+        Text::ErrDbSetupFailed => "Failed to setup database connection",
+        Text::ErrInvalidGuest => "Not allowed to access this",
+        Text::ErrPoolFailed => "Failed to get client from DB pool",
+        Text::ErrVoteParseFailed => "Failed to parse vote entry",
+        Text::ErrStackParseFailed => "Failed to parse card stack",
+        Text::ErrMissingDbHandle => "Missing DB handle extension",
+        Text::ErrVoteNotDisplayed => "You can only vote for a VTuber that's currently displayed",
+        Text::ErrVotePreviousFirst => "You have to vote for the previous entries first.",
+        Text::ErrInvalidForm => "Form data is not valid",
+        Text::ErrInvalidFormFormat => "Form data is not of the expected format.",
+        Text::ErrVoteNotCounted => "Vote was not counted. Something went wrong in the server.",
+        Text::ErrVoteResultFailed => {
+            "Vote was counted but something went wrong while handling the DB result."
+        }
+        Text::ErrEmptyDeck => "No VTubers match these filters.",
+        Text::ErrVTuberNotFound => "There's no such VTuber.",
+        Text::ErrNotAdmin => "Only moderators can do this.",
+        Text::ErrCrossSiteRequest => {
+            "That didn't come from one of Oshi Smash's pages. Go back and try again."
+        }
+        Text::ErrTooManySuggestions => {
+            "You've made enough suggestions for today. Try again tomorrow."
+        }
+        Text::ErrInvalidSuggestion => {
+            "The suggestion needs a name, and links have to start with https://."
+        }
+        Text::ErrSuggestionNotFound => "That suggestion was already looked at, or doesn't exist.",
+        Text::ErrAlreadyReported => {
+            "You already reported this VTuber. A moderator will look at it soon."
+        }
+        Text::ErrInvalidReport => "Pick what's wrong, and add some details if it's something else.",
+        Text::ErrReportNotFound => "That report was already looked at, or doesn't exist.",
+        Text::ErrBusy => "Too many people are voting right now. Try again in a few seconds.",
+
+        Text::ErrorTitle => "Something went wrong",
+        Text::ErrorClientHint => "Something about that request wasn't quite right.",
+        Text::ErrorServerHint => "It's not you, it's us. Please try again in a bit.",
+        Text::GoBack => "Go back",
+    }
+}
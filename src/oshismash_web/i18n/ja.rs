@@ -0,0 +1,112 @@
+use super::Text;
+
+pub fn translate(text: Text) -> Option<&'static str> {
+    let translated = match text {
+        Text::PageTitle => "Oshi Smash: 推しをスマッシュ or パス！",
+        Text::DefaultDescription => {
+            "好きなVTuberをスマッシュかパスか選んで、みんなの投票結果を見てみよう。"
+        }
+        Text::VTuberDescription => "{name}（{org}）はスマッシュ？それともパス？ {description}",
+        Text::Home => "ホーム",
+        Text::MadeBy => "制作: ",
+        Text::WhatOthersVotedFor => "{name}へのみんなの投票",
+        Text::Passes => "パス",
+        Text::Smashes => "スマッシュ",
+        Text::Pass => "パス",
+        Text::Smash => "スマッシュ",
+        Text::Skip => "知らない",
+        Text::SkipsCount => "{count}人が知らなかった",
+        Text::Leaderboard => "ランキング",
+        Text::SmashRatio => "スマッシュ率",
+        Text::Awareness => "認知度",
+        Text::Skips => "知らない",
+        Text::Profile => "プロフィール",
+        Text::AllTime => "全期間",
+        Text::LastWeek => "過去7日間",
+        Text::LastMonth => "過去30日間",
+        Text::OrgRank => "{org}内で{size}人中{rank}位",
+        Text::Search => "検索",
+        Text::SearchPlaceholder => "VTuberを検索",
+        Text::NoSearchResults => "「{query}」に一致するVTuberはいません。",
+        Text::SuggestVTuber => "VTuberを提案する",
+        Text::SuggestHint => "推しがいませんか？教えてくれれば、追加されるかもしれません。",
+        Text::SuggestName => "名前",
+        Text::SuggestOrg => "事務所（あれば）",
+        Text::SuggestLinks => "リンク",
+        Text::SuggestLinksHint => "YouTube、Twitch、Twitterなど。1行に1つずつ。",
+        Text::SuggestReason => "追加してほしい理由",
+        Text::SuggestSubmit => "送信",
+        Text::Report => "報告",
+        Text::ReportVTuber => "{name}について報告する",
+        Text::ReportHint => "このカードの問題を教えてください。モデレーターが確認します。",
+        Text::ReportReason => "どんな問題ですか？",
+        Text::ReportReasonTakedown => "本人または事務所が削除を希望している",
+        Text::ReportReasonWrongImage => "画像が間違っている",
+        Text::ReportReasonWrongInfo => "名前や説明が間違っている",
+        Text::ReportReasonOther => "その他",
+        Text::ReportDetails => "詳細",
+        Text::ReportSubmit => "送信",
+        Text::TouchGrass => "もう外に出て草でも触ってきていいよ。",
+        Text::FlashVoteChanged => "投票を変更しました。",
+        Text::FlashSuggestionReceived => "ありがとうございます！提案はまもなく確認されます。",
+        Text::FlashReportReceived => "ありがとうございます！報告はまもなく確認されます。",
+        Text::Filters => "絞り込み",
+        Text::FilterOrg => "事務所",
+        Text::FilterGroup => "グループ",
+        Text::FilterRegion => "地域",
+        Text::FilterAny => "すべて",
+        Text::FilterStart => "スタート",
+        Text::ShuffleOn => "順番をシャッフル",
+        Text::ShuffleOff => "いつもの順番に戻す",
+        Text::ShuffleHint => "あなただけの順番になるので、最初のVTuberばかりに票が集まりません。",
+        Text::RegionCn => "中国語",
+        Text::RegionEn => "英語",
+        Text::RegionJp => "日本語",
+        Text::RegionNone => "その他",
+
+        Text::ErrQueryFailed => "データベースへの問い合わせに失敗しました",
+        Text::ErrDbSetupFailed => "データベース接続の準備に失敗しました",
+        Text::ErrInvalidGuest => "アクセスが許可されていません",
+        Text::ErrPoolFailed => "データベースプールから接続を取得できませんでした",
+        Text::ErrVoteParseFailed => "投票内容を解析できませんでした",
+        Text::ErrStackParseFailed => "カードの読み込みに失敗しました",
+        Text::ErrMissingDbHandle => "データベースハンドルがありません",
+        Text::ErrVoteNotDisplayed => "投票できるのは表示中のVTuberだけです",
+        Text::ErrVotePreviousFirst => "先に前のVTuberに投票してください。",
+        Text::ErrInvalidForm => "フォームの内容が正しくありません",
+        Text::ErrInvalidFormFormat => "フォームの形式が正しくありません。",
+        Text::ErrVoteNotCounted => "投票は反映されませんでした。サーバーで問題が発生しました。",
+        Text::ErrVoteResultFailed => "投票は反映されましたが、結果の処理中に問題が発生しました。",
+        Text::ErrEmptyDeck => "条件に合うVTuberがいません。",
+        Text::ErrVTuberNotFound => "そのVTuberは見つかりませんでした。",
+        Text::ErrNotAdmin => "これはモデレーターのみが行えます。",
+        Text::ErrTooManySuggestions => "今日はもう十分に提案しました。また明日お試しください。",
+        Text::ErrInvalidSuggestion => "名前が必要です。リンクはhttps://で始まる必要があります。",
+        Text::ErrAlreadyReported => "このVTuberはすでに報告済みです。まもなく確認されます。",
+        Text::ErrInvalidReport => "問題を選んでください。「その他」の場合は詳細も必要です。",
+        Text::ErrBusy => "ただいま混み合っています。数秒後にもう一度お試しください。",
+
+        Text::ErrorTitle => "問題が発生しました",
+        Text::ErrorClientHint => "リクエストの内容に問題があったようです。",
+        Text::ErrorServerHint => {
+            "あなたのせいではありません。少し時間をおいてからもう一度お試しください。"
+        }
+        Text::GoBack => "戻る",
+
+        Text::VTuberTitle => return None,
+
+        // Only moderators see these.
+        Text::FlashSuggestionApproved
+        | Text::FlashSuggestionRejected
+        | Text::FlashReportResolved
+        | Text::FlashReportDismissed
+        | Text::FlashVTuberUnhidden
+        | Text::FlashVTuberRetired
+        | Text::FlashVTuberRestored
+        | Text::ErrSuggestionNotFound
+        | Text::ErrReportNotFound
+        | Text::ErrCrossSiteRequest => return None,
+    };
+
+    Some(translated)
+}
@@ -0,0 +1,105 @@
+use super::Text;
+
+pub fn translate(text: Text) -> Option<&'static str> {
+    let translated = match text {
+        Text::PageTitle => "Oshi Smash：你的推是 Smash 还是 Pass？",
+        Text::DefaultDescription => "对你喜欢的VTuber选择 Smash 或 Pass，看看其他人是怎么投票的。",
+        Text::VTuberDescription => "{name}（{org}）是 Smash 还是 Pass？{description}",
+        Text::Home => "首页",
+        Text::MadeBy => "作者：",
+        Text::WhatOthersVotedFor => "其他人对{name}的投票",
+        Text::TouchGrass => "你现在可以出门摸摸草了。",
+        Text::FlashVoteChanged => "你的投票已更改。",
+        Text::FlashSuggestionReceived => "谢谢！我们会尽快查看你的推荐。",
+        Text::FlashReportReceived => "谢谢！管理员会尽快查看你的举报。",
+        Text::Skip => "不认识",
+        Text::SkipsCount => "{count}人不认识",
+        Text::Leaderboard => "排行榜",
+        Text::SmashRatio => "Smash 率",
+        Text::Awareness => "知名度",
+        Text::Skips => "不认识",
+        Text::Profile => "简介",
+        Text::AllTime => "全部",
+        Text::LastWeek => "最近7天",
+        Text::LastMonth => "最近30天",
+        Text::OrgRank => "{org}中{size}人里排第{rank}",
+        Text::Search => "搜索",
+        Text::SearchPlaceholder => "搜索VTuber",
+        Text::NoSearchResults => "没有找到与“{query}”相符的VTuber。",
+        Text::SuggestVTuber => "推荐VTuber",
+        Text::SuggestHint => "你的推不在这里？告诉我们，她可能会被加进来。",
+        Text::SuggestName => "名字",
+        Text::SuggestOrg => "所属公司（如有）",
+        Text::SuggestLinks => "链接",
+        Text::SuggestLinksHint => "YouTube、Twitch或Twitter。每行一个。",
+        Text::SuggestReason => "为什么要加她？",
+        Text::SuggestSubmit => "提交",
+        Text::Report => "举报",
+        Text::ReportVTuber => "举报{name}",
+        Text::ReportHint => "告诉我们这张卡片有什么问题，管理员会尽快查看。",
+        Text::ReportReason => "有什么问题？",
+        Text::ReportReasonTakedown => "本人或所属公司希望被移除",
+        Text::ReportReasonWrongImage => "图片不对",
+        Text::ReportReasonWrongInfo => "名字或简介不对",
+        Text::ReportReasonOther => "其他",
+        Text::ReportDetails => "详细说明",
+        Text::ReportSubmit => "提交",
+        Text::Filters => "筛选",
+        Text::FilterOrg => "事务所",
+        Text::FilterGroup => "组合",
+        Text::FilterRegion => "地区",
+        Text::FilterAny => "全部",
+        Text::FilterStart => "开始",
+        Text::ShuffleOn => "随机顺序",
+        Text::ShuffleOff => "恢复默认顺序",
+        Text::ShuffleHint => "你会有专属的顺序，这样票数就不会都集中在前几位VTuber身上。",
+        Text::RegionCn => "中文",
+        Text::RegionEn => "英语",
+        Text::RegionJp => "日语",
+        Text::RegionNone => "其他",
+
+        Text::ErrQueryFailed => "查询数据库失败",
+        Text::ErrDbSetupFailed => "建立数据库连接失败",
+        Text::ErrInvalidGuest => "无权访问",
+        Text::ErrPoolFailed => "无法从数据库连接池获取连接",
+        Text::ErrVoteParseFailed => "无法解析投票内容",
+        Text::ErrStackParseFailed => "无法解析卡片",
+        Text::ErrMissingDbHandle => "缺少数据库句柄",
+        Text::ErrVoteNotDisplayed => "只能为当前显示的VTuber投票",
+        Text::ErrVotePreviousFirst => "请先为前面的VTuber投票。",
+        Text::ErrInvalidForm => "表单数据无效",
+        Text::ErrInvalidFormFormat => "表单数据格式不正确。",
+        Text::ErrVoteNotCounted => "投票未被计入。服务器出现了问题。",
+        Text::ErrVoteResultFailed => "投票已计入，但处理数据库结果时出现了问题。",
+        Text::ErrEmptyDeck => "没有符合条件的VTuber。",
+        Text::ErrVTuberNotFound => "找不到该VTuber。",
+        Text::ErrNotAdmin => "只有管理员可以这样做。",
+        Text::ErrTooManySuggestions => "你今天已经推荐得够多了，明天再试吧。",
+        Text::ErrInvalidSuggestion => "需要填写名字，链接必须以https://开头。",
+        Text::ErrAlreadyReported => "你已经举报过这位VTuber了，管理员会尽快查看。",
+        Text::ErrInvalidReport => "请选择问题。如果选择“其他”，还需要填写详细说明。",
+        Text::ErrBusy => "现在投票的人太多了，请过几秒再试。",
+
+        Text::ErrorTitle => "出错了",
+        Text::ErrorClientHint => "请求的内容似乎有点问题。",
+        Text::ErrorServerHint => "不是你的问题，是我们的问题。请稍后再试。",
+        Text::GoBack => "返回",
+
+        // "Smash" and "Pass" are used as-is.
+        Text::VTuberTitle | Text::Passes | Text::Smashes | Text::Pass | Text::Smash => return None,
+
+        // Only moderators see these.
+        Text::FlashSuggestionApproved
+        | Text::FlashSuggestionRejected
+        | Text::FlashReportResolved
+        | Text::FlashReportDismissed
+        | Text::FlashVTuberUnhidden
+        | Text::FlashVTuberRetired
+        | Text::FlashVTuberRestored
+        | Text::ErrSuggestionNotFound
+        | Text::ErrReportNotFound
+        | Text::ErrCrossSiteRequest => return None,
+    };
+
+    Some(translated)
+}
@@ -0,0 +1,63 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// Whether the server should still be sent traffic. It stops being ready once
+/// it starts shutting down, so load balancers can move on while the requests
+/// that are already in flight are drained.
+#[derive(Debug, Clone, Default)]
+pub struct Readiness {
+    draining: Arc<AtomicBool>,
+}
+
+impl Readiness {
+    pub fn is_ready(&self) -> bool {
+        !self.draining.load(Ordering::Relaxed)
+    }
+
+    pub fn start_draining(&self) {
+        self.draining.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Waits for SIGTERM (what systemd and container runtimes send) or SIGINT
+/// (ctrl-c).
+#[cfg(unix)]
+pub async fn signal() {
+    use tokio::signal::unix::{self, SignalKind};
+
+    let mut terminate =
+        unix::signal(SignalKind::terminate()).expect("expect tokio signal SIGTERM handler");
+
+    tokio::select! {
+        _ = terminate.recv() => println!("Received SIGTERM"),
+        _ = tokio::signal::ctrl_c() => println!("Received SIGINT"),
+    }
+}
+
+#[cfg(not(unix))]
+pub async fn signal() {
+    tokio::signal::ctrl_c()
+        .await
+        .expect("expect tokio signal ctrl-c");
+
+    println!("Received ctrl-c")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Readiness;
+
+    #[test]
+    fn draining_is_shared_between_clones() {
+        let readiness = Readiness::default();
+        let handle = readiness.clone();
+
+        assert!(readiness.is_ready());
+
+        handle.start_draining();
+
+        assert!(!readiness.is_ready());
+    }
+}
@@ -0,0 +1,297 @@
+use std::sync::Arc;
+
+use axum::{
+    async_trait,
+    extract::{FromRequest, RequestParts},
+    Extension,
+};
+use hyper::{
+    header::{HeaderName, HeaderValue, HOST, LOCATION},
+    http::uri::Authority,
+    HeaderMap, Uri,
+};
+
+use crate::{config::AppConfig, oshismash};
+
+const X_FORWARDED_PROTO: HeaderName = HeaderName::from_static("x-forwarded-proto");
+const X_FORWARDED_HOST: HeaderName = HeaderName::from_static("x-forwarded-host");
+const X_FORWARDED_PORT: HeaderName = HeaderName::from_static("x-forwarded-port");
+
+/// Absolute URL of the app as the guest reached it, like
+/// `https://oshismash.com/oshismash`, for the links that can't be relative
+/// (e.g canonical URLs). The configured public URL wins, then the
+/// `X-Forwarded-*` headers of a reverse proxy, then `Host`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaseUrl {
+    url: String,
+    base_path: String,
+}
+
+impl BaseUrl {
+    pub fn new(app_config: &AppConfig, uri: &Uri, headers: &HeaderMap) -> BaseUrl {
+        let base_path = app_config.base_path.clone();
+
+        if app_config.public_url.is_some() {
+            let url = app_config.base_url();
+
+            return BaseUrl { url, base_path };
+        }
+
+        // The server itself only speaks plain HTTP, so it's HTTPS only if a
+        // proxy says so.
+        let scheme = first_value(headers, &X_FORWARDED_PROTO)
+            .map(str::to_ascii_lowercase)
+            .filter(|scheme| scheme == "http" || scheme == "https")
+            .unwrap_or_else(|| String::from("http"));
+
+        let forwarded_host = first_value(headers, &X_FORWARDED_HOST)
+            .and_then(parse_authority)
+            .map(|authority| {
+                let port = first_value(headers, &X_FORWARDED_PORT)
+                    .and_then(|port| port.parse::<u16>().ok())
+                    .filter(|port| !is_default_port(&scheme, *port));
+
+                match (authority.port_u16(), port) {
+                    (None, Some(port)) => format!("{}:{}", authority, port),
+                    _ => authority.to_string(),
+                }
+            });
+
+        let host = forwarded_host.or_else(|| {
+            first_value(headers, &HOST)
+                .and_then(parse_authority)
+                .or_else(|| uri.authority().cloned())
+                .map(|authority| authority.to_string())
+        });
+
+        let url = match host {
+            Some(host) => format!("{}://{}{}", scheme, host, base_path),
+            None => app_config.base_url(),
+        };
+
+        BaseUrl { url, base_path }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.url
+    }
+
+    /// Scheme and host of the base URL, in the same form as an `Origin` header.
+    pub fn origin(&self) -> &str {
+        self.url.strip_suffix(&self.base_path).unwrap_or(&self.url)
+    }
+
+    /// Same as `config::AppConfig::base_path`, for the redirects.
+    pub fn base_path(&self) -> &str {
+        &self.base_path
+    }
+
+    /// Whether a URL (like a `Referer`) is one of the app's pages. The base
+    /// URL has to end right before the path, so that a look-alike host like
+    /// `oshismash.com.example` doesn't count.
+    pub fn contains(&self, url: &str) -> bool {
+        match url.strip_prefix(&self.url) {
+            Some(rest) => rest.is_empty() || rest.starts_with('/') || rest.starts_with('?'),
+            None => false,
+        }
+    }
+}
+
+#[async_trait]
+impl<B> FromRequest<B> for BaseUrl
+where
+    B: Send,
+{
+    type Rejection = oshismash::Error;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let Extension(app_config) = req.extract::<Extension<Arc<AppConfig>>>().await?;
+
+        Ok(BaseUrl::new(&app_config, req.uri(), req.headers()))
+    }
+}
+
+/// Path of a page of the app, for links, assets and form actions, given its
+/// path without the base path (e.g `/leaderboard`).
+pub fn path(base_path: &str, path: &str) -> String {
+    format!("{}{}", base_path, path)
+}
+
+/// Headers that redirect to a page of the app, given its path (e.g
+/// `/veibae?org=1`). `Location` only has the path, which the browser resolves
+/// against the URL it used, so it works behind any proxy.
+pub fn redirect(base_path: &str, path: &str) -> HeaderMap {
+    let location = self::path(base_path, path);
+
+    // Anything a header can't have (like a non-ASCII slug) is escaped, rather
+    // than failing the redirect.
+    let location = location
+        .bytes()
+        .map(|byte| match byte {
+            b'!'..=b'~' => char::from(byte).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect::<String>();
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        LOCATION,
+        HeaderValue::try_from(location).expect("only visible ASCII is left"),
+    );
+
+    headers
+}
+
+/// Proxies add their values to the end of the list, so the first one is what
+/// the guest used.
+fn first_value<'a>(headers: &'a HeaderMap, name: &HeaderName) -> Option<&'a str> {
+    let value = headers.get(name)?.to_str().ok()?;
+    let first = value.split(',').next()?.trim();
+
+    Some(first).filter(|first| !first.is_empty())
+}
+
+fn parse_authority(value: &str) -> Option<Authority> {
+    value
+        .parse::<Authority>()
+        .ok()
+        .filter(|authority| !authority.as_str().contains('@'))
+}
+
+fn is_default_port(scheme: &str, port: u16) -> bool {
+    matches!((scheme, port), ("http", 80) | ("https", 443))
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::{header::LOCATION, HeaderMap, Uri};
+
+    use super::{redirect, BaseUrl};
+    use crate::config::{AppConfig, Layers};
+
+    fn config(vars: &[(&str, &str)]) -> AppConfig {
+        let mut layers = Layers::default();
+
+        layers.add_env(|var| {
+            let value = match var {
+                "APP__STATIC_ASSETS" => Some("."),
+                "DATABASE_URL" => Some("postgres://oshismash@localhost/oshismash"),
+                _ => vars
+                    .iter()
+                    .find(|(name, _)| *name == var)
+                    .map(|(_, value)| *value),
+            };
+
+            value.map(String::from)
+        });
+
+        layers.build().unwrap()
+    }
+
+    fn base_url(app_config: &AppConfig, headers: &[(&'static str, &str)]) -> String {
+        let headers = headers
+            .iter()
+            .map(|(name, value)| (name.parse().unwrap(), value.parse().unwrap()))
+            .collect::<HeaderMap>();
+
+        BaseUrl::new(app_config, &Uri::from_static("/"), &headers)
+            .as_str()
+            .to_string()
+    }
+
+    #[test]
+    fn base_url_comes_from_the_request() {
+        let app_config = config(&[("APP__BASE_PATH", "/oshismash")]);
+
+        assert_eq!(
+            base_url(&app_config, &[("host", "staging.oshismash.com:8080")]),
+            "http://staging.oshismash.com:8080/oshismash"
+        );
+        assert_eq!(
+            base_url(&app_config, &[("host", "[::1]:3000")]),
+            "http://[::1]:3000/oshismash"
+        );
+        assert_eq!(
+            base_url(
+                &app_config,
+                &[
+                    ("host", "127.0.0.1:3000"),
+                    ("x-forwarded-proto", "https, http"),
+                    ("x-forwarded-host", "oshismash.com, proxy.internal"),
+                    ("x-forwarded-port", "443"),
+                ]
+            ),
+            "https://oshismash.com/oshismash"
+        );
+        assert_eq!(
+            base_url(
+                &app_config,
+                &[
+                    ("x-forwarded-host", "oshismash.com"),
+                    ("x-forwarded-port", "8443"),
+                ]
+            ),
+            "http://oshismash.com:8443/oshismash"
+        );
+    }
+
+    #[test]
+    fn bad_headers_are_ignored() {
+        let app_config = config(&[("APP__HOST", "oshismash.com")]);
+
+        assert_eq!(
+            base_url(
+                &app_config,
+                &[
+                    ("host", "localhost:3000"),
+                    ("x-forwarded-proto", "gopher"),
+                    ("x-forwarded-host", "evil.example@oshismash.com"),
+                ]
+            ),
+            "http://localhost:3000"
+        );
+        assert_eq!(
+            base_url(&app_config, &[("host", "not a host")]),
+            "https://oshismash.com"
+        );
+    }
+
+    #[test]
+    fn public_url_wins_over_the_request() {
+        let app_config = config(&[
+            ("APP__PUBLIC_URL", "https://staging.oshismash.com:8443/"),
+            ("APP__BASE_PATH", "/oshismash"),
+        ]);
+
+        assert_eq!(
+            base_url(
+                &app_config,
+                &[("host", "localhost"), ("x-forwarded-host", "evil.example")]
+            ),
+            "https://staging.oshismash.com:8443/oshismash"
+        );
+    }
+
+    #[test]
+    fn only_the_apps_pages_are_contained() {
+        let app_config = config(&[("APP__PUBLIC_URL", "https://oshismash.com")]);
+        let base_url = BaseUrl::new(&app_config, &Uri::from_static("/"), &HeaderMap::new());
+
+        assert!(base_url.contains("https://oshismash.com"));
+        assert!(base_url.contains("https://oshismash.com/veibae?org=1"));
+        assert!(!base_url.contains("https://oshismash.com.example/"));
+        assert!(!base_url.contains("http://oshismash.com/"));
+    }
+
+    #[test]
+    fn redirects_are_relative_and_escaped() {
+        assert_eq!(
+            redirect("/oshismash", "/veibae?region=en")[LOCATION],
+            "/oshismash/veibae?region=en"
+        );
+        assert_eq!(
+            redirect("/oshismash", "/ベイ バエ")[LOCATION],
+            "/oshismash/%E3%83%99%E3%82%A4%20%E3%83%90%E3%82%A8"
+        );
+    }
+}
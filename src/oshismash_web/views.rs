@@ -1,2 +1,10 @@
+pub mod admin;
+pub mod deck;
+pub mod details;
+pub mod error;
+pub mod leaderboard;
+pub mod reports;
 pub mod root;
+pub mod search;
+pub mod suggestions;
 pub mod vote;
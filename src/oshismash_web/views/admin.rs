@@ -0,0 +1,292 @@
+//! Pages for moderators. These are only in English, since it's just us.
+
+use maud::{html, Markup};
+
+use crate::{
+    oshismash::{
+        deck::{DeckEntry, DeckOptions, RetiredVTuber},
+        locale::Locale,
+        reports::{HiddenVTuber, Report},
+        suggestions::Suggestion,
+    },
+    oshismash_web::{i18n, urls, views::reports::reason_text},
+};
+
+const INPUT_CLASS: &str = "rounded-md p-2 bg-su-bg-1 dark:bg-su-dark-bg-1";
+
+/// Queue of pending suggestions. Each one comes with the form for adding the
+/// VTuber, prefilled with what the guest wrote.
+pub fn suggestions(
+    base_path: &str,
+    pending: &[Suggestion],
+    options: &DeckOptions,
+    order: &[DeckEntry],
+) -> Markup {
+    html! {
+        div class="flex-1 flex flex-col items-center space-y-6 dark:text-su-dark-fg-1" {
+            h1 class="font-bold text-2xl md:text-3xl" { "Suggestions" }
+
+            @if pending.is_empty() {
+                p class="opacity-70" { "Nothing to look at right now." }
+            }
+
+            @for suggestion in pending {
+                (suggestion_card(base_path, suggestion, options, order))
+            }
+        }
+    }
+}
+
+fn suggestion_card(
+    base_path: &str,
+    suggestion: &Suggestion,
+    options: &DeckOptions,
+    order: &[DeckEntry],
+) -> Markup {
+    let suggested_org = options
+        .orgs
+        .iter()
+        .find(|org| org.name.eq_ignore_ascii_case(&suggestion.org_name));
+
+    html! {
+        div class="rounded-lg shadow-lg bg-su-bg-2 dark:bg-su-dark-bg-2 w-full md:w-2/3 p-6 space-y-4" {
+            div {
+                h2 class="font-bold text-xl" { (suggestion.name) }
+                p class="text-sm opacity-70" {
+                    (suggestion.org_name) " · " (suggestion.created_at)
+                }
+            }
+
+            @if !suggestion.reason.is_empty() {
+                p class="whitespace-pre-line" { (suggestion.reason) }
+            }
+
+            @if !suggestion.links.is_empty() {
+                ul class="text-sm" {
+                    @for link in &suggestion.links {
+                        li { a target="_blank" rel="noopener noreferrer" href=(link) class="underline" { (link) } }
+                    }
+                }
+            }
+
+            form method="POST" action=(urls::path(base_path, &format!("/admin/suggestions/{}/approve", suggestion.suggestion_id))) class="grid md:grid-cols-2 gap-4" {
+                label class="flex flex-col space-y-1" {
+                    span class="font-bold" { "Name" }
+                    input type="text" name="name" required value=(suggestion.name) class=(INPUT_CLASS);
+                }
+
+                label class="flex flex-col space-y-1" {
+                    span class="font-bold" { "Org" }
+                    select name="org" required class=(INPUT_CLASS) {
+                        @for org in &options.orgs {
+                            option value=(org.org_id) selected[suggested_org.map_or(false, |s| s.org_id == org.org_id)] {
+                                (org.name)
+                            }
+                        }
+                    }
+                }
+
+                label class="flex flex-col space-y-1" {
+                    span class="font-bold" { "Group" }
+                    select name="group" class=(INPUT_CLASS) {
+                        option value="" { "None" }
+
+                        @for org in options.orgs.iter().filter(|org| !org.groups.is_empty()) {
+                            optgroup label=(org.name) {
+                                @for group in &org.groups {
+                                    option value=(group.group_id) { (group.name) }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                label class="flex flex-col space-y-1" {
+                    span class="font-bold" { "Region" }
+                    select name="region" class=(INPUT_CLASS) {
+                        @for region in &options.regions {
+                            option value=(region.code()) { (i18n::region_name(Locale::En, region)) }
+                        }
+                    }
+                }
+
+                label class="flex flex-col space-y-1 md:col-span-2" {
+                    span class="font-bold" { "Description" }
+                    textarea name="description" rows="3" required class=(INPUT_CLASS) {}
+                }
+
+                label class="flex flex-col space-y-1 md:col-span-2" {
+                    span class="font-bold" { "Image URL" }
+                    input type="url" name="img_source" placeholder="https://" class=(INPUT_CLASS);
+                }
+
+                label class="flex flex-col space-y-1" {
+                    span class="font-bold" { "YouTube" }
+                    input type="url" name="youtube" value=[find_link(&suggestion.links, &["youtube.com", "youtu.be"])] class=(INPUT_CLASS);
+                }
+
+                label class="flex flex-col space-y-1" {
+                    span class="font-bold" { "Twitch" }
+                    input type="url" name="twitch" value=[find_link(&suggestion.links, &["twitch.tv"])] class=(INPUT_CLASS);
+                }
+
+                label class="flex flex-col space-y-1" {
+                    span class="font-bold" { "Twitter" }
+                    input type="url" name="twitter" value=[find_link(&suggestion.links, &["twitter.com"])] class=(INPUT_CLASS);
+                }
+
+                label class="flex flex-col space-y-1" {
+                    span class="font-bold" { "Position in the deck" }
+                    select name="after" class=(INPUT_CLASS) {
+                        option value="" { "At the start" }
+
+                        @for (i, entry) in order.iter().enumerate() {
+                            option value=(entry.vtuber_id) selected[i + 1 == order.len()] {
+                                "After " (entry.name)
+                            }
+                        }
+                    }
+                }
+
+                button class="md:col-span-2 rounded-full px-5 py-2 shadow-md text-white bg-gradient-to-r from-cyan-500 to-blue-500" {
+                    "Approve"
+                }
+            }
+
+            form method="POST" action=(urls::path(base_path, &format!("/admin/suggestions/{}/reject", suggestion.suggestion_id))) {
+                button class="w-full rounded-full px-5 py-2 shadow-md border border-red-500 text-red-500" {
+                    "Reject"
+                }
+            }
+        }
+    }
+}
+
+/// First link that's on one of `domains`, to prefill the profile links with.
+fn find_link<'a>(links: &'a [String], domains: &[&str]) -> Option<&'a str> {
+    links
+        .iter()
+        .find(|link| domains.iter().any(|domain| link.contains(domain)))
+        .map(String::as_str)
+}
+
+/// Queue of open reports, and the VTubers that were hidden because of them (or
+/// by hand).
+pub fn reports(base_path: &str, open: &[Report], hidden: &[HiddenVTuber]) -> Markup {
+    html! {
+        div class="flex-1 flex flex-col items-center space-y-6 dark:text-su-dark-fg-1" {
+            h1 class="font-bold text-2xl md:text-3xl" { "Reports" }
+
+            @if open.is_empty() {
+                p class="opacity-70" { "Nothing to look at right now." }
+            }
+
+            @for report in open {
+                div class="rounded-lg shadow-lg bg-su-bg-2 dark:bg-su-dark-bg-2 w-full md:w-2/3 p-6 space-y-4" {
+                    div {
+                        h2 class="font-bold text-xl" {
+                            a href=(urls::path(base_path, &format!("/vtuber/{}/details", report.vtuber_id))) class="underline" { (report.vtuber_name) }
+
+                            @if report.hidden {
+                                " (hidden)"
+                            }
+                        }
+                        p class="text-sm opacity-70" {
+                            (i18n::t(Locale::En, reason_text(&report.reason))) " · " (report.created_at)
+                        }
+                    }
+
+                    @if !report.details.is_empty() {
+                        p class="whitespace-pre-line" { (report.details) }
+                    }
+
+                    div class="grid grid-cols-2 gap-4" {
+                        form method="POST" action=(urls::path(base_path, &format!("/admin/reports/{}/resolve", report.report_id))) {
+                            button class="w-full rounded-full px-5 py-2 shadow-md text-white bg-red-500" {
+                                "Hide VTuber"
+                            }
+                        }
+
+                        form method="POST" action=(urls::path(base_path, &format!("/admin/reports/{}/dismiss", report.report_id))) {
+                            button class="w-full rounded-full px-5 py-2 shadow-md border border-gray-500" {
+                                "Dismiss"
+                            }
+                        }
+                    }
+                }
+            }
+
+            h2 class="font-bold text-xl md:text-2xl" { "Hidden VTubers" }
+
+            @if hidden.is_empty() {
+                p class="opacity-70" { "Nobody is hidden." }
+            }
+
+            ul class="w-full md:w-2/3 space-y-2" {
+                @for vtuber in hidden {
+                    li class="flex items-center justify-between rounded-lg bg-su-bg-2 dark:bg-su-dark-bg-2 px-4 py-2" {
+                        span { (vtuber.name) " " span class="text-sm opacity-70" { "/" (vtuber.slug) } }
+
+                        form method="POST" action=(urls::path(base_path, &format!("/admin/vtubers/{}/unhide", vtuber.vtuber_id))) {
+                            button class="rounded-full px-4 py-1 shadow-md border border-gray-500" { "Unhide" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The deck in its usual order, with a way to retire each VTuber, and the
+/// retired VTubers with a way to put them back.
+pub fn vtubers(base_path: &str, order: &[DeckEntry], retired: &[RetiredVTuber]) -> Markup {
+    html! {
+        div class="flex-1 flex flex-col items-center space-y-6 dark:text-su-dark-fg-1" {
+            h1 class="font-bold text-2xl md:text-3xl" { "VTubers" }
+
+            ol class="w-full md:w-2/3 space-y-2 list-decimal list-inside" {
+                @for entry in order {
+                    li class="flex items-center justify-between rounded-lg bg-su-bg-2 dark:bg-su-dark-bg-2 px-4 py-2" {
+                        a href=(urls::path(base_path, &format!("/vtuber/{}/details", entry.vtuber_id))) class="underline" { (entry.name) }
+
+                        form method="POST" action=(urls::path(base_path, &format!("/admin/vtubers/{}/retire", entry.vtuber_id))) {
+                            button class="rounded-full px-4 py-1 shadow-md border border-red-500 text-red-500" { "Retire" }
+                        }
+                    }
+                }
+            }
+
+            h2 class="font-bold text-xl md:text-2xl" { "Retired" }
+
+            @if retired.is_empty() {
+                p class="opacity-70" { "Nobody is retired." }
+            }
+
+            ul class="w-full md:w-2/3 space-y-2" {
+                @for vtuber in retired {
+                    li class="rounded-lg bg-su-bg-2 dark:bg-su-dark-bg-2 px-4 py-2 space-y-2" {
+                        div {
+                            a href=(urls::path(base_path, &format!("/vtuber/{}/details", vtuber.vtuber_id))) class="underline" { (vtuber.name) }
+                            " "
+                            span class="text-sm opacity-70" { (vtuber.retired_at) }
+                        }
+
+                        form method="POST" action=(urls::path(base_path, &format!("/admin/vtubers/{}/restore", vtuber.vtuber_id))) class="flex space-x-2" {
+                            select name="after" class=(INPUT_CLASS) {
+                                option value="" { "At the start" }
+
+                                @for entry in order {
+                                    option value=(entry.vtuber_id) selected[vtuber.retired_after == Some(entry.vtuber_id)] {
+                                        "After " (entry.name)
+                                    }
+                                }
+                            }
+
+                            button class="rounded-full px-4 py-1 shadow-md border border-gray-500" { "Restore" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use maud::{html, Markup};
+
+use crate::{
+    oshismash::{
+        deck::{DeckFilter, DeckOptions},
+        locale::Locale,
+    },
+    oshismash_web::{
+        i18n::{self, Text},
+        urls,
+    },
+};
+
+/// Picker for the filters that the deck is narrowed down to. Submitting it
+/// starts the deck with the filter in the URL. The shuffle is separate since
+/// it's kept server-side rather than in the URL.
+pub fn render(
+    locale: Locale,
+    base_path: &str,
+    options: &DeckOptions,
+    filter: &DeckFilter,
+    shuffled: bool,
+) -> Markup {
+    html! {
+        div class="flex-1 flex flex-col justify-center items-center" {
+            form method="GET" action=(urls::path(base_path, "/")) class="rounded-lg shadow-lg bg-su-bg-2 dark:bg-su-dark-bg-2 w-full sm:w-2/3 lg:w-1/3 p-6 md:p-8 space-y-4 dark:text-su-dark-fg-1" {
+                h1 class="font-bold text-2xl md:text-3xl" {
+                    (i18n::t(locale, Text::Filters))
+                }
+
+                label class="flex flex-col space-y-1" {
+                    span class="font-bold" { (i18n::t(locale, Text::FilterOrg)) }
+                    select name="org" class="rounded-md p-2 bg-su-bg-1 dark:bg-su-dark-bg-1" {
+                        option value="" { (i18n::t(locale, Text::FilterAny)) }
+
+                        @for org in &options.orgs {
+                            option value=(org.org_id) selected[filter.org_id == Some(org.org_id)] {
+                                (org.name)
+                            }
+                        }
+                    }
+                }
+
+                label class="flex flex-col space-y-1" {
+                    span class="font-bold" { (i18n::t(locale, Text::FilterGroup)) }
+                    select name="group" class="rounded-md p-2 bg-su-bg-1 dark:bg-su-dark-bg-1" {
+                        option value="" { (i18n::t(locale, Text::FilterAny)) }
+
+                        @for org in options.orgs.iter().filter(|org| !org.groups.is_empty()) {
+                            optgroup label=(org.name) {
+                                @for group in &org.groups {
+                                    option value=(group.group_id) selected[filter.group_id == Some(group.group_id)] {
+                                        (group.name)
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                label class="flex flex-col space-y-1" {
+                    span class="font-bold" { (i18n::t(locale, Text::FilterRegion)) }
+                    select name="region" class="rounded-md p-2 bg-su-bg-1 dark:bg-su-dark-bg-1" {
+                        option value="" { (i18n::t(locale, Text::FilterAny)) }
+
+                        @for region in &options.regions {
+                            option value=(region.code()) selected[filter.region == Some(*region)] {
+                                (i18n::region_name(locale, region))
+                            }
+                        }
+                    }
+                }
+
+                button class="w-full rounded-full px-5 py-2 shadow-md text-white bg-gradient-to-r from-cyan-500 to-blue-500" {
+                    (i18n::t(locale, Text::FilterStart))
+                }
+            }
+
+            form method="POST" action=(urls::path(base_path, &filter.link("/shuffle"))) class="w-full sm:w-2/3 lg:w-1/3 mt-4 text-center dark:text-su-dark-fg-1" {
+                input class="hidden" type="text" name="shuffled" value=(!shuffled);
+
+                button class="w-full rounded-full px-5 py-2 shadow-md border border-cyan-500 text-cyan-500 hover:border-0 hover:bg-gradient-to-t hover:from-cyan-500 hover:to-blue-500 hover:text-white" {
+                    @if shuffled {
+                        (i18n::t(locale, Text::ShuffleOff))
+                    } @else {
+                        (i18n::t(locale, Text::ShuffleOn))
+                    }
+                }
+
+                p class="text-sm mt-2 opacity-70" { (i18n::t(locale, Text::ShuffleHint)) }
+            }
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use maud::{html, Markup};
+
+use crate::{
+    oshismash::{
+        locale::Locale,
+        vtubers::{Details, Metrics},
+    },
+    oshismash_web::{
+        components::{icon, image},
+        i18n::{self, Text},
+    },
+};
+
+pub fn render(locale: Locale, base_path: &str, details: &Details) -> Markup {
+    html! {
+        div class="flex-1 flex flex-col md:flex-row md:items-start md:justify-center md:space-x-8 dark:text-su-dark-fg-1" {
+            figure class="flex-none w-full md:w-1/3 aspect-square bg-su-dark-bg-2 rounded-lg shadow-lg" {
+                (image::vtuber(base_path, details.img.as_deref(), &details.name, "(min-width: 768px) 33vw, 100vw", "object-cover object-top h-full w-full rounded-lg"))
+            }
+
+            div class="flex flex-col w-full md:w-1/2 mt-4 md:mt-0 space-y-4" {
+                div {
+                    h1 class="font-bold text-3xl" { (details.name) }
+
+                    span class="opacity-70" {
+                        (i18n::t(locale, Text::OrgRank)
+                            .replace("{rank}", &details.org_rank.to_string())
+                            .replace("{size}", &details.org_size.to_string())
+                            .replace("{org}", &details.org_name.to_string()))
+                    }
+                }
+
+                dl class="grid grid-cols-2 gap-2" {
+                    dt class="font-bold" { (i18n::t(locale, Text::FilterOrg)) }
+                    dd { (details.org_name.to_string()) }
+
+                    @if let Some(group_name) = &details.group_name {
+                        dt class="font-bold" { (i18n::t(locale, Text::FilterGroup)) }
+                        dd { (group_name) }
+                    }
+
+                    dt class="font-bold" { (i18n::t(locale, Text::FilterRegion)) }
+                    dd { (i18n::region_name(locale, &details.region)) }
+                }
+
+                p class="text-lg" { (details.description) }
+
+                div class="flex space-x-2.5" {
+                    @if let Some(youtube) = &details.youtube {
+                        a target="_blank" rel="noopener" href=(youtube) title="YouTube" { (icon::youtube()) }
+                    }
+
+                    @if let Some(twitch) = &details.twitch {
+                        a target="_blank" rel="noopener" href=(twitch) class="underline" { "Twitch" }
+                    }
+
+                    @if let Some(twitter) = &details.twitter {
+                        a target="_blank" rel="noopener" href=(twitter) title="Twitter" { (icon::twitter()) }
+                    }
+                }
+
+                table class="w-full text-left" {
+                    thead {
+                        tr class="text-sm opacity-70" {
+                            th class="p-2" {}
+                            th class="p-2 text-right" { (i18n::t(locale, Text::Smashes)) }
+                            th class="p-2 text-right" { (i18n::t(locale, Text::Passes)) }
+                            th class="p-2 text-right" { (i18n::t(locale, Text::Skips)) }
+                            th class="p-2 text-right" { (i18n::t(locale, Text::SmashRatio)) }
+                        }
+                    }
+
+                    tbody {
+                        (stats_row(i18n::t(locale, Text::AllTime), &details.stats.all_time))
+                        (stats_row(i18n::t(locale, Text::LastMonth), &details.stats.last_month))
+                        (stats_row(i18n::t(locale, Text::LastWeek), &details.stats.last_week))
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn stats_row(label: &str, metrics: &Metrics) -> Markup {
+    let ratio = match metrics.smashes + metrics.passes {
+        0 => String::from("-"),
+        total => format!("{:.0}%", metrics.smashes as f64 / total as f64 * 100.0),
+    };
+
+    html! {
+        tr class="border-t border-su-bg-2 dark:border-su-dark-bg-2" {
+            th class="p-2" { (label) }
+            td class="p-2 text-right" { (metrics.smashes) }
+            td class="p-2 text-right" { (metrics.passes) }
+            td class="p-2 text-right" { (metrics.skips) }
+            td class="p-2 text-right" { (ratio) }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use hyper::StatusCode;
+use maud::{html, Markup};
+
+use crate::{
+    oshismash::locale::Locale,
+    oshismash_web::{
+        i18n::{self, ErrorMessage, Text},
+        urls,
+    },
+};
+
+pub fn render(
+    locale: Locale,
+    base_path: &str,
+    message: &ErrorMessage,
+    status: StatusCode,
+    back_url: &str,
+) -> Markup {
+    let hint = if status.is_server_error() {
+        Text::ErrorServerHint
+    } else {
+        Text::ErrorClientHint
+    };
+
+    html! {
+        div class="flex-1 flex flex-col justify-center items-center" {
+            div class="rounded-lg shadow-lg bg-su-bg-2 dark:bg-su-dark-bg-2 w-full sm:w-2/3 lg:w-1/3 p-6 md:p-8 text-center dark:text-su-dark-fg-1" {
+                span class="font-mono text-sm opacity-70" { (message.code) }
+
+                h1 class="font-bold text-2xl md:text-3xl mt-2" {
+                    (i18n::t(locale, Text::ErrorTitle))
+                }
+
+                p class="text-lg mt-4" { (message.explain(locale)) }
+                p class="mt-2 opacity-70" { (i18n::t(locale, hint)) }
+
+                div class="flex justify-center space-x-2.5 mt-6" {
+                    a href=(back_url) class="rounded-full px-5 py-2 shadow-md border border-cyan-500 text-cyan-500 hover:border-0 hover:bg-gradient-to-t hover:from-cyan-500 hover:to-blue-500 hover:text-white" {
+                        (i18n::t(locale, Text::GoBack))
+                    }
+
+                    a href=(urls::path(base_path, "/")) class="rounded-full px-5 py-2 shadow-md bg-su-bg-1 dark:bg-su-dark-bg-1" {
+                        (i18n::t(locale, Text::Home))
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use maud::{html, Markup};
+
+use crate::{
+    oshismash::{leaderboard::Entry, locale::Locale},
+    oshismash_web::{
+        components::image,
+        i18n::{self, Text},
+        urls,
+    },
+};
+
+pub fn render(locale: Locale, base_path: &str, entries: &[Entry]) -> Markup {
+    html! {
+        div class="flex-1 flex flex-col items-center dark:text-su-dark-fg-1" {
+            h1 class="font-bold text-2xl md:text-3xl mb-4" {
+                (i18n::t(locale, Text::Leaderboard))
+            }
+
+            table class="w-full md:w-2/3 text-left" {
+                thead {
+                    tr class="text-sm opacity-70" {
+                        th class="p-2" { "#" }
+                        th class="p-2" {}
+                        th class="p-2 text-right" { (i18n::t(locale, Text::Smashes)) }
+                        th class="p-2 text-right" { (i18n::t(locale, Text::Passes)) }
+                        th class="p-2 text-right" { (i18n::t(locale, Text::SmashRatio)) }
+                        th class="p-2 text-right" { (i18n::t(locale, Text::Awareness)) }
+                    }
+                }
+
+                tbody {
+                    @for (rank, entry) in entries.iter().enumerate() {
+                        tr class="border-t border-su-bg-2 dark:border-su-dark-bg-2" {
+                            td class="p-2 font-bold" { (rank + 1) }
+                            td class="p-2" {
+                                div class="flex items-center space-x-2.5" {
+                                    figure class="flex-none w-10 aspect-square bg-su-dark-bg-2 rounded-md" {
+                                        (image::vtuber(base_path, entry.img.as_deref(), &entry.name, "40px", "object-cover object-top h-full w-full rounded-md"))
+                                    }
+
+                                    div class="flex flex-col" {
+                                        a href=(urls::path(base_path, &format!("/vtuber/{}/details", entry.vtuber_id))) class="font-bold" { (entry.name) }
+                                        span class="text-sm opacity-70" { (entry.org_name.to_string()) }
+                                    }
+                                }
+                            }
+                            td class="p-2 text-right" { (entry.smashes) }
+                            td class="p-2 text-right" { (entry.passes) }
+                            td class="p-2 text-right" { (percentage(entry.smash_ratio())) }
+                            td class="p-2 text-right" { (percentage(entry.awareness())) }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn percentage(ratio: Option<f64>) -> String {
+    match ratio {
+        Some(ratio) => format!("{:.0}%", ratio * 100.0),
+        None => String::from("-"),
+    }
+}
@@ -0,0 +1,54 @@
+use maud::{html, Markup};
+
+use crate::{
+    oshismash::{locale::Locale, reports::Reason, vtubers::Details},
+    oshismash_web::{
+        i18n::{self, Text},
+        urls,
+    },
+};
+
+/// Form for reporting a VTuber's card. It goes to the moderation queue, and
+/// nothing changes until a moderator looks at it.
+pub fn render(locale: Locale, base_path: &str, vtuber: &Details) -> Markup {
+    html! {
+        div class="flex-1 flex flex-col justify-center items-center" {
+            form method="POST" action=(urls::path(base_path, &format!("/vtuber/{}/report", vtuber.id))) class="rounded-lg shadow-lg bg-su-bg-2 dark:bg-su-dark-bg-2 w-full sm:w-2/3 lg:w-1/3 p-6 md:p-8 space-y-4 dark:text-su-dark-fg-1" {
+                h1 class="font-bold text-2xl md:text-3xl" {
+                    (i18n::t(locale, Text::ReportVTuber).replace("{name}", &vtuber.name))
+                }
+
+                p class="text-sm opacity-70" { (i18n::t(locale, Text::ReportHint)) }
+
+                fieldset class="flex flex-col space-y-1" {
+                    legend class="font-bold mb-1" { (i18n::t(locale, Text::ReportReason)) }
+
+                    @for (i, reason) in Reason::ALL.iter().enumerate() {
+                        label class="flex items-center space-x-2" {
+                            input type="radio" name="reason" value=(reason.code()) required checked[i == 0];
+                            span { (i18n::t(locale, reason_text(reason))) }
+                        }
+                    }
+                }
+
+                label class="flex flex-col space-y-1" {
+                    span class="font-bold" { (i18n::t(locale, Text::ReportDetails)) }
+                    textarea name="details" rows="4" maxlength="1000" class="rounded-md p-2 bg-su-bg-1 dark:bg-su-dark-bg-1" {}
+                }
+
+                button class="w-full rounded-full px-5 py-2 shadow-md text-white bg-gradient-to-r from-cyan-500 to-blue-500" {
+                    (i18n::t(locale, Text::ReportSubmit))
+                }
+            }
+        }
+    }
+}
+
+pub fn reason_text(reason: &Reason) -> Text {
+    match reason {
+        Reason::Takedown => Text::ReportReasonTakedown,
+        Reason::WrongImage => Text::ReportReasonWrongImage,
+        Reason::WrongInfo => Text::ReportReasonWrongInfo,
+        Reason::Other => Text::ReportReasonOther,
+    }
+}
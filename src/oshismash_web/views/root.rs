@@ -1,76 +1,219 @@
 use maud::{html, Markup, DOCTYPE};
 
-use crate::oshismash_web::components::icon;
+use crate::{
+    media::{Format, Variant, WIDTHS},
+    oshismash::{
+        locale::Locale,
+        vtubers::{Details, VTuber},
+    },
+    oshismash_web::{
+        components::{icon, image},
+        flash::Flash,
+        i18n::{self, Text},
+        urls,
+    },
+};
 
-pub fn render(title: &str, content: Markup) -> Markup {
+const SITE_NAME: &str = "Oshi Smash";
+
+/// Metadata of a page. This is what ends up in `<head>`, including the Open
+/// Graph and Twitter card tags that are used when a link gets shared.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Meta {
+    pub title: String,
+    pub description: String,
+    /// Canonical URL of the page.
+    pub url: Option<String>,
+    /// Image used for link previews.
+    pub image: Option<String>,
+}
+
+impl Meta {
+    /// Metadata for pages that aren't about a specific VTuber.
+    pub fn new(title: &str, locale: Locale) -> Meta {
+        Meta {
+            title: title.to_string(),
+            description: i18n::t(locale, Text::DefaultDescription).to_string(),
+            url: None,
+            image: None,
+        }
+    }
+
+    /// Metadata for a VTuber's page, so that sharing a link to a specific oshi
+    /// previews their name, description, and image.
+    pub fn from_vtuber(vtuber: &VTuber, base_url: &str, locale: Locale) -> Meta {
+        Meta {
+            title: i18n::t(locale, Text::VTuberTitle).replace("{name}", &vtuber.name),
+            description: i18n::t(locale, Text::VTuberDescription)
+                .replace("{name}", &vtuber.name)
+                .replace("{org}", &vtuber.org_name.to_string())
+                .replace("{description}", &vtuber.description),
+            url: Some(format!("{}/{}", base_url, vtuber.slug)),
+            image: vtuber.img.as_deref().map(|key| share_image(base_url, key)),
+        }
+    }
+
+    /// Metadata for a VTuber's profile page.
+    pub fn from_details(details: &Details, base_url: &str, locale: Locale) -> Meta {
+        Meta {
+            title: i18n::t(locale, Text::VTuberTitle).replace("{name}", &details.name),
+            description: i18n::t(locale, Text::VTuberDescription)
+                .replace("{name}", &details.name)
+                .replace("{org}", &details.org_name.to_string())
+                .replace("{description}", &details.description),
+            url: Some(format!("{}/vtuber/{}/details", base_url, details.id)),
+            image: details.img.as_deref().map(|key| share_image(base_url, key)),
+        }
+    }
+
+    pub fn with_url(mut self, url: String) -> Meta {
+        self.url = Some(url);
+        self
+    }
+}
+
+/// Link previews need an absolute URL, and a format that every site supports.
+/// The base URL already ends with the base path.
+fn share_image(base_url: &str, key: &str) -> String {
+    let variant = Variant {
+        width: WIDTHS[2],
+        format: Format::Webp,
+    };
+
+    image::url(base_url, key, variant)
+}
+
+pub fn render(
+    locale: Locale,
+    base_path: &str,
+    meta: &Meta,
+    flash: Option<Flash>,
+    content: Markup,
+) -> Markup {
     html! {
-        (header(title))
+        (DOCTYPE)
+        html lang=(locale.code()) {
+            (header(base_path, meta))
 
-        body class="bg-gray-100 dark:bg-su-dark-bg-1 h-screen flex flex-col" {
-            main class="max-w-7xl mx-auto px-4 sm:px-6 lg:px-8 w-full flex flex-col flex-1 py-4 md:py-8" {
-                div class="flex items-center justify-between md:justify-center mb-4 md:mb-8" {
-                    (logo())
+            body class="bg-gray-100 dark:bg-su-dark-bg-1 h-screen flex flex-col" {
+                main class="max-w-7xl mx-auto px-4 sm:px-6 lg:px-8 w-full flex flex-col flex-1 py-4 md:py-8" {
+                    div class="flex items-center justify-between md:justify-center mb-4 md:mb-8" {
+                        (logo(base_path))
 
-                    nav class="dark:text-su-dark-fg-1 flex space-x-2 md:hidden" {
-                        a target="_blank" href="https://ko-fi.com/sekun" {
-                            (icon::money())
-                        }
+                        nav class="dark:text-su-dark-fg-1 flex space-x-2 md:hidden" {
+                            (language_links(locale, base_path))
 
-                        a target="_blank" href="https://twitter.com/sekunho_" {
-                            (icon::twitter())
-                        }
+                            a target="_blank" href="https://ko-fi.com/sekun" {
+                                (icon::money())
+                            }
 
-                        a target="_blank" href="https://youtube.com/sekunho" {
-                            (icon::youtube())
-                        }
+                            a target="_blank" href="https://twitter.com/sekunho_" {
+                                (icon::twitter())
+                            }
+
+                            a target="_blank" href="https://youtube.com/sekunho" {
+                                (icon::youtube())
+                            }
 
-                        a target="_blank" href="https://github.com/sekunho/oshismash" {
-                            (icon::github())
+                            a target="_blank" href="https://github.com/sekunho/oshismash" {
+                                (icon::github())
+                            }
                         }
                     }
-                }
 
-                (content)
+                    @if let Some(flash) = flash {
+                        (flash_message(locale, flash))
+                    }
+
+                    (content)
+                }
             }
+
+            (footer(locale, base_path))
         }
+    }
+}
+
+fn flash_message(locale: Locale, flash: Flash) -> Markup {
+    let color = if flash.is_warning() {
+        "from-red-500 to-pink-500"
+    } else {
+        "from-cyan-500 to-blue-500"
+    };
 
-        (footer())
+    html! {
+        div role="status" class=(format!("mx-auto mb-4 rounded-lg shadow-md px-4 py-2 text-white text-center bg-gradient-to-r {}", color)) {
+            (i18n::t(locale, flash.text()))
+        }
     }
 }
 
-fn logo() -> Markup {
+fn logo(base_path: &str) -> Markup {
     html! {
-        a href="/" class="font-serif mb-auto dark:text-su-dark-fg-1 font-semibold uppercase text-2xl md:text-4xl text-center" { ("Oshi Smash") }
+        a href=(urls::path(base_path, "/")) class="font-serif mb-auto dark:text-su-dark-fg-1 font-semibold uppercase text-2xl md:text-4xl text-center" { ("Oshi Smash") }
     }
 }
 
-fn header(title: &str) -> Markup {
+fn header(base_path: &str, meta: &Meta) -> Markup {
     html! {
-        (DOCTYPE)
         meta charset="utf-8";
 
-        link rel="stylesheet" href="/assets/app.css";
+        link rel="stylesheet" href=(urls::path(base_path, "/assets/app.css"));
         link rel="preconnect" href="https://fonts.googleapis.com";
         link rel="preconnect" href="https://fonts.gstatic.com" crossorigin;
         link href="https://fonts.googleapis.com/css2?family=Vollkorn:wght@600&display=swap&text=OSHIMA" rel="stylesheet";
 
-        link rel="apple-touch-icon" sizes="180x180" href="/assets/apple-touch-icon.png";
-        link rel="icon" type="image/png" sizes="32x32" href="/assets/favicon-32x32.png";
-        link rel="icon" type="image/png" sizes="16x16" href="/assets/favicon-16x16.png";
-        link rel="manifest" href="/assets/site.webmanifest";
+        link rel="apple-touch-icon" sizes="180x180" href=(urls::path(base_path, "/assets/apple-touch-icon.png"));
+        link rel="icon" type="image/png" sizes="32x32" href=(urls::path(base_path, "/assets/favicon-32x32.png"));
+        link rel="icon" type="image/png" sizes="16x16" href=(urls::path(base_path, "/assets/favicon-16x16.png"));
+        link rel="manifest" href=(urls::path(base_path, "/assets/site.webmanifest"));
 
         meta name="viewport" content="width=device-width, initial-scale=1.0";
 
-        title { (title) }
+        title { (meta.title) }
+        meta name="description" content=(meta.description);
+
+        @if let Some(url) = &meta.url {
+            link rel="canonical" href=(url);
+            meta property="og:url" content=(url);
+        }
+
+        meta property="og:type" content="website";
+        meta property="og:site_name" content=(SITE_NAME);
+        meta property="og:title" content=(meta.title);
+        meta property="og:description" content=(meta.description);
+
+        @if let Some(image) = &meta.image {
+            meta property="og:image" content=(image);
+            meta name="twitter:card" content="summary_large_image";
+            meta name="twitter:image" content=(image);
+        } @else {
+            meta name="twitter:card" content="summary";
+        }
+
+        meta name="twitter:site" content="@sekunho_";
+        meta name="twitter:title" content=(meta.title);
+        meta name="twitter:description" content=(meta.description);
     }
 }
 
-fn footer() -> Markup {
+/// Links to switch to the other locales.
+fn language_links(locale: Locale, base_path: &str) -> Markup {
+    html! {
+        @for other in Locale::ALL.iter().filter(|l| **l != locale) {
+            a href=(urls::path(base_path, &format!("/locale/{}", other.code()))) lang=(other.code()) {
+                (other.native_name())
+            }
+        }
+    }
+}
+
+fn footer(locale: Locale, base_path: &str) -> Markup {
     html! {
         footer class="hidden md:block bg-gray-100 dark:bg-su-dark-bg-1" {
             div class="max-w-7xl mx-auto pb-6 pt-2 px-4 sm:px-6 flex flex-col-reverse items-center md:flex-row md:items-center md:justify-between lg:px-8 text-su-fg-1 dark:text-su-dark-fg-1" {
                 span class="hidden md:block mt-4 md:mt-0" {
-                    "Made by "
+                    (i18n::t(locale, Text::MadeBy))
                     a href="https://sekun.dev" target="_blank" class="underline decoration-wavy decoration-red-500" {
                         "SEKUN"
                     }
@@ -78,10 +221,24 @@ fn footer() -> Markup {
                 }
 
                 nav class="space-y-1 sm:space-y-0 space-x-5 flex items-end" {
-                    a href="/" {
-                        "Home"
+                    a href=(urls::path(base_path, "/")) {
+                        (i18n::t(locale, Text::Home))
                     }
 
+                    a href=(urls::path(base_path, "/leaderboard")) {
+                        (i18n::t(locale, Text::Leaderboard))
+                    }
+
+                    a href=(urls::path(base_path, "/search")) {
+                        (i18n::t(locale, Text::Search))
+                    }
+
+                    a href=(urls::path(base_path, "/suggest")) {
+                        (i18n::t(locale, Text::SuggestVTuber))
+                    }
+
+                    (language_links(locale, base_path))
+
                     a target="_blank" href="https://ko-fi.com/sekun" {
                         (icon::money())
                     }
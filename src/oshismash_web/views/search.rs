@@ -0,0 +1,65 @@
+use maud::{html, Markup};
+
+use crate::{
+    oshismash::{locale::Locale, search::SearchResult},
+    oshismash_web::{
+        components::image,
+        i18n::{self, Text},
+        urls,
+    },
+};
+
+/// Search form, and the results if something was searched for. `search.js`
+/// adds suggestions while typing, but the form works fine without it.
+pub fn render(
+    locale: Locale,
+    base_path: &str,
+    query: &str,
+    results: Option<&[SearchResult]>,
+) -> Markup {
+    html! {
+        div class="flex-1 flex flex-col items-center dark:text-su-dark-fg-1" {
+            form method="GET" action=(urls::path(base_path, "/search")) class="relative w-full sm:w-2/3 lg:w-1/3" {
+                input
+                    id="search"
+                    type="search"
+                    name="q"
+                    value=(query)
+                    autocomplete="off"
+                    placeholder=(i18n::t(locale, Text::SearchPlaceholder))
+                    data-suggestions=(urls::path(base_path, "/search/suggestions"))
+                    data-base-path=(base_path)
+                    class="w-full rounded-full px-5 py-2 shadow-md bg-su-bg-2 dark:bg-su-dark-bg-2";
+
+                ul id="search-suggestions" class="hidden absolute left-0 right-0 mt-2 rounded-lg shadow-lg bg-su-bg-2 dark:bg-su-dark-bg-2 overflow-hidden z-10" {}
+            }
+
+            @if let Some(results) = results {
+                @if results.is_empty() {
+                    p class="mt-6 opacity-70" {
+                        (i18n::t(locale, Text::NoSearchResults).replace("{query}", query))
+                    }
+                } @else {
+                    ul class="w-full sm:w-2/3 lg:w-1/3 mt-6 space-y-2" {
+                        @for result in results {
+                            li {
+                                a href=(urls::path(base_path, &format!("/vtuber/{}/details", result.id))) class="flex items-center space-x-2.5 rounded-lg p-2 hover:bg-su-bg-2 dark:hover:bg-su-dark-bg-2" {
+                                    figure class="flex-none w-10 aspect-square bg-su-dark-bg-2 rounded-md" {
+                                        (image::vtuber(base_path, result.img.as_deref(), &result.name, "40px", "object-cover object-top h-full w-full rounded-md"))
+                                    }
+
+                                    div class="flex flex-col" {
+                                        span class="font-bold" { (result.name) }
+                                        span class="text-sm opacity-70" { (result.org_name.to_string()) }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            script src=(urls::path(base_path, "/assets/search.js")) defer {}
+        }
+    }
+}
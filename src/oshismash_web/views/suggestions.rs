@@ -0,0 +1,50 @@
+use maud::{html, Markup};
+
+use crate::{
+    oshismash::locale::Locale,
+    oshismash_web::{
+        i18n::{self, Text},
+        urls,
+    },
+};
+
+/// Form for suggesting a VTuber. It goes to the moderation queue rather than
+/// straight into the deck.
+pub fn render(locale: Locale, base_path: &str) -> Markup {
+    html! {
+        div class="flex-1 flex flex-col justify-center items-center" {
+            form method="POST" action=(urls::path(base_path, "/suggest")) class="rounded-lg shadow-lg bg-su-bg-2 dark:bg-su-dark-bg-2 w-full sm:w-2/3 lg:w-1/3 p-6 md:p-8 space-y-4 dark:text-su-dark-fg-1" {
+                h1 class="font-bold text-2xl md:text-3xl" {
+                    (i18n::t(locale, Text::SuggestVTuber))
+                }
+
+                p class="text-sm opacity-70" { (i18n::t(locale, Text::SuggestHint)) }
+
+                label class="flex flex-col space-y-1" {
+                    span class="font-bold" { (i18n::t(locale, Text::SuggestName)) }
+                    input type="text" name="name" required maxlength="100" class="rounded-md p-2 bg-su-bg-1 dark:bg-su-dark-bg-1";
+                }
+
+                label class="flex flex-col space-y-1" {
+                    span class="font-bold" { (i18n::t(locale, Text::SuggestOrg)) }
+                    input type="text" name="org" maxlength="100" class="rounded-md p-2 bg-su-bg-1 dark:bg-su-dark-bg-1";
+                }
+
+                label class="flex flex-col space-y-1" {
+                    span class="font-bold" { (i18n::t(locale, Text::SuggestLinks)) }
+                    textarea name="links" rows="3" placeholder="https://" class="rounded-md p-2 bg-su-bg-1 dark:bg-su-dark-bg-1" {}
+                    span class="text-sm opacity-70" { (i18n::t(locale, Text::SuggestLinksHint)) }
+                }
+
+                label class="flex flex-col space-y-1" {
+                    span class="font-bold" { (i18n::t(locale, Text::SuggestReason)) }
+                    textarea name="reason" rows="4" maxlength="1000" class="rounded-md p-2 bg-su-bg-1 dark:bg-su-dark-bg-1" {}
+                }
+
+                button class="w-full rounded-full px-5 py-2 shadow-md text-white bg-gradient-to-r from-cyan-500 to-blue-500" {
+                    (i18n::t(locale, Text::SuggestSubmit))
+                }
+            }
+        }
+    }
+}
@@ -2,13 +2,19 @@ use maud::{html, Markup};
 
 use crate::{
     oshismash::{
+        deck::DeckFilter,
+        locale::Locale,
         vote::{Stat, UserAction},
         vtubers::{Stack, VTuber},
     },
-    oshismash_web::components::icon,
+    oshismash_web::{
+        components::{icon, image},
+        i18n::{self, Text},
+        urls,
+    },
 };
 
-pub fn render(stack: Stack) -> Markup {
+pub fn render(stack: Stack, locale: Locale, base_path: &str, filter: &DeckFilter) -> Markup {
     let current_vtuber = stack.get_current();
 
     html! {
@@ -23,38 +29,44 @@ pub fn render(stack: Stack) -> Markup {
 
                     div id="card" class="top-5 left-0 right-0 mx-auto absolute rounded-lg shadow-lg opacity-70 bg-su-bg-2 dark:bg-su-dark-bg-2 w-11/12 h-full mx-auto" {
                     }
-                    (card(vtuber))
+                    (card(vtuber, locale, base_path, filter))
                 } @else {
-                    (last_card())
+                    (last_card(locale))
                 }
             }
 
             div class="flex mt-16 space-x-2.5" {
                 // noscript {
-                    (prev_vtuber(&stack))
-                    (next_vtuber(&stack))
+                    (prev_vtuber(&stack, base_path, filter))
+                    (next_vtuber(&stack, base_path, filter))
                 // }
 
                 @match stack.clone() {
                     Stack::NoPrev { current, vote_for_current, .. } => {
-                        (pass(&current, &vote_for_current))
-                        (smash(&current, &vote_for_current))
+                        (pass(&current, &vote_for_current, locale, base_path, filter))
+                        (skip(&current, &vote_for_current, locale, base_path, filter))
+                        (smash(&current, &vote_for_current, locale, base_path, filter))
                     }
 
                     Stack::HasBoth { current, vote_for_current, .. } => {
-                        (pass(&current, &vote_for_current))
-                        (smash(&current, &vote_for_current))
+                        (pass(&current, &vote_for_current, locale, base_path, filter))
+                        (skip(&current, &vote_for_current, locale, base_path, filter))
+                        (smash(&current, &vote_for_current, locale, base_path, filter))
                     }
 
                     Stack::NoCurrent { .. } => ("")
                 }
             }
 
+            a href=(urls::path(base_path, &filter.link("/filters"))) class="mt-4 text-sm underline dark:text-su-dark-fg-1" {
+                (i18n::t(locale, Text::Filters))
+            }
+
             @if let Some(stat) = stack.get_last_voted_stat() {
-                span class="hidden md:block dark:text-su-dark-fg-1 mt-6" { (format!("What others voted for {}", stat.name)) }
+                span class="hidden md:block dark:text-su-dark-fg-1 mt-6" { (i18n::t(locale, Text::WhatOthersVotedFor).replace("{name}", &stat.name)) }
                 div class="flex justify-center space-x-2 w-full sm:w-2/3 md:w-1/3 mt-4 dark:text-su-dark-fg-1" {
                     div class="flex flex-col w-full items-end" {
-                        span class="text-sm md:text-base font-bold mb-1 md:mb-2.5 text-right" { ("Passes") }
+                        span class="text-sm md:text-base font-bold mb-1 md:mb-2.5 text-right" { (i18n::t(locale, Text::Passes)) }
                         span
                             class="text-sm md:text-base rounded-md h-2 md:h-6 bg-gradient-to-l from-red-500 to-pink-500"
                             style=(style_percentage(stat.passes, stat.smashes + stat.passes)) {
@@ -65,11 +77,11 @@ pub fn render(stack: Stack) -> Markup {
                     }
 
                     figure class="flex-none w-12 md:w-24 aspect-square bg-su-dark-bg-2 rounded-md" {
-                        img class="object-cover object-top h-full w-full" src=(stat.img.as_ref().unwrap());
+                        (image::vtuber(base_path, stat.img.as_deref(), &stat.name, "96px", "object-cover object-top h-full w-full"))
                     }
 
                     div class="flex flex-col w-full" {
-                        span class="text-sm md:text-base font-bold mb-1 md:mb-2.5" { ("Smashes") }
+                        span class="text-sm md:text-base font-bold mb-1 md:mb-2.5" { (i18n::t(locale, Text::Smashes)) }
                         span
                             class="text-sm md:text-base rounded-md h-2 md:h-6 bg-gradient-to-r from-cyan-500 to-blue-500"
                             style=(style_percentage(stat.smashes, stat.smashes + stat.passes)) {
@@ -79,33 +91,45 @@ pub fn render(stack: Stack) -> Markup {
                         span class="font-bold text-lg" { (stat.smashes) }
                     }
                 }
+
+                @if stat.skips > 0 {
+                    span class="text-sm mt-2 opacity-70 dark:text-su-dark-fg-1" {
+                        (i18n::t(locale, Text::SkipsCount).replace("{count}", &stat.skips.to_string()))
+                    }
+                }
             }
         }
     }
 }
 
 fn style_percentage(nominator: i64, denominator: i64) -> String {
+    // Skips are neither, so a VTuber that was only skipped has no smashes or
+    // passes to divide by.
+    if denominator == 0 {
+        return String::from("width: 0%;");
+    }
+
     format!(
         "width: {}%;",
         (nominator as f64 / denominator as f64) * 100 as f64
     )
 }
 
-fn last_card() -> Markup {
+fn last_card(locale: Locale) -> Markup {
     html! {
         div id="card" class="flex items-center justify-center absolute rounded-lg shadow-lg bg-su-bg-2 dark:bg-su-dark-bg-2 w-full h-full mx-auto" {
             span class="font-medium text-white text-2xl text-center" {
-                ("You can touch grass now.")
+                (i18n::t(locale, Text::TouchGrass))
             }
         }
     }
 }
 
-fn card(vtuber: &VTuber) -> Markup {
+fn card(vtuber: &VTuber, locale: Locale, base_path: &str, filter: &DeckFilter) -> Markup {
     html! {
         div id="card" class="absolute rounded-lg shadow-lg bg-su-bg-2 dark:bg-su-dark-bg-2 w-full h-full mx-auto" {
             figure class="h-full w-full rounded-lg relative" {
-                img class="object-top object-cover h-full w-full rounded-lg" src=(vtuber.img);
+                (image::vtuber(base_path, vtuber.img.as_deref(), &vtuber.name, "(min-width: 1024px) 33vw, (min-width: 640px) 66vw, 100vw", "object-top object-cover h-full w-full rounded-lg"))
 
                 figcaption class="w-full left-0 bottom-0 rounded-b-lg absolute bg-gradient-to-t from-black p-4" {
                     div class="flex items-center space-x-2.5" {
@@ -116,17 +140,33 @@ fn card(vtuber: &VTuber) -> Markup {
                         span class="text-white text-lg" {
                             (vtuber.org_name.to_string())
                         }
+
+                        @if let Some(group_name) = &vtuber.group_name {
+                            span class="text-white text-lg opacity-70" {
+                                (group_name)
+                            }
+                        }
                     }
 
                     p class="text-lg text-white overflow-y-auto max-h-24" {
                         (vtuber.description)
                     }
+
+                    div class="flex space-x-2.5" {
+                        a href=(urls::path(base_path, &format!("/vtuber/{}/details", vtuber.id))) class="text-sm text-white underline opacity-70" {
+                            (i18n::t(locale, Text::Profile))
+                        }
+
+                        a href=(urls::path(base_path, &format!("/vtuber/{}/report", vtuber.id))) class="text-sm text-white underline opacity-70" {
+                            (i18n::t(locale, Text::Report))
+                        }
+                    }
                 }
 
 
                 div class="top-2 left-2 absolute space-y-2" {
-                    (next_button(vtuber.next))
-                    (prev_button(vtuber.prev))
+                    (next_button(vtuber.next, base_path, filter))
+                    (prev_button(vtuber.prev, base_path, filter))
                 }
             }
         }
@@ -134,11 +174,11 @@ fn card(vtuber: &VTuber) -> Markup {
 }
 
 // TODO: Use newtype
-fn prev_button(vtuber_id: Option<i64>) -> Markup {
+fn prev_button(vtuber_id: Option<i64>, base_path: &str, filter: &DeckFilter) -> Markup {
     html! {
         @match vtuber_id {
             Some(vtuber_id) =>  {
-                form method="POST" action="/" {
+                form method="POST" action=(urls::path(base_path, &filter.link("/"))) {
                     input class="hidden" type="text" name="action" value="prev";
                     input class="hidden" type="text" name="vtuber_id" value=(vtuber_id);
                     button class="rounded-full h-6 w-6 dark:bg-su-dark-bg-1" {
@@ -161,11 +201,11 @@ fn prev_button(vtuber_id: Option<i64>) -> Markup {
     }
 }
 
-fn next_button(vtuber_id: Option<i64>) -> Markup {
+fn next_button(vtuber_id: Option<i64>, base_path: &str, filter: &DeckFilter) -> Markup {
     html! {
         @match vtuber_id {
             Some(vtuber_id) =>  {
-                form method="POST" action="/" {
+                form method="POST" action=(urls::path(base_path, &filter.link("/"))) {
                     input class="hidden" type="text" name="action" value="next";
                     input class="hidden" type="text" name="vtuber_id" value=(vtuber_id);
                     button class="rounded-full h-6 w-6 dark:bg-su-dark-bg-1" {
@@ -188,10 +228,16 @@ fn next_button(vtuber_id: Option<i64>) -> Markup {
     }
 }
 
-fn smash(current_vtuber: &VTuber, current_vote: &Option<UserAction>) -> Markup {
+fn smash(
+    current_vtuber: &VTuber,
+    current_vote: &Option<UserAction>,
+    locale: Locale,
+    base_path: &str,
+    filter: &DeckFilter,
+) -> Markup {
     // let voted = vote_list.into_raw_parts
     html! {
-        form method="POST" action="/" {
+        form method="POST" action=(urls::path(base_path, &filter.link("/"))) {
             input class="hidden" type="text" name="action" value="smashed";
             input class="hidden" type="text" name="vtuber_id" value=(current_vtuber.id);
 
@@ -203,7 +249,7 @@ fn smash(current_vtuber: &VTuber, current_vote: &Option<UserAction>) -> Markup {
                 }
 
                 _ => {
-                    button class="shadow-md rounded-full h-12 w-12 md:h-14 md:w-14 border border-cyan-500 hover:border-0 hover:bg-gradient-to-t hover:from-cyan-500 hover:to-blue-500 text-cyan-500 hover:text-white" title="Smash" {
+                    button class="shadow-md rounded-full h-12 w-12 md:h-14 md:w-14 border border-cyan-500 hover:border-0 hover:bg-gradient-to-t hover:from-cyan-500 hover:to-blue-500 text-cyan-500 hover:text-white" title=(i18n::t(locale, Text::Smash)) {
                         p class="mx-auto h-6 w-6 md:h-8 md:w-8 flex items-center justify-center" {
                             (icon::heart())
                         }
@@ -214,9 +260,46 @@ fn smash(current_vtuber: &VTuber, current_vote: &Option<UserAction>) -> Markup {
     }
 }
 
-fn pass(current_vtuber: &VTuber, current_vote: &Option<UserAction>) -> Markup {
+fn skip(
+    current_vtuber: &VTuber,
+    current_vote: &Option<UserAction>,
+    locale: Locale,
+    base_path: &str,
+    filter: &DeckFilter,
+) -> Markup {
+    html! {
+        form method="POST" action=(urls::path(base_path, &filter.link("/"))) {
+            input class="hidden" type="text" name="action" value="skipped";
+            input class="hidden" type="text" name="vtuber_id" value=(current_vtuber.id);
+
+            @match current_vote {
+                Some(UserAction::Skipped) => {
+                    button class="shadow-md rounded-full h-12 w-12 md:h-14 md:w-14 bg-gray-500" title=(i18n::t(locale, Text::Skip)) {
+                        p class="mx-auto h-6 w-6 md:h-8 md:w-8 text-white flex items-center justify-center" { (icon::question_mark()) }
+                    }
+                }
+
+                _ => {
+                    button class="shadow-md rounded-full h-12 w-12 md:h-14 md:w-14 border border-gray-500 hover:border-0 hover:bg-gray-500 text-gray-500 hover:text-white" title=(i18n::t(locale, Text::Skip)) {
+                        p class="mx-auto h-6 w-6 md:h-8 md:w-8 flex items-center justify-center" {
+                            (icon::question_mark())
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+fn pass(
+    current_vtuber: &VTuber,
+    current_vote: &Option<UserAction>,
+    locale: Locale,
+    base_path: &str,
+    filter: &DeckFilter,
+) -> Markup {
     html! {
-        form method="POST" action="/" {
+        form method="POST" action=(urls::path(base_path, &filter.link("/"))) {
             input class="hidden" type="text" name="action" value="passed";
             input class="hidden" type="text" name="vtuber_id" value=(current_vtuber.id);
 
@@ -229,7 +312,7 @@ fn pass(current_vtuber: &VTuber, current_vote: &Option<UserAction>) -> Markup {
                 }
 
                 _ => {
-                    button class="shadow-md rounded-full h-12 w-12 md:h-14 md:w-14 border border-red-500 hover:border-0 hover:bg-gradient-to-t hover:from-red-500 hover:to-pink-500 text-red-500 hover:text-white" title="Pass" {
+                    button class="shadow-md rounded-full h-12 w-12 md:h-14 md:w-14 border border-red-500 hover:border-0 hover:bg-gradient-to-t hover:from-red-500 hover:to-pink-500 text-red-500 hover:text-white" title=(i18n::t(locale, Text::Pass)) {
                         p class="mx-auto h-6 w-6 md:h-8 md:w-8 flex items-center justify-center" {
                             (icon::x())
                         }
@@ -240,10 +323,10 @@ fn pass(current_vtuber: &VTuber, current_vote: &Option<UserAction>) -> Markup {
     }
 }
 
-fn next_vtuber(stack: &Stack) -> Markup {
-    let next_button = |vtuber_id: i64| {
+fn next_vtuber(stack: &Stack, base_path: &str, filter: &DeckFilter) -> Markup {
+    let next_button = |slug: &str| {
         html! {
-            a href=(format!("/{}", vtuber_id)) class="flex items-center justify-center shadow-md rounded-full h-12 w-12 md:h-14 md:w-14 bg-su-bg-2 dark:bg-su-dark-bg-2" {
+            a href=(urls::path(base_path, &filter.link(&format!("/{}", slug)))) class="flex items-center justify-center shadow-md rounded-full h-12 w-12 md:h-14 md:w-14 bg-su-bg-2 dark:bg-su-dark-bg-2" {
                 p class="h-6 w-6 md:h-8 md:w-8 text-white flex items-center justify-center" {
                     (icon::chevron_up())
                 }
@@ -262,18 +345,18 @@ fn next_vtuber(stack: &Stack) -> Markup {
     };
 
     match stack.get_current() {
-        Some(current) => match current.next {
-            Some(id) => next_button(id),
+        Some(current) => match &current.next_slug {
+            Some(slug) => next_button(slug),
             None => next_disabled_button,
         },
         None => next_disabled_button,
     }
 }
 
-fn prev_vtuber(stack: &Stack) -> Markup {
-    let prev_button = |vtuber_id: i64| {
+fn prev_vtuber(stack: &Stack, base_path: &str, filter: &DeckFilter) -> Markup {
+    let prev_button = |slug: &str| {
         html! {
-            a href=(format!("/{}", vtuber_id)) class="flex items-center justify-center shadow-md rounded-full h-12 w-12 md:h-14 md:w-14 bg-su-bg-2 dark:bg-su-dark-bg-2" {
+            a href=(urls::path(base_path, &filter.link(&format!("/{}", slug)))) class="flex items-center justify-center shadow-md rounded-full h-12 w-12 md:h-14 md:w-14 bg-su-bg-2 dark:bg-su-dark-bg-2" {
                 p class="h-6 w-6 md:h-8 md:w-8 text-white flex items-center justify-center" {
                     (icon::chevron_down())
                 }
@@ -292,12 +375,12 @@ fn prev_vtuber(stack: &Stack) -> Markup {
     };
 
     match stack.get_current() {
-        Some(current) => match current.prev {
-            Some(id) => prev_button(id),
+        Some(current) => match &current.prev_slug {
+            Some(slug) => prev_button(slug),
             None => prev_disabled_button,
         },
         None => match stack.get_last_voted_stat() {
-            Some(Stat { vtuber_id, .. }) => prev_button(*vtuber_id),
+            Some(Stat { slug, .. }) => prev_button(slug),
             None => prev_disabled_button,
         },
     }
@@ -1,13 +1,23 @@
 use axum::response::{IntoResponse, Response};
 
-use super::views;
-use crate::oshismash::vtubers::Stack;
+use super::{
+    i18n::{self, Text},
+    views,
+};
+use crate::oshismash::{deck::DeckFilter, locale::Locale, vtubers::Stack};
 
 impl IntoResponse for Stack {
+    /// Renders the stack in the default locale, without a base path. Handlers
+    /// that know the client's locale should render the views themselves.
     fn into_response(self) -> Response {
+        let locale = Locale::default();
+
         views::root::render(
-            "Oshi Smash: Smash or Pass Your Oshis!",
-            views::vote::render(self),
+            locale,
+            "",
+            &views::root::Meta::new(i18n::t(locale, Text::PageTitle), locale),
+            None,
+            views::vote::render(self, locale, "", &DeckFilter::default()),
         )
         .into_response()
     }